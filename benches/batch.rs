@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Benchmarks for executing large plans.
+//!
+//! The files are created on tmpfs (`/dev/shm`) if it is available, so that
+//! the results are dominated by the system calls rather than the storage. With
+//! the `io-uring` feature on Linux, the plans with more than one job are
+//! executed with `io_uring`.
+
+use std::{fs::File, num::NonZeroUsize, path::Path};
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use hf::{Companions, Plan, batch::Batch};
+use tempfile::TempDir;
+
+const DIRS: usize = 10;
+const FILES: usize = 1000;
+
+fn setup() -> (TempDir, Plan) {
+    let temp_dir = tempfile::tempdir_in("/dev/shm")
+        .or_else(|_| tempfile::tempdir())
+        .unwrap();
+    let companions = Companions::new();
+    let mut plan = Plan::new();
+    for i in 0..DIRS {
+        let dir = temp_dir.path().join(format!("dir{i}"));
+        std::fs::create_dir(&dir).unwrap();
+        for j in 0..FILES {
+            let path = dir.join(format!("{j}.txt"));
+            File::create(&path).unwrap();
+            plan.hide(path, &companions).unwrap();
+        }
+    }
+    (temp_dir, plan)
+}
+
+fn execute(c: &mut Criterion) {
+    let mut group = c.benchmark_group("execute");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements((DIRS * FILES) as u64));
+    for jobs in [1, 4, 16, 64] {
+        let batch = Batch::new(NonZeroUsize::new(jobs).unwrap());
+        group.bench_with_input(BenchmarkId::from_parameter(jobs), &batch, |b, batch| {
+            b.iter_batched(
+                setup,
+                |(temp_dir, plan)| {
+                    batch.execute(&plan).unwrap();
+                    assert!(Path::new(temp_dir.path()).join("dir0/.0.txt").exists());
+                    temp_dir
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, execute);
+criterion_main!(benches);
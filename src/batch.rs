@@ -0,0 +1,645 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Running operations on many files concurrently.
+//!
+//! On network file systems, hiding and showing many files is dominated by the
+//! round trips of each file operation. A [`Batch`] hides this latency by
+//! running the operations on multiple threads.
+//!
+//! The results are always returned in the order of the input, so the output
+//! doesn't depend on the order in which the threads finish.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(unix)]
+//! # {
+//! use std::{fs, num::NonZeroUsize};
+//!
+//! use hf::{Companions, Plan, batch::Batch};
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! let temp_dir = temp_dir.path();
+//! let mut files = Vec::new();
+//! for dir in ["foo", "bar"] {
+//!     fs::create_dir(temp_dir.join(dir)).unwrap();
+//!     for name in ["a.txt", "b.txt"] {
+//!         let path = temp_dir.join(dir).join(name);
+//!         fs::write(&path, "").unwrap();
+//!         files.push(path);
+//!     }
+//! }
+//!
+//! let batch = Batch::new(NonZeroUsize::new(4).unwrap());
+//! let companions = Companions::default();
+//! let plans = batch.map(&files, |f| {
+//!     let mut plan = Plan::new();
+//!     plan.hide(f, &companions).map(|()| plan)
+//! });
+//! let mut plan = Plan::new();
+//! for p in plans {
+//!     plan.append(p.unwrap()).unwrap();
+//! }
+//!
+//! batch.execute(&plan).unwrap();
+//! assert!(temp_dir.join("foo/.a.txt").exists());
+//! assert!(temp_dir.join("bar/.b.txt").exists());
+//! # }
+//! ```
+
+use std::{
+    collections::HashMap,
+    io,
+    num::NonZeroUsize,
+    panic,
+    path::Path,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use crate::{Plan, Step};
+
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring;
+
+/// An executor which runs operations on multiple threads.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Batch {
+    jobs: NonZeroUsize,
+}
+
+impl Batch {
+    /// Creates a new `Batch` which runs at most `jobs` operations at a time.
+    ///
+    /// If `jobs` is `1`, the operations are run on the current thread.
+    #[must_use]
+    #[inline]
+    pub const fn new(jobs: NonZeroUsize) -> Self {
+        Self { jobs }
+    }
+
+    /// Returns the maximum number of operations which run at a time.
+    #[must_use]
+    #[inline]
+    pub const fn jobs(&self) -> NonZeroUsize {
+        self.jobs
+    }
+
+    /// Calls `f` with each item of `items` concurrently, and returns the
+    /// results in the order of `items`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` panics, or a thread could not be spawned.
+    #[inline]
+    pub fn map<T, R, F>(&self, items: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let mut results = self.run(items.len(), |i| f(&items[i]));
+        results.sort_unstable_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, r)| r).collect()
+    }
+
+    /// Calls `f` with each item of `items` concurrently like [`Batch::map`],
+    /// and calls `progress` whenever an item has been processed.
+    ///
+    /// An item is counted as failed if `f` returns [`Err`]. `progress` is
+    /// called once before any item is processed, and is never called
+    /// concurrently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` or `progress` panics, or a thread could not be spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use hf::batch::Batch;
+    ///
+    /// let batch = Batch::new(NonZeroUsize::new(4).unwrap());
+    /// let mut last = None;
+    /// let results = batch.try_map_with_progress(
+    ///     &[1, 2, 0, 4],
+    ///     |&n| if n > 0 { Ok(n) } else { Err(n) },
+    ///     |p| last = Some(p),
+    /// );
+    /// assert_eq!(results, [Ok(1), Ok(2), Err(0), Ok(4)]);
+    ///
+    /// let last = last.unwrap();
+    /// assert_eq!(last.total(), 4);
+    /// assert_eq!(last.processed(), 4);
+    /// assert_eq!(last.failed(), 1);
+    /// ```
+    #[inline]
+    pub fn try_map_with_progress<T, R, E, F, P>(
+        &self,
+        items: &[T],
+        f: F,
+        progress: P,
+    ) -> Vec<Result<R, E>>
+    where
+        T: Sync,
+        R: Send,
+        E: Send,
+        F: Fn(&T) -> Result<R, E> + Sync,
+        P: FnMut(Progress) + Send,
+    {
+        let tracker = Tracker::new(items.len(), progress);
+        self.map(items, |item| {
+            let result = f(item);
+            tracker.report(result.is_err());
+            result
+        })
+    }
+
+    /// Executes `plan` concurrently.
+    ///
+    /// The steps in the same directory, or in a directory which is renamed by
+    /// another step, are executed in the order of the plan, because they may
+    /// depend on each other. The other steps may be executed in any order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any step fails. In that case, no more steps are
+    /// started, and the steps which have already been executed are reverted as
+    /// far as possible. If more than one step fails, the error of the earliest
+    /// step in the plan is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a thread could not be spawned.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Linux with the `io-uring` feature, if `jobs` is greater than `1`, the
+    /// steps are submitted to an `io_uring` instead of being executed on
+    /// multiple threads, with at most `jobs` operations in flight. In that
+    /// case, the steps are renamed with `RENAME_NOREPLACE`, so that no file
+    /// which has been created since the plan was made is overwritten. If
+    /// `io_uring` or `RENAME_NOREPLACE` is not available, this falls back to
+    /// the threads.
+    #[inline]
+    pub fn execute(&self, plan: &Plan) -> io::Result<()> {
+        self.execute_with_progress(plan, |_| {})
+    }
+
+    /// Executes `plan` concurrently like [`Batch::execute`], and calls
+    /// `progress` whenever a step has been executed.
+    ///
+    /// `progress` is called once before any step is executed, and is never
+    /// called concurrently. The steps which are reverted are not reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any step fails. See [`Batch::execute`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `progress` panics, or a thread could not be spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::{fs::File, num::NonZeroUsize};
+    ///
+    /// use hf::{Companions, Plan, batch::Batch};
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// let companions = Companions::default();
+    /// let mut plan = Plan::new();
+    /// for name in ["foo.txt", "bar.txt", "baz.txt"] {
+    ///     File::create(temp_dir.path().join(name)).unwrap();
+    ///     plan.hide(temp_dir.path().join(name), &companions).unwrap();
+    /// }
+    ///
+    /// let batch = Batch::new(NonZeroUsize::new(2).unwrap());
+    /// let mut processed = Vec::new();
+    /// batch
+    ///     .execute_with_progress(&plan, |p| processed.push(p.processed()))
+    ///     .unwrap();
+    /// assert_eq!(processed, [0, 1, 2, 3]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn execute_with_progress<P>(&self, plan: &Plan, progress: P) -> io::Result<()>
+    where
+        P: FnMut(Progress) + Send,
+    {
+        let steps = plan.steps();
+        let tracker = Tracker::new(steps.len(), progress);
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
+        if self.jobs.get() > 1 {
+            if let Some(result) = uring::execute(steps, self.jobs, &|failed| tracker.report(failed))
+            {
+                return result;
+            }
+        }
+        let groups = groups(steps);
+        let failed = AtomicBool::new(false);
+        let mut outcomes = self.run(groups.len(), |i| {
+            let mut done = 0;
+            for &step in &groups[i] {
+                if failed.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Err(err) = steps[step].apply() {
+                    failed.store(true, Ordering::Relaxed);
+                    tracker.report(true);
+                    return (done, Some((step, err)));
+                }
+                tracker.report(false);
+                done += 1;
+            }
+            (done, None)
+        });
+        if !failed.into_inner() {
+            return Ok(());
+        }
+
+        outcomes.sort_unstable_by_key(|(i, _)| *i);
+        let mut error = None;
+        for (i, (done, err)) in outcomes.into_iter().rev() {
+            for &step in groups[i][..done].iter().rev() {
+                let _ = steps[step].revert();
+            }
+            if let Some((step, err)) = err {
+                match &error {
+                    Some((first, _)) if *first < step => {}
+                    _ => error = Some((step, err)),
+                }
+            }
+        }
+        Err(error.map_or_else(|| io::Error::other("a step failed"), |(_, err)| err))
+    }
+
+    /// Calls `f` with each index in `0..len` on at most `jobs` threads, and
+    /// returns the indices and the results in any order.
+    fn run<R, F>(self, len: usize, f: F) -> Vec<(usize, R)>
+    where
+        R: Send,
+        F: Fn(usize) -> R + Sync,
+    {
+        let jobs = self.jobs.get().min(len);
+        if jobs <= 1 {
+            return (0..len).map(|i| (i, f(i))).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        thread::scope(|s| {
+            let workers = (0..jobs)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut results = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            if i >= len {
+                                break results;
+                            }
+                            results.push((i, f(i)));
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap_or_else(|err| panic::resume_unwind(err)))
+                .collect()
+        })
+    }
+}
+
+impl Default for Batch {
+    /// Creates a new `Batch` which runs as many operations at a time as the
+    /// available parallelism.
+    #[inline]
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().unwrap_or(NonZeroUsize::MIN))
+    }
+}
+
+/// The progress of a [`Batch`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Progress {
+    total: usize,
+    processed: usize,
+    failed: usize,
+}
+
+impl Progress {
+    /// Returns the total number of items or steps.
+    #[must_use]
+    #[inline]
+    pub const fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of items or steps which have been processed,
+    /// including the failed ones.
+    #[must_use]
+    #[inline]
+    pub const fn processed(&self) -> usize {
+        self.processed
+    }
+
+    /// Returns the number of items or steps which have failed.
+    #[must_use]
+    #[inline]
+    pub const fn failed(&self) -> usize {
+        self.failed
+    }
+}
+
+/// Reports the progress to a callback from multiple threads.
+struct Tracker<P> {
+    state: Mutex<(Progress, P)>,
+}
+
+impl<P: FnMut(Progress)> Tracker<P> {
+    /// Creates a new `Tracker` for `total` items, and reports the initial
+    /// progress.
+    fn new(total: usize, mut progress: P) -> Self {
+        let initial = Progress {
+            total,
+            ..Default::default()
+        };
+        progress(initial);
+        Self {
+            state: Mutex::new((initial, progress)),
+        }
+    }
+
+    /// Reports that an item has been processed.
+    fn report(&self, failed: bool) {
+        let (progress, callback) = &mut *self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        progress.processed += 1;
+        progress.failed += usize::from(failed);
+        callback(*progress);
+    }
+}
+
+/// Splits `steps` into groups which must be executed in order.
+///
+/// The indices of the steps in each group are in the order of `steps`, and the
+/// groups are in the order of their first steps.
+fn groups(steps: &[Step]) -> Vec<Vec<usize>> {
+    let mut parents = (0..steps.len()).collect::<Vec<_>>();
+
+    // Steps in the same directory.
+    let mut dirs = HashMap::<&Path, usize>::new();
+    for (i, step) in steps.iter().enumerate() {
+        for dir in [step.path().parent(), step.dest().parent()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(&j) = dirs.get(dir) {
+                union(&mut parents, i, j);
+            } else {
+                dirs.insert(dir, i);
+            }
+        }
+    }
+
+    // Steps under a directory which is renamed by another step.
+    let mut paths = HashMap::<&Path, usize>::new();
+    for (i, step) in steps.iter().enumerate() {
+        paths.insert(step.path(), i);
+        paths.insert(step.dest(), i);
+    }
+    for (i, step) in steps.iter().enumerate() {
+        for ancestor in [step.path(), step.dest()]
+            .into_iter()
+            .flat_map(|p| p.ancestors().skip(1))
+        {
+            if let Some(&j) = paths.get(ancestor) {
+                union(&mut parents, i, j);
+            }
+        }
+    }
+
+    let mut groups = Vec::<Vec<usize>>::new();
+    let mut indices = HashMap::new();
+    for i in 0..steps.len() {
+        let root = find(&mut parents, i);
+        let index = *indices.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(i);
+    }
+    groups
+}
+
+/// Merges the groups which contain `i` and `j`.
+fn union(parents: &mut [usize], i: usize, j: usize) {
+    let (i, j) = (find(parents, i), find(parents, j));
+    parents[i.max(j)] = i.min(j);
+}
+
+/// Returns the representative of the group which contains `i`.
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use super::*;
+    use crate::Companions;
+
+    const JOBS: NonZeroUsize = NonZeroUsize::new(4).unwrap();
+
+    #[test]
+    fn map() {
+        let items = (0..100).collect::<Vec<_>>();
+        assert_eq!(
+            Batch::new(JOBS).map(&items, |i| i * 2),
+            (0..100).map(|i| i * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Batch::new(NonZeroUsize::MIN).map(&items, |i| i * 2),
+            (0..100).map(|i| i * 2).collect::<Vec<_>>()
+        );
+        assert!(Batch::new(JOBS).map(&[] as &[i32], |i| *i).is_empty());
+    }
+
+    #[test]
+    fn try_map_with_progress() {
+        let items = (0..100).collect::<Vec<_>>();
+        let mut reports = Vec::new();
+        let results = Batch::new(JOBS).try_map_with_progress(
+            &items,
+            |&i| if i % 10 == 0 { Err(i) } else { Ok(i) },
+            |p| reports.push(p),
+        );
+        assert_eq!(
+            results,
+            (0..100)
+                .map(|i| if i % 10 == 0 { Err(i) } else { Ok(i) })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(reports.len(), 101);
+        assert!(reports.iter().all(|p| p.total() == 100));
+        assert!(reports.iter().enumerate().all(|(i, p)| p.processed() == i));
+        assert_eq!(reports.last().unwrap().failed(), 10);
+    }
+
+    #[test]
+    fn default_batch() {
+        assert!(Batch::default().jobs().get() >= 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn groups_of_steps() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for dir in ["foo", "bar", "foo/baz"] {
+            fs::create_dir(temp_dir.join(dir)).unwrap();
+        }
+        for file in ["foo/a.txt", "bar/b.txt", "foo/baz/c.txt", "bar/d.txt"] {
+            File::create(temp_dir.join(file)).unwrap();
+        }
+        let companions = Companions::new();
+
+        let mut plan = Plan::new();
+        for path in [
+            "foo/a.txt",
+            "bar/b.txt",
+            "foo/baz/c.txt",
+            "foo/baz",
+            "bar/d.txt",
+        ] {
+            plan.hide(temp_dir.join(path), &companions).unwrap();
+        }
+        assert_eq!(groups(plan.steps()), [vec![0, 2, 3], vec![1, 4]]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for i in 0..10 {
+            let dir = temp_dir.join(format!("dir{i}"));
+            fs::create_dir(&dir).unwrap();
+            for j in 0..10 {
+                File::create(dir.join(format!("{j}.txt"))).unwrap();
+                plan.hide(dir.join(format!("{j}.txt")), &companions)
+                    .unwrap();
+            }
+            plan.hide(&dir, &companions).unwrap();
+        }
+
+        Batch::new(JOBS).execute(&plan).unwrap();
+        for i in 0..10 {
+            for j in 0..10 {
+                assert!(temp_dir.join(format!(".dir{i}/.{j}.txt")).exists());
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_with_progress() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for i in 0..10 {
+            let dir = temp_dir.join(format!("dir{i}"));
+            fs::create_dir(&dir).unwrap();
+            for j in 0..10 {
+                File::create(dir.join(format!("{j}.txt"))).unwrap();
+                plan.hide(dir.join(format!("{j}.txt")), &companions)
+                    .unwrap();
+            }
+        }
+
+        let mut reports = Vec::new();
+        Batch::new(JOBS)
+            .execute_with_progress(&plan, |p| reports.push(p))
+            .unwrap();
+        assert_eq!(reports.len(), 101);
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                total: 100,
+                processed: 100,
+                failed: 0
+            })
+        );
+
+        let mut plan = Plan::new();
+        for j in 0..10 {
+            plan.show(temp_dir.join(format!("dir0/.{j}.txt")), &companions)
+                .unwrap();
+        }
+        plan.show(temp_dir.join("dir3/.4.txt"), &companions)
+            .unwrap();
+        fs::remove_file(temp_dir.join("dir3/.4.txt")).unwrap();
+        let mut last = None;
+        Batch::new(NonZeroUsize::MIN)
+            .execute_with_progress(&plan, |p| last = Some(p))
+            .unwrap_err();
+        assert_eq!(
+            last,
+            Some(Progress {
+                total: 11,
+                processed: 11,
+                failed: 1
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_rolls_back_on_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for i in 0..10 {
+            let dir = temp_dir.join(format!("dir{i}"));
+            fs::create_dir(&dir).unwrap();
+            for j in 0..10 {
+                File::create(dir.join(format!("{j}.txt"))).unwrap();
+                plan.hide(dir.join(format!("{j}.txt")), &companions)
+                    .unwrap();
+            }
+        }
+        fs::remove_file(temp_dir.join("dir5/5.txt")).unwrap();
+
+        assert_eq!(
+            Batch::new(JOBS).execute(&plan).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        for i in 0..10 {
+            for j in 0..10 {
+                let dir = temp_dir.join(format!("dir{i}"));
+                assert!(!dir.join(format!(".{j}.txt")).exists());
+                if (i, j) != (5, 5) {
+                    assert!(dir.join(format!("{j}.txt")).exists());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,406 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Executing plans with `io_uring` on Linux.
+//!
+//! The steps are split into levels so that each step comes after the steps it
+//! depends on. The steps of a level are submitted to a ring at once as
+//! `renameat2` with `RENAME_NOREPLACE`, so that no file which has appeared
+//! since the plan was made is overwritten.
+
+use std::{
+    collections::HashMap,
+    ffi::CString,
+    io::{self, Error, ErrorKind},
+    mem,
+    num::NonZeroUsize,
+    os::{
+        fd::{AsFd, AsRawFd, RawFd},
+        unix::ffi::OsStrExt,
+    },
+    path::Path,
+};
+
+use io_uring::{IoUring, opcode, register::Probe, squeue, types};
+use rustix::{fs::RenameFlags, io::Errno};
+
+use crate::{Dir, Step, platform::imp};
+
+/// The maximum number of operations in flight.
+const MAX_DEPTH: usize = 4096;
+
+/// Executes `steps` with at most `depth` operations in flight, and calls
+/// `report` with whether each step has failed.
+///
+/// Returns [`None`] without doing anything if `io_uring` is not available, for
+/// example because the kernel is too old or it is disabled by a seccomp
+/// filter.
+pub(super) fn execute(
+    steps: &[Step],
+    depth: NonZeroUsize,
+    report: &dyn Fn(bool),
+) -> Option<io::Result<()>> {
+    Ring::new(depth).map(|mut ring| ring.execute(steps, report))
+}
+
+/// A ring which runs the operations of the steps.
+struct Ring {
+    ring: IoUring,
+    depth: usize,
+}
+
+impl Ring {
+    /// Sets up a ring, or returns [`None`] if `io_uring` or the operations are
+    /// not available.
+    fn new(depth: NonZeroUsize) -> Option<Self> {
+        let depth = depth.get().min(MAX_DEPTH);
+        let ring = IoUring::new(u32::try_from(depth).ok()?).ok()?;
+        let mut probe = Probe::new();
+        ring.submitter().register_probe(&mut probe).ok()?;
+        probe
+            .is_supported(opcode::RenameAt::CODE)
+            .then_some(Self { ring, depth })
+    }
+
+    /// Executes `steps`, and reverts the executed steps if any step fails.
+    fn execute(&mut self, steps: &[Step], report: &dyn Fn(bool)) -> io::Result<()> {
+        let mut done = Vec::new();
+        for level in levels(steps) {
+            if let Err(err) = self.run_level(steps, &level, &mut done, report) {
+                for &i in done.iter().rev() {
+                    let _ = steps[i].revert();
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes the steps at the indices `level`, which don't depend on each
+    /// other, and adds the indices of the executed steps to `done`.
+    ///
+    /// If the file system does not support `RENAME_NOREPLACE`, the steps are
+    /// executed again with [`Step::apply`] as on the threads. If more than one
+    /// step fails, the error of the earliest step is returned.
+    fn run_level(
+        &mut self,
+        steps: &[Step],
+        level: &[usize],
+        done: &mut Vec<usize>,
+        report: &dyn Fn(bool),
+    ) -> io::Result<()> {
+        let mut dirs = HashMap::<&Path, Dir>::new();
+        let mut ops = Vec::with_capacity(level.len());
+        for &i in level {
+            let step = &steps[i];
+            let (parent, name) = imp::split_path(step.path())?;
+            let dest = step
+                .dest()
+                .file_name()
+                .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            if !dirs.contains_key(parent) {
+                dirs.insert(parent, Dir::open_path(parent)?);
+            }
+            ops.push(Rename {
+                dir: dirs[parent].as_fd().as_raw_fd(),
+                name: CString::new(name.as_bytes())?,
+                dest: CString::new(dest.as_bytes())?,
+            });
+        }
+
+        let entry = |j: usize| {
+            opcode::RenameAt::new(
+                types::Fd(ops[j].dir),
+                ops[j].name.as_ptr(),
+                types::Fd(ops[j].dir),
+                ops[j].dest.as_ptr(),
+            )
+            .flags(RenameFlags::NOREPLACE.bits())
+            .build()
+        };
+        // The steps which are executed again are reported afterwards.
+        let report_done = |result: &io::Result<()>| {
+            if !is_unsupported(result) {
+                report(result.is_err());
+            }
+        };
+        let results = match self.submit_all(ops.len(), entry, &report_done) {
+            Ok(results) => results,
+            Err(err) => return Err(err.leak((dirs, ops))),
+        };
+        let mut error = None;
+        for (j, result) in results.into_iter().enumerate() {
+            let step = &steps[level[j]];
+            let result = match result {
+                result if is_unsupported(&result) => {
+                    let result = step.apply();
+                    report(result.is_err());
+                    result
+                }
+                Err(err) if err.raw_os_error() == Some(Errno::EXIST.raw_os_error()) => {
+                    Err(Error::new(
+                        ErrorKind::AlreadyExists,
+                        format!("{} already exists", step.dest().display()),
+                    ))
+                }
+                result => result,
+            };
+            match result {
+                Ok(()) => done.push(level[j]),
+                Err(err) if error.is_none() => error = Some(err),
+                Err(_) => {}
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Submits the operations built by `entry` for each index in `0..len`, and
+    /// returns their results in the order of the indices.
+    ///
+    /// `report` is called with the result of each operation as soon as it
+    /// completes.
+    ///
+    /// Returns [`InFlight`] if the ring fails.
+    fn submit_all<F>(
+        &mut self,
+        len: usize,
+        entry: F,
+        report: &dyn Fn(&io::Result<()>),
+    ) -> Result<Vec<io::Result<()>>, InFlight>
+    where
+        F: Fn(usize) -> squeue::Entry,
+    {
+        let mut results = (0..len).map(|_| Ok(())).collect::<Vec<_>>();
+        let (mut next, mut in_flight) = (0, 0);
+        while next < len || in_flight > 0 {
+            while next < len && in_flight < self.depth {
+                let entry = entry(next).user_data(next as u64);
+                // SAFETY: The buffers which the entry points to outlive the
+                // operation, because this waits for all operations to complete,
+                // and the caller leaks the buffers if this fails.
+                if unsafe { self.ring.submission().push(&entry) }.is_err() {
+                    break;
+                }
+                next += 1;
+                in_flight += 1;
+            }
+            match self.ring.submit_and_wait(1) {
+                Ok(_) => {}
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(InFlight { err, in_flight }),
+            }
+            for cqe in self.ring.completion() {
+                let i = usize::try_from(cqe.user_data()).unwrap_or(usize::MAX);
+                if let Some(result) = results.get_mut(i) {
+                    if cqe.result() < 0 {
+                        *result = Err(Error::from_raw_os_error(-cqe.result()));
+                    }
+                    report(result);
+                }
+                in_flight -= 1;
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Returns [`true`] if `result` is the error which `renameat2` returns when the
+/// file system does not support `RENAME_NOREPLACE`.
+fn is_unsupported(result: &io::Result<()>) -> bool {
+    result
+        .as_ref()
+        .is_err_and(|err| err.raw_os_error() == Some(Errno::INVAL.raw_os_error()))
+}
+
+/// The operands of a `renameat` within a directory.
+struct Rename {
+    dir: RawFd,
+    name: CString,
+    dest: CString,
+}
+
+/// An error of a ring while some operations may still be in flight.
+struct InFlight {
+    err: io::Error,
+    in_flight: usize,
+}
+
+impl InFlight {
+    /// Returns the error, leaking `buffers` if any operation which refers to
+    /// them is still in flight, because the kernel may still write to them.
+    fn leak<B>(self, buffers: B) -> io::Error {
+        if self.in_flight > 0 {
+            mem::forget(buffers);
+        }
+        self.err
+    }
+}
+
+/// Splits `steps` into levels, so that each step depends only on the steps in
+/// the earlier levels.
+///
+/// A step depends on an earlier step if the paths of the steps are the same,
+/// or one of them is under the other.
+fn levels(steps: &[Step]) -> Vec<Vec<usize>> {
+    // The level of the last step which renames from or to each path.
+    let mut at = HashMap::<&Path, usize>::new();
+    // The level of the last step which renames from or to each path, or a path
+    // under it.
+    let mut under = HashMap::<&Path, usize>::new();
+
+    let mut levels = Vec::<Vec<usize>>::new();
+    for (i, step) in steps.iter().enumerate() {
+        let paths = [step.path(), step.dest()];
+        let level = paths
+            .iter()
+            .filter_map(|p| {
+                p.ancestors()
+                    .skip(1)
+                    .filter_map(|a| at.get(a))
+                    .chain(under.get(p))
+                    .max()
+            })
+            .max()
+            .map_or(0, |l| l + 1);
+        for p in paths {
+            at.insert(p, level);
+            for a in p.ancestors() {
+                let l = under.entry(a).or_insert(level);
+                *l = (*l).max(level);
+            }
+        }
+        if levels.len() <= level {
+            levels.resize_with(level + 1, Vec::new);
+        }
+        levels[level].push(i);
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use super::*;
+    use crate::{Companions, Plan};
+
+    const DEPTH: NonZeroUsize = NonZeroUsize::new(64).unwrap();
+
+    /// Sets up a ring, which must be available to run the tests.
+    fn ring() -> Ring {
+        Ring::new(DEPTH).expect("`io_uring` should be available")
+    }
+
+    /// Creates a temporary directory on tmpfs if possible.
+    fn temp_dir() -> tempfile::TempDir {
+        tempfile::tempdir_in("/dev/shm")
+            .or_else(|_| tempfile::tempdir())
+            .unwrap()
+    }
+
+    #[test]
+    fn levels_of_steps() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        for dir in ["foo", "bar"] {
+            fs::create_dir(temp_dir.join(dir)).unwrap();
+        }
+        for file in ["foo/a.txt", "bar/b.txt", "bar/c.txt", "foo/d.txt"] {
+            File::create(temp_dir.join(file)).unwrap();
+        }
+        let companions = Companions::new();
+
+        let mut plan = Plan::new();
+        for path in ["foo/a.txt", "bar/b.txt", "foo", "bar/c.txt"] {
+            plan.hide(temp_dir.join(path), &companions).unwrap();
+        }
+        plan.hide(temp_dir.join(".foo/d.txt"), &companions)
+            .unwrap_err();
+        assert_eq!(levels(plan.steps()), [vec![0, 1, 3], vec![2]]);
+        assert!(levels(&[]).is_empty());
+    }
+
+    #[test]
+    #[ignore = "`io_uring` may be disabled, for example by a seccomp filter"]
+    fn execute() {
+        let mut ring = ring();
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for i in 0..10 {
+            let dir = temp_dir.join(format!("dir{i}"));
+            fs::create_dir(&dir).unwrap();
+            for j in 0..100 {
+                File::create(dir.join(format!("{j}.txt"))).unwrap();
+                plan.hide(dir.join(format!("{j}.txt")), &companions)
+                    .unwrap();
+            }
+            plan.hide(&dir, &companions).unwrap();
+        }
+
+        ring.execute(plan.steps(), &|_| {}).unwrap();
+        for i in 0..10 {
+            assert!(!temp_dir.join(format!("dir{i}")).exists());
+            for j in 0..100 {
+                assert!(temp_dir.join(format!(".dir{i}/.{j}.txt")).exists());
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "`io_uring` may be disabled, for example by a seccomp filter"]
+    fn execute_rolls_back_on_failure() {
+        let mut ring = ring();
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for i in 0..100 {
+            File::create(temp_dir.join(format!("{i}.txt"))).unwrap();
+            plan.hide(temp_dir.join(format!("{i}.txt")), &companions)
+                .unwrap();
+        }
+        fs::remove_file(temp_dir.join("50.txt")).unwrap();
+
+        assert_eq!(
+            ring.execute(plan.steps(), &|_| {}).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        for i in 0..100 {
+            assert!(!temp_dir.join(format!(".{i}.txt")).exists());
+            if i != 50 {
+                assert!(temp_dir.join(format!("{i}.txt")).exists());
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "`io_uring` may be disabled, for example by a seccomp filter"]
+    fn execute_when_destination_appears() {
+        let mut ring = ring();
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for name in ["foo.txt", "bar.txt"] {
+            File::create(temp_dir.join(name)).unwrap();
+            plan.hide(temp_dir.join(name), &companions).unwrap();
+        }
+        fs::write(temp_dir.join(".bar.txt"), "bar").unwrap();
+
+        let err = ring.execute(plan.steps(), &|_| {}).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(
+            err.to_string(),
+            format!("{} already exists", temp_dir.join(".bar.txt").display())
+        );
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(temp_dir.join("bar.txt").exists());
+        assert_eq!(
+            fs::read_to_string(temp_dir.join(".bar.txt")).unwrap(),
+            "bar"
+        );
+    }
+}
@@ -2,127 +2,851 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use anyhow::Context;
-use clap::{CommandFactory, Parser, error::ErrorKind};
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use anyhow::{Context, bail};
+use clap::{CommandFactory, FromArgMatches, error::ErrorKind};
+use hf::{
+    Companions, HiddenReason, NamingScheme, Options, Plan, Step,
+    batch::Batch,
+    git::{Status, WorkTree},
+    manifest::{Drift, Manifest, State},
+    migrate::Migration,
+};
 use log::{info, warn};
 use simplelog::{ColorChoice, Config, SimpleLogger, TermLogger, TerminalMode};
 
-use crate::cli::{Command, Opt};
+use crate::{
+    cli::{
+        Audit, Clean, Command, ConfigCommand, Exec, Explain, Hide, Migrate, Opt, Show, Sync, Tui,
+        Watch,
+    },
+    config::Settings,
+    exec::{self, SignalGuard},
+    progress::Reporter,
+    prompt::Prompt,
+    tui::Browser,
+    watch::Enforcer,
+};
 
 /// Runs the program and returns the result.
 #[allow(clippy::too_many_lines)]
-pub fn run() -> anyhow::Result<()> {
-    let opt = Opt::parse();
+pub fn run() -> anyhow::Result<ExitCode> {
+    let matches = Opt::command().get_matches();
+    let mut opt = Opt::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
 
     if let Some(shell) = opt.generate_completion {
         Opt::print_completion(shell);
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
+    // An invalid configuration must not prevent `hf config show` from showing
+    // where the values come from, so it is reported as a warning. This is
+    // written to standard error, because the logger has not been set up yet,
+    // and standard output may be the output of the command.
+    let (mut settings, errors) = Settings::load();
+    for err in errors {
+        eprintln!("Warning: {err:?}");
+    }
+    settings.apply(&mut opt, &matches);
+
+    // The archive or the output of the command is written to standard output,
+    // so log messages must not be mixed with it.
+    let terminal_mode = match opt.command {
+        Some(
+            Command::Hide(Hide { tar: true, .. })
+            | Command::Show(Show { tar: true, .. })
+            | Command::Exec(_),
+        ) => TerminalMode::Stderr,
+        _ => TerminalMode::Mixed,
+    };
     let log_level = opt.log_level.into();
     TermLogger::init(
         log_level,
         Config::default(),
-        TerminalMode::Mixed,
+        terminal_mode,
         ColorChoice::Auto,
     )
     .or_else(|_| SimpleLogger::init(log_level, Config::default()))?;
 
     if let Some(command) = opt.command {
         match command {
+            Command::Hide(arg) if arg.tar => rewrite_archive(&arg.input, arg.dry_run, true)?,
+            Command::Show(arg) if arg.tar => rewrite_archive(&arg.input, arg.dry_run, false)?,
             Command::Hide(arg) => {
-                let files = arg
-                    .input
-                    .into_iter()
-                    .map(|f| {
-                        #[cfg(unix)]
-                        std::fs::metadata(&f)
-                            .with_context(|| format!("{} does not exist", f.display()))?;
-                        let is_hidden = hf::is_hidden(&f).with_context(|| {
-                            format!("could not read information from {}", f.display())
-                        });
-                        match is_hidden {
-                            Ok(false) => Ok((f, true)),
-                            Ok(true) => Ok((f, false)),
-                            Err(err) => Err(err),
-                        }
-                    })
-                    .collect::<anyhow::Result<Vec<_>>>()?;
-
-                match (arg.dry_run, arg.force) {
-                    (true, _) => {
-                        for file in files {
-                            if file.1 {
-                                println!("{}", file.0.display());
-                            } else {
-                                warn!("{} is ignored", file.0.display());
-                            }
-                        }
-                    }
-                    (_, true) => {
-                        for file in files {
-                            if file.1 {
-                                hf::hide(&file.0).with_context(|| {
-                                    format!("could not hide {}", file.0.display())
-                                })?;
-                                info!("{} has been hidden", file.0.display());
-                            } else {
-                                warn!("{} is already hidden", file.0.display());
-                            }
-                        }
-                    }
-                    _ => unreachable!(),
-                }
+                let operation = Operation {
+                    options: arg.options(),
+                    progress: arg.progress(),
+                    companions: arg.companions(),
+                    scheme: arg.naming_scheme(),
+                    dereference: arg.dereference,
+                    #[cfg(unix)]
+                    fix_links: arg.fix_links,
+                    git: arg.git,
+                    allow_dirty: arg.allow_dirty,
+                    git_ignore: arg.git_ignore,
+                    hide: true,
+                    jobs: arg.jobs,
+                    interactive: arg.interactive,
+                };
+                operation.run(arg.input, arg.dry_run)?;
             }
             Command::Show(arg) => {
-                let files = arg
-                    .input
-                    .into_iter()
-                    .map(|f| {
-                        #[cfg(unix)]
-                        std::fs::metadata(&f)
-                            .with_context(|| format!("{} does not exist", f.display()))?;
-                        let is_hidden = hf::is_hidden(&f).with_context(|| {
-                            format!("could not read information from {}", f.display())
-                        });
-                        match is_hidden {
-                            Ok(true) => Ok((f, true)),
-                            Ok(false) => Ok((f, false)),
-                            Err(err) => Err(err),
-                        }
-                    })
-                    .collect::<anyhow::Result<Vec<_>>>()?;
-
-                match (arg.dry_run, arg.force) {
-                    (true, _) => {
-                        for file in files {
-                            if file.1 {
-                                println!("{}", file.0.display());
-                            } else {
-                                warn!("{} is ignored", file.0.display());
-                            }
-                        }
-                    }
-                    (_, true) => {
-                        for file in files {
-                            if file.1 {
-                                hf::show(&file.0).with_context(|| {
-                                    format!("could not show {}", file.0.display())
-                                })?;
-                                info!("{} has been shown", file.0.display());
-                            } else {
-                                warn!("{} is already shown", file.0.display());
-                            }
-                        }
-                    }
-                    _ => unreachable!(),
-                }
+                let operation = Operation {
+                    options: arg.options(),
+                    progress: arg.progress(),
+                    companions: arg.companions(),
+                    scheme: arg.naming_scheme(),
+                    dereference: arg.dereference,
+                    #[cfg(unix)]
+                    fix_links: arg.fix_links,
+                    git: arg.git,
+                    allow_dirty: arg.allow_dirty,
+                    git_ignore: false,
+                    hide: false,
+                    jobs: arg.jobs,
+                    interactive: arg.interactive,
+                };
+                operation.run(arg.input, arg.dry_run)?;
             }
+            Command::Clean(arg) => clean(&arg)?,
+            Command::Explain(arg) => explain(&arg)?,
+            Command::Migrate(arg) => migrate(&arg)?,
+            Command::Audit(arg) => audit(&arg)?,
+            Command::Sync(arg) => sync(&arg)?,
+            Command::Watch(arg) => watch(&arg)?,
+            Command::Exec(arg) => return exec(&arg),
+            Command::Tui(arg) => tui(&arg)?,
+            Command::Config(arg) => match arg.command {
+                ConfigCommand::Show => print!("{settings}"),
+            },
         }
     } else {
         Opt::command()
             .error(ErrorKind::MissingSubcommand, "missing subcommand")
             .exit()
     }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Hiding or showing files on the file system.
+#[allow(clippy::struct_excessive_bools)]
+struct Operation {
+    options: Options,
+    progress: Option<bool>,
+    companions: Companions,
+    scheme: Option<Box<dyn NamingScheme + std::marker::Sync>>,
+    dereference: bool,
+    #[cfg(unix)]
+    fix_links: Option<PathBuf>,
+    git: bool,
+    allow_dirty: bool,
+    git_ignore: bool,
+    hide: bool,
+    jobs: NonZeroUsize,
+    interactive: bool,
+}
+
+impl Operation {
+    /// Hides or shows `input` and their companion files as a whole.
+    #[allow(clippy::too_many_lines)]
+    fn run(self, input: Vec<PathBuf>, dry_run: bool) -> anyhow::Result<()> {
+        let verb = if self.hide { "hide" } else { "show" };
+        let mut prompt = if self.interactive {
+            Some(Prompt::stdin()?)
+        } else {
+            None
+        };
+        let batch = Batch::new(self.jobs);
+        let reporter = Reporter::new(self.progress, "checking");
+        // The files are checked concurrently, but the errors are reported in
+        // the order of the input.
+        let prepared =
+            batch.try_map_with_progress(&input, |file| self.prepare(file), |p| reporter.update(p));
+        let mut plan = Plan::new();
+        let mut files = Vec::with_capacity(input.len());
+        let prepared = prepared.into_iter().collect::<anyhow::Result<Vec<_>>>()?;
+        if prompt.is_some() {
+            reporter.clear();
+        }
+        for (file, prepared) in input.into_iter().zip(prepared) {
+            let Some(p) = prepared else {
+                files.push((file, None));
+                continue;
+            };
+            if let Some(prompt) = &mut prompt {
+                let question = question(verb, &file, p.steps());
+                if !prompt.confirm(&question)? {
+                    continue;
+                }
+            }
+            let first = plan.steps().len();
+            plan.append(p)
+                .with_context(|| format!("could not {verb} {}", file.display()))?;
+            files.push((file, Some(first..plan.steps().len())));
+        }
+        drop(prompt);
+        if self.git {
+            check_git_status(plan.steps(), self.allow_dirty)?;
+        }
+        #[cfg(unix)]
+        let renames = plan
+            .steps()
+            .iter()
+            .map(|s| (s.path(), s.dest()))
+            .collect::<Vec<_>>();
+
+        if dry_run {
+            drop(reporter);
+            for (file, steps) in files {
+                if let Some(steps) = steps {
+                    println!("{}", file.display());
+                    for step in &plan.steps()[steps][1..] {
+                        println!("{}", step.path().display());
+                    }
+                } else {
+                    warn!("{} is ignored", file.display());
+                }
+            }
+            #[cfg(unix)]
+            if let Some(root) = &self.fix_links {
+                fix_links(root, &renames, true)?;
+            }
+            return Ok(());
+        }
+
+        reporter.start(if self.hide { "hiding" } else { "showing" });
+        batch
+            .execute_with_progress(&plan, |p| reporter.update(p))
+            .with_context(|| {
+                format!("could not {verb} the files, so the changes have been rolled back")
+            })?;
+        drop(reporter);
+        if self.git {
+            if let Err(err) = self.update_index(plan.steps()) {
+                plan.revert().context("could not restore the files")?;
+                return Err(err.context(
+                    "could not update the Git index, so the changes have been rolled back",
+                ));
+            }
+        }
+        for (file, steps) in files {
+            let Some(steps) = steps else {
+                if self.hide {
+                    warn!("{} is already hidden", file.display());
+                } else {
+                    warn!("{} is already shown", file.display());
+                }
+                continue;
+            };
+            for step in &plan.steps()[steps] {
+                let path = if step.is_companion() {
+                    step.path()
+                } else {
+                    &file
+                };
+                match (self.hide, step.path() == step.dest()) {
+                    (_, false) if step.is_companion() => info!(
+                        "{} has been renamed to {}",
+                        path.display(),
+                        step.dest().display()
+                    ),
+                    (true, _) => info!("{} has been hidden", path.display()),
+                    (false, _) => info!("{} has been shown", path.display()),
+                }
+            }
+        }
+        #[cfg(unix)]
+        if let Some(root) = &self.fix_links {
+            fix_links(root, &renames, false)?;
+        }
+        Ok(())
+    }
+
+    /// Makes a plan to hide or show `file`, or returns [`None`] if it is
+    /// already hidden or shown.
+    fn prepare(&self, file: &PathBuf) -> anyhow::Result<Option<Plan>> {
+        let verb = if self.hide { "hide" } else { "show" };
+        #[cfg(unix)]
+        if self.dereference {
+            fs::metadata(file)
+        } else {
+            fs::symlink_metadata(file)
+        }
+        .with_context(|| format!("{} does not exist", file.display()))?;
+        let path = if self.dereference && file.is_symlink() {
+            fs::canonicalize(file)
+                .with_context(|| format!("could not resolve {}", file.display()))?
+        } else {
+            file.clone()
+        };
+        let is_hidden = if let Some(scheme) = &self.scheme {
+            path.file_name().is_some_and(|n| scheme.is_hidden_name(n))
+        } else {
+            self.options
+                .is_hidden(file)
+                .with_context(|| format!("could not read information from {}", file.display()))?
+        };
+        if is_hidden == self.hide {
+            return Ok(None);
+        }
+        let mut plan = Plan::new();
+        match (&self.scheme, self.hide) {
+            (Some(scheme), true) => plan.hide_with(&path, scheme, &self.companions),
+            (Some(scheme), false) => plan.show_with(&path, scheme, &self.companions),
+            (None, true) => plan.hide(&path, &self.companions),
+            (None, false) => plan.show(&path, &self.companions),
+        }
+        .with_context(|| format!("could not {verb} {}", file.display()))?;
+        Ok(Some(plan))
+    }
+
+    /// Records the renames of `steps` in the index of the Git work trees.
+    ///
+    /// Each work tree is opened once, and all the renames are recorded before
+    /// anything is written. The index files and the `.gitignore` files are
+    /// backed up before they are written, and restored if any of the writes
+    /// fails, so they are left as they were.
+    fn update_index(&self, steps: &[Step]) -> anyhow::Result<()> {
+        let mut work_trees = Vec::<WorkTree>::new();
+        let mut owners = Vec::with_capacity(steps.len());
+        for step in steps {
+            let dir = match step.dest().parent() {
+                Some(p) if !p.as_os_str().is_empty() => p,
+                _ => Path::new("."),
+            };
+            let dir = fs::canonicalize(dir)
+                .with_context(|| format!("could not resolve {}", dir.display()))?;
+            // Prefer the innermost work tree, such as a submodule.
+            let found = work_trees
+                .iter()
+                .enumerate()
+                .filter(|(_, w)| dir.starts_with(w.workdir()))
+                .max_by_key(|(_, w)| w.workdir().components().count())
+                .map(|(i, _)| i);
+            let i = if let Some(i) = found {
+                i
+            } else {
+                let work_tree = WorkTree::discover(&dir).with_context(|| {
+                    format!("could not open the Git work tree of {}", dir.display())
+                })?;
+                work_trees.push(work_tree);
+                work_trees.len() - 1
+            };
+            owners.push(i);
+            let work_tree = &mut work_trees[i];
+            if self.git_ignore {
+                work_tree.untrack(step.path())
+            } else {
+                work_tree.record_rename(step.path(), step.dest())
+            }
+            .with_context(|| format!("could not record {}", step.path().display()))?;
+        }
+
+        let mut paths = work_trees
+            .iter()
+            .map(|w| w.index_file().to_path_buf())
+            .collect::<Vec<_>>();
+        if self.git_ignore {
+            for (step, &i) in steps.iter().zip(&owners) {
+                let path = work_trees[i]
+                    .gitignore_path(step.dest())
+                    .with_context(|| format!("could not ignore {}", step.dest().display()))?;
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        let backups = paths
+            .into_iter()
+            .map(|p| {
+                Backup::new(p.clone()).with_context(|| format!("could not read {}", p.display()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let result = (|| -> anyhow::Result<()> {
+            for work_tree in &mut work_trees {
+                work_tree.write().with_context(|| {
+                    format!(
+                        "could not write the Git index of {}",
+                        work_tree.workdir().display()
+                    )
+                })?;
+            }
+            if self.git_ignore {
+                for (step, &i) in steps.iter().zip(&owners) {
+                    work_trees[i]
+                        .ignore(step.dest())
+                        .with_context(|| format!("could not ignore {}", step.dest().display()))?;
+                }
+            }
+            Ok(())
+        })();
+        if result.is_err() {
+            for backup in &backups {
+                if let Err(err) = backup.restore() {
+                    warn!("could not restore {}: {err}", backup.path.display());
+                }
+            }
+        }
+        result
+    }
+}
+
+/// The contents of a file before it is written.
+struct Backup {
+    path: PathBuf,
+    contents: Option<Vec<u8>>,
+}
+
+impl Backup {
+    /// Reads the file at `path`, which may not exist.
+    fn new(path: PathBuf) -> io::Result<Self> {
+        let contents = match fs::read(&path) {
+            Ok(contents) => Some(contents),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        Ok(Self { path, contents })
+    }
+
+    /// Writes the contents back, or removes the file if it did not exist.
+    ///
+    /// A dangling symbolic link is left as is.
+    fn restore(&self) -> io::Result<()> {
+        if let Some(contents) = &self.contents {
+            return fs::write(&self.path, contents);
+        }
+        if self.path.is_symlink() {
+            return Ok(());
+        }
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the question whether to `verb` `file`, which shows the destination
+/// of `steps`.
+fn question(verb: &str, file: &Path, steps: &[Step]) -> String {
+    let dest = steps
+        .first()
+        .filter(|s| s.path() != s.dest())
+        .map(|s| format!(" -> {}", s.dest().display()))
+        .unwrap_or_default();
+    let companions = match steps.len().saturating_sub(1) {
+        0 => String::new(),
+        1 => " (with 1 companion file)".to_owned(),
+        n => format!(" (with {n} companion files)"),
+    };
+    format!("{verb} {}{dest}{companions}?", file.display())
+}
+
+/// Checks that the files to rename don't have uncommitted changes.
+fn check_git_status(steps: &[Step], allow_dirty: bool) -> anyhow::Result<()> {
+    for path in steps.iter().map(Step::path) {
+        let status = WorkTree::discover(path)
+            .and_then(|w| w.status(path))
+            .with_context(|| format!("could not read Git status of {}", path.display()))?;
+        if status == Status::Modified {
+            if allow_dirty {
+                warn!("{} has uncommitted changes", path.display());
+            } else {
+                bail!("{} has uncommitted changes", path.display());
+            }
+        }
+    }
     Ok(())
 }
+
+/// Removes junk files in the directories.
+fn clean(arg: &Clean) -> anyhow::Result<()> {
+    let mut prompt = if arg.interactive {
+        Some(Prompt::stdin()?)
+    } else {
+        None
+    };
+    let catalogue = arg.catalogue();
+    let reporter = Reporter::new(
+        if arg.dry_run {
+            Some(false)
+        } else {
+            arg.progress()
+        },
+        "removing",
+    );
+    for dir in &arg.input {
+        let junk = catalogue
+            .find(dir, arg.recursive)
+            .with_context(|| format!("could not read {}", dir.display()))?;
+        reporter.inc_length(junk.len());
+        for path in junk {
+            if arg.dry_run {
+                println!("{}", path.display());
+                continue;
+            }
+            if let Some(prompt) = &mut prompt {
+                let question = format!("remove {}?", path.display());
+                if !reporter.suspend(|| prompt.confirm(&question))? {
+                    if prompt.is_quit() {
+                        return Ok(());
+                    }
+                    reporter.inc();
+                    continue;
+                }
+            }
+            hf::clean::remove(&path)
+                .with_context(|| format!("could not remove {}", path.display()))?;
+            reporter.inc();
+            reporter.suspend(|| info!("{} has been removed", path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Prints the reasons why the files are hidden.
+fn explain(arg: &Explain) -> anyhow::Result<()> {
+    for path in &arg.input {
+        let status = hf::hidden_status(path)
+            .with_context(|| format!("could not read information from {}", path.display()))?;
+        let (is_hidden, reasons) = if arg.effective {
+            let is_hidden = arg
+                .root
+                .as_ref()
+                .map_or_else(
+                    || hf::is_effectively_hidden(path),
+                    |root| hf::is_effectively_hidden_within(path, root),
+                )
+                .with_context(|| format!("could not read information from {}", path.display()))?;
+            let reasons = status
+                .reasons()
+                .iter()
+                .filter(|r| match r {
+                    HiddenReason::DotPrefix | HiddenReason::Attribute => true,
+                    HiddenReason::HiddenAncestor(ancestor) => arg
+                        .root
+                        .as_ref()
+                        .is_none_or(|root| ancestor.starts_with(root) && ancestor != root),
+                    _ => false,
+                })
+                .collect();
+            (is_hidden, reasons)
+        } else {
+            (
+                status.is_hidden(),
+                status.reasons().iter().collect::<Vec<_>>(),
+            )
+        };
+        if is_hidden {
+            println!("{}: hidden", path.display());
+            for reason in reasons {
+                println!("  - {reason}");
+            }
+        } else {
+            println!("{}: not hidden", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites symbolic links under `root` which point at the renamed files.
+#[cfg(unix)]
+fn fix_links(root: &Path, renames: &[(&Path, &Path)], dry_run: bool) -> anyhow::Result<()> {
+    let fixes = hf::links::find(root, renames)
+        .with_context(|| format!("could not find symbolic links under {}", root.display()))?;
+    for fix in fixes {
+        if dry_run {
+            println!("{} -> {}", fix.path().display(), fix.new_target().display());
+        } else {
+            fix.apply()
+                .with_context(|| format!("could not rewrite {}", fix.path().display()))?;
+            info!(
+                "{} has been rewritten to point to {}",
+                fix.path().display(),
+                fix.new_target().display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Renames members of the tar archive read from standard input.
+fn rewrite_archive(input: &[PathBuf], dry_run: bool, hide: bool) -> anyhow::Result<()> {
+    let members = input
+        .iter()
+        .map(|m| {
+            let is_hidden = m
+                .file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .with_context(|| format!("{} is not a valid member name", m.display()))?;
+            Ok((m.as_path(), is_hidden != hide))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    for member in members.iter().filter(|m| !m.1) {
+        match (dry_run, hide) {
+            (true, _) => warn!("{} is ignored", member.0.display()),
+            (_, true) => warn!("{} is already hidden", member.0.display()),
+            (_, false) => warn!("{} is already shown", member.0.display()),
+        }
+    }
+    let members = members
+        .into_iter()
+        .filter_map(|m| m.1.then_some(m.0))
+        .collect::<Vec<_>>();
+
+    let reader = io::stdin().lock();
+    let writer: Box<dyn Write> = if dry_run {
+        Box::new(io::sink())
+    } else {
+        Box::new(io::stdout().lock())
+    };
+    let renamed = if hide {
+        hf::tar::hide(reader, writer, &members)
+    } else {
+        hf::tar::show(reader, writer, &members)
+    }
+    .context("could not rewrite the archive")?;
+
+    for member in members {
+        let name = member.strip_prefix(".").unwrap_or(member);
+        if !renamed.iter().any(|(p, _)| p.starts_with(name)) {
+            warn!("{} does not exist in the archive", member.display());
+            continue;
+        }
+        match (dry_run, hide) {
+            (true, _) => println!("{}", member.display()),
+            (_, true) => info!("{} has been hidden", member.display()),
+            (_, false) => info!("{} has been shown", member.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Converts hidden files in the directories from one mechanism to another.
+fn migrate(arg: &Migrate) -> anyhow::Result<()> {
+    if arg.from == arg.to {
+        bail!("--from and --to must be different");
+    }
+    let mut prompt = if arg.interactive {
+        Some(Prompt::stdin()?)
+    } else {
+        None
+    };
+    let mut migration = Migration::new(arg.from.into(), arg.to.into());
+    for dir in &arg.input {
+        migration
+            .find(dir, arg.recursive)
+            .with_context(|| format!("could not read {}", dir.display()))?;
+    }
+    if let Some(prompt) = &mut prompt {
+        let mut accepted = Migration::new(arg.from.into(), arg.to.into());
+        for step in migration.steps() {
+            let question = if step.path() == step.dest() {
+                format!("convert {}?", step.path().display())
+            } else {
+                format!(
+                    "convert {} -> {}?",
+                    step.path().display(),
+                    step.dest().display()
+                )
+            };
+            if prompt.confirm(&question)? {
+                accepted
+                    .add(step.path())
+                    .with_context(|| format!("could not read {}", step.path().display()))?;
+            }
+        }
+        migration = accepted;
+    }
+    if arg.dry_run {
+        for step in migration.steps() {
+            println!("{}", step.path().display());
+        }
+        return Ok(());
+    }
+
+    migration
+        .execute()
+        .context("could not migrate the files, so the changes have been rolled back")?;
+    for step in migration.steps() {
+        if step.path() != step.dest() {
+            info!(
+                "{} has been renamed to {}",
+                step.path().display(),
+                step.dest().display()
+            );
+        }
+        info!(
+            "{} has been hidden with {}",
+            step.dest().display(),
+            migration.to()
+        );
+    }
+    Ok(())
+}
+
+/// Returns the files which are not in the state declared by the manifest.
+fn find_drift(manifest_path: &Path) -> anyhow::Result<Vec<Drift>> {
+    let manifest = Manifest::open(manifest_path)
+        .with_context(|| format!("could not read {}", manifest_path.display()))?;
+    let root = match manifest_path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let drift = manifest
+        .audit(root)
+        .with_context(|| format!("could not read {}", root.display()))?;
+    // The manifest itself is never hidden or shown.
+    let manifest_path = manifest_path.file_name().map(|n| root.join(n));
+    let drift = drift
+        .into_iter()
+        .filter(|d| manifest_path.as_deref() != Some(d.path()))
+        .collect();
+    Ok(drift)
+}
+
+/// Reports the files which are not in the state declared by the manifest.
+fn audit(arg: &Audit) -> anyhow::Result<()> {
+    let drift = find_drift(&arg.manifest)?;
+    for d in &drift {
+        println!("{}: should be {}", d.path().display(), d.state());
+    }
+    if !drift.is_empty() {
+        bail!(
+            "{} files are not in the state declared by {}",
+            drift.len(),
+            arg.manifest.display()
+        );
+    }
+    Ok(())
+}
+
+/// Hides and shows the files to reach the state declared by the manifest.
+fn sync(arg: &Sync) -> anyhow::Result<()> {
+    let drift = find_drift(&arg.manifest)?;
+    let companions = Companions::default();
+    let mut plan = Plan::new();
+    for d in &drift {
+        match d.state() {
+            State::Hidden => plan.hide(d.path(), &companions),
+            State::Visible => plan.show(d.path(), &companions),
+        }
+        .with_context(|| format!("could not {} {}", verb(d.state()), d.path().display()))?;
+    }
+    if arg.dry_run {
+        for step in plan.steps() {
+            println!("{}", step.path().display());
+        }
+        return Ok(());
+    }
+
+    plan.execute()
+        .context("could not sync the files, so the changes have been rolled back")?;
+    for step in plan.steps() {
+        match (step.is_hide(), step.path() == step.dest()) {
+            (_, false) if step.is_companion() => info!(
+                "{} has been renamed to {}",
+                step.path().display(),
+                step.dest().display()
+            ),
+            (true, _) => info!("{} has been hidden", step.path().display()),
+            (false, _) => info!("{} has been shown", step.path().display()),
+        }
+    }
+    Ok(())
+}
+
+/// Hides and shows entries as they are created or renamed.
+fn watch(arg: &Watch) -> anyhow::Result<()> {
+    let manifest = arg.manifest()?;
+    let debounce = Duration::from_millis(arg.debounce);
+    let mut enforcer = Enforcer::new(&arg.input, manifest, arg.recursive, debounce)
+        .with_context(|| format!("could not open {}", arg.input.display()))?;
+    enforcer.scan()?;
+    if arg.once {
+        return Ok(());
+    }
+    enforcer.watch()
+}
+
+/// Runs the command while the files are hidden or shown, and restores them
+/// after it exits.
+fn exec(arg: &Exec) -> anyhow::Result<ExitCode> {
+    let companions = Companions::default();
+    let mut plan = Plan::new();
+    for (path, hide) in arg
+        .hide
+        .iter()
+        .map(|p| (p, true))
+        .chain(arg.show.iter().map(|p| (p, false)))
+    {
+        let verb = if hide { "hide" } else { "show" };
+        if hf::is_hidden(path).with_context(|| format!("could not {verb} {}", path.display()))?
+            == hide
+        {
+            warn!(
+                "{} is already {}",
+                path.display(),
+                if hide { "hidden" } else { "visible" }
+            );
+            continue;
+        }
+        if hide {
+            plan.hide(path, &companions)
+        } else {
+            plan.show(path, &companions)
+        }
+        .with_context(|| format!("could not {verb} {}", path.display()))?;
+    }
+
+    // The signals must be handled before renaming the files, so that they are
+    // always restored.
+    let guard = SignalGuard::new().context("could not handle signals")?;
+    plan.execute()
+        .context("could not prepare the files, so the changes have been rolled back")?;
+    for step in plan.steps() {
+        info!(
+            "{} has been renamed to {}",
+            step.path().display(),
+            step.dest().display()
+        );
+    }
+
+    let status = guard.run(&arg.command);
+    plan.revert().context("could not restore the files")?;
+    for step in plan.steps().iter().rev() {
+        info!("{} has been restored", step.path().display());
+    }
+    let status = status
+        .with_context(|| format!("could not run {}", Path::new(&arg.command[0]).display()))?;
+    Ok(exec::exit_code(status))
+}
+
+/// Browses the directory in the terminal UI.
+fn tui(arg: &Tui) -> anyhow::Result<()> {
+    if !io::stdout().is_terminal() {
+        bail!("could not start the terminal UI because standard output is not a terminal");
+    }
+    let mut browser = Browser::open(&arg.input)
+        .with_context(|| format!("could not open {}", arg.input.display()))?;
+    let mut terminal = ratatui::try_init().context("could not initialize the terminal")?;
+    let result = browser.run(&mut terminal);
+    ratatui::try_restore().context("could not restore the terminal")?;
+    result.context("could not read a key event")
+}
+
+/// Returns the verb which changes a file to `state`.
+const fn verb(state: State) -> &'static str {
+    match state {
+        State::Hidden => "hide",
+        State::Visible => "show",
+    }
+}
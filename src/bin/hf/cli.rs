@@ -3,12 +3,22 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use std::{
+    ffi::OsString,
     io::{self, Write},
+    num::NonZeroUsize,
     path::PathBuf,
+    str::FromStr,
 };
 
 use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
 use clap_complete::Generator;
+use hf::{
+    Companions, NamingScheme, Options, SymlinkPolicy,
+    clean::Catalogue,
+    manifest::{Manifest, State},
+    migrate,
+    scheme::{Dot, Prefix, Suffix},
+};
 use simplelog::LevelFilter;
 
 const LONG_VERSION: &str = concat!(
@@ -31,6 +41,24 @@ const HIDE_AFTER_LONG_HELP: &str = "See `hf-hide(1)` for more details.";
 
 const SHOW_AFTER_LONG_HELP: &str = "See `hf-show(1)` for more details.";
 
+const CLEAN_AFTER_LONG_HELP: &str = "See `hf-clean(1)` for more details.";
+
+const EXPLAIN_AFTER_LONG_HELP: &str = "See `hf-explain(1)` for more details.";
+
+const MIGRATE_AFTER_LONG_HELP: &str = "See `hf-migrate(1)` for more details.";
+
+const AUDIT_AFTER_LONG_HELP: &str = "See `hf-audit(1)` for more details.";
+
+const SYNC_AFTER_LONG_HELP: &str = "See `hf-sync(1)` for more details.";
+
+const WATCH_AFTER_LONG_HELP: &str = "See `hf-watch(1)` for more details.";
+
+const EXEC_AFTER_LONG_HELP: &str = "See `hf-exec(1)` for more details.";
+
+const TUI_AFTER_LONG_HELP: &str = "See `hf-tui(1)` for more details.";
+
+const CONFIG_AFTER_LONG_HELP: &str = "See `hf-config(1)` for more details.";
+
 #[derive(Debug, Parser)]
 #[command(
     version,
@@ -73,9 +101,46 @@ pub enum Command {
     /// Make hidden files and directories visible.
     #[command(after_long_help(SHOW_AFTER_LONG_HELP))]
     Show(Show),
+
+    /// Remove well-known junk files, such as `.DS_Store` and `Thumbs.db`.
+    #[command(after_long_help(CLEAN_AFTER_LONG_HELP))]
+    Clean(Clean),
+
+    /// Explain why files and directories are or aren't hidden.
+    #[command(after_long_help(EXPLAIN_AFTER_LONG_HELP))]
+    Explain(Explain),
+
+    /// Convert hidden files from one hiding mechanism to another.
+    #[command(after_long_help(MIGRATE_AFTER_LONG_HELP))]
+    Migrate(Migrate),
+
+    /// Report files which are not in the state declared by a manifest.
+    #[command(after_long_help(AUDIT_AFTER_LONG_HELP))]
+    Audit(Audit),
+
+    /// Hide and show files to reach the state declared by a manifest.
+    #[command(after_long_help(SYNC_AFTER_LONG_HELP))]
+    Sync(Sync),
+
+    /// Hide and show entries as they are created or renamed.
+    #[command(after_long_help(WATCH_AFTER_LONG_HELP))]
+    Watch(Watch),
+
+    /// Run a command while files are hidden or shown.
+    #[command(after_long_help(EXEC_AFTER_LONG_HELP))]
+    Exec(Exec),
+
+    /// Browse a directory and hide or show its entries interactively.
+    #[command(after_long_help(TUI_AFTER_LONG_HELP))]
+    Tui(Tui),
+
+    /// Inspect the configuration.
+    #[command(after_long_help(CONFIG_AFTER_LONG_HELP))]
+    Config(Config),
 }
 
 #[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 #[command(group(ArgGroup::new("mode").required(true)))]
 pub struct Hide {
     /// Actually hide files and directories.
@@ -86,12 +151,121 @@ pub struct Hide {
     #[arg(short('n'), long, group("mode"))]
     pub dry_run: bool,
 
+    /// Prompt before hiding each file or directory.
+    ///
+    /// The prompt shows the path which the file is renamed to. The answer is
+    /// one of `y` (yes), `n` (no), `a` (yes to this and all the remaining) and
+    /// `q` (quit). This requires standard input to be a terminal.
+    #[arg(short, long, group("mode"), conflicts_with("tar"))]
+    pub interactive: bool,
+
+    /// Hide members of a tar archive instead of files and directories.
+    ///
+    /// The archive is read from standard input, and the archive with the
+    /// members renamed is written to standard output. The archive may be
+    /// compressed with gzip or Zstandard.
+    #[arg(long)]
+    pub tar: bool,
+
+    /// Hide the targets of symbolic links instead of the links themselves.
+    ///
+    /// Chains of symbolic links are resolved until a file which is not a
+    /// symbolic link is reached.
+    #[arg(short('L'), long, overrides_with("no_dereference"))]
+    pub dereference: bool,
+
+    /// Hide symbolic links themselves.
+    ///
+    /// This is the default behavior.
+    #[arg(short('P'), long, overrides_with("dereference"))]
+    pub no_dereference: bool,
+
+    /// Rewrite symbolic links under the given directory which point at the
+    /// renamed files.
+    ///
+    /// The rewritten links keep whether their targets are relative or
+    /// absolute. This option is only available on Unix.
+    #[cfg(unix)]
+    #[arg(long, value_name("ROOT"), value_hint(ValueHint::DirPath))]
+    pub fix_links: Option<PathBuf>,
+
+    /// Rename files with the given naming scheme.
+    ///
+    /// SCHEME is one of `dot`, `prefix:STRING` or `suffix:STRING`. `dot` hides
+    /// files by prefixing `.` to their names, `prefix:STRING` by prefixing
+    /// STRING, and `suffix:STRING` by appending STRING. For example, hiding
+    /// with `prefix:dot_` renames `bashrc` to `dot_bashrc`. The files are
+    /// renamed even on Windows.
+    #[arg(long, value_name("SCHEME"), conflicts_with("tar"))]
+    pub scheme: Option<Scheme>,
+
+    /// Rename companion files matching the given pattern together.
+    ///
+    /// `{name}` in the pattern is replaced with the file name, and `{stem}` is
+    /// replaced with the file name without the extension. For example,
+    /// `{stem}.xmp` matches `IMG_1.xmp` for `IMG_1.jpg`. This option can be
+    /// specified multiple times. On Unix, `._{name}`, the metadata files
+    /// created by macOS, is always included unless --no-companions is
+    /// specified.
+    #[arg(long, value_name("PATTERN"), conflicts_with("no_companions"))]
+    pub companion: Vec<String>,
+
+    /// Don't rename any companion files.
+    #[arg(long)]
+    pub no_companions: bool,
+
+    /// Keep the index of the Git work tree in sync.
+    ///
+    /// If a file or directory is tracked, the rename is staged as a move. Files
+    /// with uncommitted changes are refused unless --allow-dirty is specified.
+    #[arg(long, conflicts_with("dereference"))]
+    pub git: bool,
+
+    /// Allow files with uncommitted changes with --git.
+    ///
+    /// The uncommitted changes are kept in the work tree.
+    #[arg(long, requires("git"))]
+    pub allow_dirty: bool,
+
+    /// Add the hidden names to `.gitignore` instead of staging the renames.
+    ///
+    /// The renamed files are removed from the index, and the hidden names are
+    /// added to the `.gitignore` file in the same directory.
+    #[arg(long, requires("git"))]
+    pub git_ignore: bool,
+
+    /// The number of files to hide concurrently.
+    ///
+    /// This speeds up hiding many files on network file systems. The
+    /// output is in the same order regardless of this.
+    #[arg(
+        short,
+        long,
+        default_value("1"),
+        value_name("N"),
+        conflicts_with("tar")
+    )]
+    pub jobs: NonZeroUsize,
+
+    /// Show a progress bar on standard error.
+    ///
+    /// This is the default if standard error is a terminal. The progress bar
+    /// shows the number of files which have been checked or hidden, the number
+    /// of errors, the rate and the estimated remaining time.
+    #[arg(long, overrides_with("no_progress"), conflicts_with("tar"))]
+    pub progress: bool,
+
+    /// Don't show a progress bar.
+    #[arg(long, overrides_with("progress"))]
+    pub no_progress: bool,
+
     /// Files and directories to hide.
     #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
     pub input: Vec<PathBuf>,
 }
 
 #[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 #[command(group(ArgGroup::new("mode").required(true)))]
 pub struct Show {
     /// Actually show hidden files and directories.
@@ -102,11 +276,453 @@ pub struct Show {
     #[arg(short('n'), long, group("mode"))]
     pub dry_run: bool,
 
+    /// Prompt before showing each file or directory.
+    ///
+    /// The prompt shows the path which the file is renamed to. The answer is
+    /// one of `y` (yes), `n` (no), `a` (yes to this and all the remaining) and
+    /// `q` (quit). This requires standard input to be a terminal.
+    #[arg(short, long, group("mode"), conflicts_with("tar"))]
+    pub interactive: bool,
+
+    /// Show hidden members of a tar archive instead of files and directories.
+    ///
+    /// The archive is read from standard input, and the archive with the
+    /// members renamed is written to standard output. The archive may be
+    /// compressed with gzip or Zstandard.
+    #[arg(long)]
+    pub tar: bool,
+
+    /// Show the targets of symbolic links instead of the links themselves.
+    ///
+    /// Chains of symbolic links are resolved until a file which is not a
+    /// symbolic link is reached.
+    #[arg(short('L'), long, overrides_with("no_dereference"))]
+    pub dereference: bool,
+
+    /// Show symbolic links themselves.
+    ///
+    /// This is the default behavior.
+    #[arg(short('P'), long, overrides_with("dereference"))]
+    pub no_dereference: bool,
+
+    /// Rewrite symbolic links under the given directory which point at the
+    /// renamed files.
+    ///
+    /// The rewritten links keep whether their targets are relative or
+    /// absolute. This option is only available on Unix.
+    #[cfg(unix)]
+    #[arg(long, value_name("ROOT"), value_hint(ValueHint::DirPath))]
+    pub fix_links: Option<PathBuf>,
+
+    /// Rename files with the given naming scheme.
+    ///
+    /// SCHEME is one of `dot`, `prefix:STRING` or `suffix:STRING`. `dot` hides
+    /// files by prefixing `.` to their names, `prefix:STRING` by prefixing
+    /// STRING, and `suffix:STRING` by appending STRING. For example, showing
+    /// with `prefix:dot_` renames `dot_bashrc` to `bashrc`. The files are
+    /// renamed even on Windows.
+    #[arg(long, value_name("SCHEME"), conflicts_with("tar"))]
+    pub scheme: Option<Scheme>,
+
+    /// Rename companion files matching the given pattern together.
+    ///
+    /// `{name}` in the pattern is replaced with the file name, and `{stem}` is
+    /// replaced with the file name without the extension. For example,
+    /// `{stem}.xmp` matches `IMG_1.xmp` for `IMG_1.jpg`. This option can be
+    /// specified multiple times. On Unix, `._{name}`, the metadata files
+    /// created by macOS, is always included unless --no-companions is
+    /// specified.
+    #[arg(long, value_name("PATTERN"), conflicts_with("no_companions"))]
+    pub companion: Vec<String>,
+
+    /// Don't rename any companion files.
+    #[arg(long)]
+    pub no_companions: bool,
+
+    /// Keep the index of the Git work tree in sync.
+    ///
+    /// If a file or directory is tracked, the rename is staged as a move. Files
+    /// with uncommitted changes are refused unless --allow-dirty is specified.
+    #[arg(long, conflicts_with("dereference"))]
+    pub git: bool,
+
+    /// Allow files with uncommitted changes with --git.
+    ///
+    /// The uncommitted changes are kept in the work tree.
+    #[arg(long, requires("git"))]
+    pub allow_dirty: bool,
+
+    /// The number of files to show concurrently.
+    ///
+    /// This speeds up showing many files on network file systems. The
+    /// output is in the same order regardless of this.
+    #[arg(
+        short,
+        long,
+        default_value("1"),
+        value_name("N"),
+        conflicts_with("tar")
+    )]
+    pub jobs: NonZeroUsize,
+
+    /// Show a progress bar on standard error.
+    ///
+    /// This is the default if standard error is a terminal. The progress bar
+    /// shows the number of files which have been checked or shown, the number
+    /// of errors, the rate and the estimated remaining time.
+    #[arg(long, overrides_with("no_progress"), conflicts_with("tar"))]
+    pub progress: bool,
+
+    /// Don't show a progress bar.
+    #[arg(long, overrides_with("progress"))]
+    pub no_progress: bool,
+
     /// Hidden files and directories to show.
     #[arg(value_name("FILE"), value_hint(ValueHint::FilePath))]
     pub input: Vec<PathBuf>,
 }
 
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[command(group(ArgGroup::new("mode").required(true)))]
+pub struct Clean {
+    /// Actually remove junk files.
+    #[arg(short, long, group("mode"))]
+    pub force: bool,
+
+    /// Don't actually remove anything, just show what would be done.
+    #[arg(short('n'), long, group("mode"))]
+    pub dry_run: bool,
+
+    /// Prompt before removing each junk file.
+    ///
+    /// The answer is one of `y` (yes), `n` (no), `a` (yes to this and all the
+    /// remaining) and `q` (quit). This requires standard input to be a
+    /// terminal.
+    #[arg(short, long, group("mode"))]
+    pub interactive: bool,
+
+    /// Search the subdirectories recursively.
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    /// Also treat files matching the given pattern as junk.
+    ///
+    /// `*` in the pattern matches any sequence of characters. If the pattern
+    /// ends with `/`, it only matches directories. Otherwise, it only matches
+    /// files which are not directories. This option can be specified multiple
+    /// times.
+    #[arg(long, value_name("PATTERN"))]
+    pub pattern: Vec<String>,
+
+    /// Show a progress bar on standard error.
+    ///
+    /// This is the default if standard error is a terminal. The progress bar
+    /// shows the number of junk files which have been removed, the rate and
+    /// the estimated remaining time.
+    #[arg(long, overrides_with("no_progress"))]
+    pub progress: bool,
+
+    /// Don't show a progress bar.
+    #[arg(long, overrides_with("progress"))]
+    pub no_progress: bool,
+
+    /// Directories to clean.
+    #[arg(
+        default_value("."),
+        value_name("DIRECTORY"),
+        value_hint(ValueHint::DirPath)
+    )]
+    pub input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Explain {
+    /// Report only whether the files are effectively hidden.
+    ///
+    /// A file is effectively hidden if the file itself or any ancestor
+    /// directory in the given path is hidden. Other reasons, such as `.hidden`
+    /// files, are not reported.
+    #[arg(short, long)]
+    pub effective: bool,
+
+    /// Don't check the given directory and its ancestor directories with
+    /// --effective.
+    #[arg(
+        long,
+        requires("effective"),
+        value_name("DIRECTORY"),
+        value_hint(ValueHint::DirPath)
+    )]
+    pub root: Option<PathBuf>,
+
+    /// Files and directories to explain.
+    #[arg(required(true), value_name("FILE"), value_hint(ValueHint::AnyPath))]
+    pub input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[command(group(ArgGroup::new("mode").required(true)))]
+pub struct Migrate {
+    /// Actually convert hidden files.
+    #[arg(short, long, group("mode"))]
+    pub force: bool,
+
+    /// Don't actually convert anything, just show what would be done.
+    #[arg(short('n'), long, group("mode"))]
+    pub dry_run: bool,
+
+    /// Prompt before converting each hidden file.
+    ///
+    /// The answer is one of `y` (yes), `n` (no), `a` (yes to this and all the
+    /// remaining) and `q` (quit). This requires standard input to be a
+    /// terminal.
+    #[arg(short, long, group("mode"))]
+    pub interactive: bool,
+
+    /// The mechanism which the files are hidden with.
+    #[arg(long, value_enum, value_name("MECHANISM"))]
+    pub from: Mechanism,
+
+    /// The mechanism to hide the files with.
+    #[arg(long, value_enum, value_name("MECHANISM"))]
+    pub to: Mechanism,
+
+    /// Search the subdirectories recursively.
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    /// Directories to migrate.
+    #[arg(
+        default_value("."),
+        value_name("DIRECTORY"),
+        value_hint(ValueHint::DirPath)
+    )]
+    pub input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Audit {
+    /// The manifest to check against.
+    ///
+    /// The paths in the manifest are relative to the directory which contains
+    /// it.
+    #[arg(
+        short,
+        long,
+        default_value("hf.toml"),
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath)
+    )]
+    pub manifest: PathBuf,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("mode").required(true)))]
+pub struct Sync {
+    /// Actually hide and show files.
+    #[arg(short, long, group("mode"))]
+    pub force: bool,
+
+    /// Don't actually hide or show anything, just show what would be done.
+    #[arg(short('n'), long, group("mode"))]
+    pub dry_run: bool,
+
+    /// The manifest to apply.
+    ///
+    /// The paths in the manifest are relative to the directory which contains
+    /// it.
+    #[arg(
+        short,
+        long,
+        default_value("hf.toml"),
+        value_name("FILE"),
+        value_hint(ValueHint::FilePath)
+    )]
+    pub manifest: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct Watch {
+    /// A rule which declares the state which the matching entries must be in.
+    ///
+    /// <PATTERN> is a glob which is relative to <DIRECTORY>, and <STATE> is
+    /// either `hidden` or `visible`. If an entry matches more than one rule,
+    /// the last one wins. This option can be specified multiple times.
+    #[arg(long("rule"), required(true), value_name("PATTERN=STATE"))]
+    pub rules: Vec<Rule>,
+
+    /// Watch the subdirectories recursively.
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    /// Wait until no events have occurred for this many milliseconds before
+    /// applying the rules.
+    #[arg(long, default_value("500"), value_name("MILLISECONDS"))]
+    pub debounce: u64,
+
+    /// Apply the rules to the existing entries and exit without watching.
+    #[arg(long)]
+    pub once: bool,
+
+    /// Directory to watch.
+    #[arg(value_name("DIRECTORY"), value_hint(ValueHint::DirPath))]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("files").required(true).multiple(true)))]
+pub struct Exec {
+    /// Hide files and directories while the command is running.
+    #[arg(
+        long,
+        value_name("FILE"),
+        num_args(1..),
+        group("files"),
+        value_hint(ValueHint::AnyPath)
+    )]
+    pub hide: Vec<PathBuf>,
+
+    /// Show hidden files and directories while the command is running.
+    #[arg(
+        long,
+        value_name("FILE"),
+        num_args(1..),
+        group("files"),
+        value_hint(ValueHint::AnyPath)
+    )]
+    pub show: Vec<PathBuf>,
+
+    /// The command to run and its arguments.
+    #[arg(
+        last(true),
+        required(true),
+        value_name("COMMAND"),
+        value_hint(ValueHint::CommandWithArguments)
+    )]
+    pub command: Vec<OsString>,
+}
+
+#[derive(Args, Debug)]
+pub struct Tui {
+    /// Directory to browse.
+    #[arg(
+        default_value("."),
+        value_name("DIRECTORY"),
+        value_hint(ValueHint::DirPath)
+    )]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct Config {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective configuration and where each value came from.
+    Show,
+}
+
+impl Clean {
+    /// Returns the catalogue of junk files.
+    pub fn catalogue(&self) -> Catalogue {
+        self.pattern
+            .iter()
+            .fold(Catalogue::default(), |c, p| c.pattern(p.as_str()))
+    }
+
+    /// Returns whether to show a progress bar, or [`None`] if it depends on
+    /// whether standard error is a terminal.
+    pub const fn progress(&self) -> Option<bool> {
+        progress(self.progress, self.no_progress)
+    }
+}
+
+impl Watch {
+    /// Returns the manifest which consists of the rules.
+    pub fn manifest(&self) -> io::Result<Manifest> {
+        self.rules
+            .iter()
+            .try_fold(Manifest::new(), |m, r| m.glob(&r.pattern, r.state))
+    }
+}
+
+impl Hide {
+    /// Returns the patterns of companion files.
+    pub fn companions(&self) -> Companions {
+        companions(&self.companion, self.no_companions)
+    }
+
+    /// Returns the naming scheme.
+    pub fn naming_scheme(&self) -> Option<Box<dyn NamingScheme + std::marker::Sync>> {
+        self.scheme.as_ref().map(Scheme::naming_scheme)
+    }
+
+    /// Returns the options for the operations.
+    pub const fn options(&self) -> Options {
+        options(self.dereference)
+    }
+
+    /// Returns whether to show a progress bar, or [`None`] if it depends on
+    /// whether standard error is a terminal.
+    pub const fn progress(&self) -> Option<bool> {
+        progress(self.progress, self.no_progress)
+    }
+}
+
+impl Show {
+    /// Returns the patterns of companion files.
+    pub fn companions(&self) -> Companions {
+        companions(&self.companion, self.no_companions)
+    }
+
+    /// Returns the naming scheme.
+    pub fn naming_scheme(&self) -> Option<Box<dyn NamingScheme + std::marker::Sync>> {
+        self.scheme.as_ref().map(Scheme::naming_scheme)
+    }
+
+    /// Returns the options for the operations.
+    pub const fn options(&self) -> Options {
+        options(self.dereference)
+    }
+
+    /// Returns whether to show a progress bar, or [`None`] if it depends on
+    /// whether standard error is a terminal.
+    pub const fn progress(&self) -> Option<bool> {
+        progress(self.progress, self.no_progress)
+    }
+}
+
+const fn options(dereference: bool) -> Options {
+    let policy = if dereference {
+        SymlinkPolicy::Target
+    } else {
+        SymlinkPolicy::Link
+    };
+    Options::new().symlink_policy(policy)
+}
+
+fn companions(patterns: &[String], no_companions: bool) -> Companions {
+    if no_companions {
+        return Companions::new();
+    }
+    patterns
+        .iter()
+        .fold(Companions::default(), |c, p| c.pattern(p.as_str()))
+}
+
+const fn progress(progress: bool, no_progress: bool) -> Option<bool> {
+    match (progress, no_progress) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
 impl Opt {
     /// Generates shell completion and print it.
     pub fn print_completion(generator: impl Generator) {
@@ -167,6 +783,107 @@ impl Generator for Shell {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum Mechanism {
+    /// The file name starts with `.`.
+    Dot,
+
+    /// The hidden file attribute of Windows or FAT file systems.
+    Attribute,
+
+    /// The file name is listed in the `.hidden` file.
+    HiddenList,
+
+    /// The DOS attributes stored by Samba.
+    Samba,
+}
+
+impl From<Mechanism> for migrate::Mechanism {
+    fn from(mechanism: Mechanism) -> Self {
+        match mechanism {
+            Mechanism::Dot => Self::Dot,
+            Mechanism::Attribute => Self::Attribute,
+            Mechanism::HiddenList => Self::HiddenList,
+            Mechanism::Samba => Self::Samba,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Scheme {
+    /// Prefix `.`.
+    Dot,
+
+    /// Prefix the given string.
+    Prefix(String),
+
+    /// Append the given string.
+    Suffix(String),
+}
+
+impl Scheme {
+    /// Returns the naming scheme of the library.
+    pub fn naming_scheme(&self) -> Box<dyn NamingScheme + std::marker::Sync> {
+        match self {
+            Self::Dot => Box::new(Dot),
+            Self::Prefix(prefix) => Box::new(Prefix::new(prefix.as_str())),
+            Self::Suffix(suffix) => Box::new(Suffix::new(suffix.as_str())),
+        }
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let scheme = match s.split_once(':') {
+            None if s == "dot" => Self::Dot,
+            Some(("prefix", prefix)) if !prefix.is_empty() => Self::Prefix(prefix.into()),
+            Some(("suffix", suffix)) if !suffix.is_empty() => Self::Suffix(suffix.into()),
+            _ => {
+                return Err(String::from(
+                    "expected `dot`, `prefix:STRING` or `suffix:STRING`",
+                ));
+            }
+        };
+        if let Self::Prefix(affix) | Self::Suffix(affix) = &scheme {
+            if affix.contains(['/', '\\']) {
+                return Err(String::from("the string must not contain a path separator"));
+            }
+        }
+        Ok(scheme)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+    pub pattern: String,
+    pub state: State,
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((pattern, state)) = s.rsplit_once('=') else {
+            return Err(String::from("expected `PATTERN=STATE`"));
+        };
+        let state = match state {
+            "hidden" => State::Hidden,
+            "visible" => State::Visible,
+            _ => return Err(String::from("the state must be `hidden` or `visible`")),
+        };
+        Manifest::new()
+            .glob(pattern, state)
+            .map_err(|err| err.to_string())?;
+        Ok(Self {
+            pattern: pattern.into(),
+            state,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
 #[value(rename_all = "UPPER")]
 pub enum LogLevel {
@@ -222,6 +939,68 @@ mod tests {
         assert_eq!(Shell::Zsh.file_name("hf"), "_hf");
     }
 
+    #[test]
+    fn from_str_scheme() {
+        assert_eq!(Scheme::from_str("dot").unwrap(), Scheme::Dot);
+        assert_eq!(
+            Scheme::from_str("prefix:dot_").unwrap(),
+            Scheme::Prefix(String::from("dot_"))
+        );
+        assert_eq!(
+            Scheme::from_str("suffix:~").unwrap(),
+            Scheme::Suffix(String::from("~"))
+        );
+        assert_eq!(
+            Scheme::from_str("prefix:a:b").unwrap(),
+            Scheme::Prefix(String::from("a:b"))
+        );
+        assert!(Scheme::from_str("prefix:").is_err());
+        assert!(Scheme::from_str("suffix:foo/").is_err());
+        assert!(Scheme::from_str("infix:_").is_err());
+        assert!(Scheme::from_str("").is_err());
+    }
+
+    #[test]
+    fn from_str_rule() {
+        assert_eq!(
+            Rule::from_str("snap=hidden").unwrap(),
+            Rule {
+                pattern: String::from("snap"),
+                state: State::Hidden
+            }
+        );
+        assert_eq!(
+            Rule::from_str("**/a=b=visible").unwrap(),
+            Rule {
+                pattern: String::from("**/a=b"),
+                state: State::Visible
+            }
+        );
+        assert!(Rule::from_str("snap").is_err());
+        assert!(Rule::from_str("snap=gone").is_err());
+        assert!(Rule::from_str("[snap=hidden").is_err());
+    }
+
+    #[test]
+    fn from_mechanism_to_migrate_mechanism() {
+        assert_eq!(
+            migrate::Mechanism::from(Mechanism::Dot),
+            migrate::Mechanism::Dot
+        );
+        assert_eq!(
+            migrate::Mechanism::from(Mechanism::Attribute),
+            migrate::Mechanism::Attribute
+        );
+        assert_eq!(
+            migrate::Mechanism::from(Mechanism::HiddenList),
+            migrate::Mechanism::HiddenList
+        );
+        assert_eq!(
+            migrate::Mechanism::from(Mechanism::Samba),
+            migrate::Mechanism::Samba
+        );
+    }
+
     #[test]
     fn default_log_level() {
         assert_eq!(LogLevel::default(), LogLevel::Info);
@@ -0,0 +1,466 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    env,
+    ffi::OsString,
+    fmt, fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use clap::{ArgMatches, ValueEnum, parser::ValueSource};
+use serde::{Deserialize, de::IgnoredAny};
+
+use crate::cli::{Command, LogLevel, Opt};
+
+/// The name of the configuration file of a project.
+const PROJECT_FILE_NAME: &str = "hf.toml";
+
+/// The environment variables which override the configuration files.
+const LOG_LEVEL_VAR: &str = "HF_LOG_LEVEL";
+const JOBS_VAR: &str = "HF_JOBS";
+const COMPANIONS_VAR: &str = "HF_COMPANIONS";
+
+/// Where a configuration value came from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    /// The built-in default value.
+    Default,
+
+    /// The configuration file at the given path.
+    File(PathBuf),
+
+    /// The given environment variable.
+    Env(&'static str),
+
+    /// The command-line option.
+    CommandLine,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Env(name) => write!(f, "{name}"),
+            Self::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// A configuration value and where it came from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Setting<T> {
+    value: T,
+    source: Source,
+}
+
+impl<T> Setting<T> {
+    const fn new(value: T) -> Self {
+        Self {
+            value,
+            source: Source::Default,
+        }
+    }
+
+    fn set(&mut self, value: T, source: Source) {
+        self.value = value;
+        self.source = source;
+    }
+}
+
+/// The contents of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    #[serde(default)]
+    options: Options,
+    // `hf.toml` is also the manifest of `hf audit` and `hf sync`.
+    #[serde(default, rename = "rule")]
+    _rules: IgnoredAny,
+}
+
+/// The `[options]` table of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct Options {
+    log_level: Option<String>,
+    jobs: Option<NonZeroUsize>,
+    companions: Option<Vec<String>>,
+}
+
+/// The effective configuration.
+///
+/// The values are taken from the following in the order of precedence, from
+/// highest to lowest:
+///
+/// 1. The command-line options.
+/// 2. The `HF_*` environment variables.
+/// 3. The nearest `hf.toml` in the current directory or its ancestors.
+/// 4. The user configuration file.
+/// 5. The system configuration file.
+/// 6. The built-in default values.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settings {
+    log_level: Setting<LogLevel>,
+    jobs: Setting<NonZeroUsize>,
+    companions: Setting<Vec<String>>,
+}
+
+impl Settings {
+    /// Loads the configuration files and the environment variables.
+    ///
+    /// A configuration file or an environment variable which is invalid is
+    /// ignored, so that it doesn't prevent every command from running, and
+    /// the errors are returned with the settings.
+    pub fn load() -> (Self, Vec<anyhow::Error>) {
+        let mut settings = Self::default();
+        let mut errors = Vec::new();
+        match env::current_dir().context("could not get the current directory") {
+            Ok(current_dir) => {
+                for path in files(&current_dir) {
+                    if let Err(err) = settings.merge_file(&path) {
+                        errors.push(err);
+                    }
+                }
+            }
+            Err(err) => errors.push(err),
+        }
+        settings.merge_env(|name| env::var_os(name), &mut errors);
+        (settings, errors)
+    }
+
+    /// Overrides the values with the options which are specified on the
+    /// command line, and sets the other options of `opt` to the values.
+    pub fn apply(&mut self, opt: &mut Opt, matches: &ArgMatches) {
+        if is_specified(matches, "log_level") {
+            self.log_level
+                .set(opt.log_level.clone(), Source::CommandLine);
+        } else {
+            opt.log_level = self.log_level.value.clone();
+        }
+        let (jobs, companion, no_companions, matches) =
+            match (&mut opt.command, matches.subcommand()) {
+                (Some(Command::Hide(arg)), Some((_, matches))) => (
+                    &mut arg.jobs,
+                    &mut arg.companion,
+                    arg.no_companions,
+                    matches,
+                ),
+                (Some(Command::Show(arg)), Some((_, matches))) => (
+                    &mut arg.jobs,
+                    &mut arg.companion,
+                    arg.no_companions,
+                    matches,
+                ),
+                _ => return,
+            };
+        if is_specified(matches, "jobs") {
+            self.jobs.set(*jobs, Source::CommandLine);
+        } else {
+            *jobs = self.jobs.value;
+        }
+        if !companion.is_empty() {
+            self.companions.set(companion.clone(), Source::CommandLine);
+        } else if !no_companions {
+            companion.clone_from(&self.companions.value);
+        }
+    }
+
+    /// Merges the configuration file at `path` if it exists.
+    ///
+    /// If the file is invalid, no values are taken from it.
+    fn merge_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("could not read {}", path.display()));
+            }
+        };
+        let File { options, .. } = toml::from_str(&contents)
+            .with_context(|| format!("could not parse {}", path.display()))?;
+        let source = Source::File(path.to_path_buf());
+        if let Some(level) = options.log_level {
+            let level = parse_log_level(&level)
+                .with_context(|| format!("invalid log-level in {}", path.display()))?;
+            self.log_level.set(level, source.clone());
+        }
+        if let Some(jobs) = options.jobs {
+            self.jobs.set(jobs, source.clone());
+        }
+        if let Some(companions) = options.companions {
+            self.companions.set(companions, source);
+        }
+        Ok(())
+    }
+
+    /// Merges the environment variables which are returned by `var`.
+    ///
+    /// An invalid variable is skipped, and its error is added to `errors`.
+    fn merge_env(
+        &mut self,
+        var: impl Fn(&str) -> Option<OsString>,
+        errors: &mut Vec<anyhow::Error>,
+    ) {
+        let mut var = |name: &'static str| -> Option<String> {
+            let value = var(name)?.into_string();
+            value
+                .map_err(|_| errors.push(anyhow!("{name} is not valid UTF-8")))
+                .ok()
+        };
+        let log_level = var(LOG_LEVEL_VAR).map(|level| {
+            parse_log_level(&level).with_context(|| format!("invalid value for {LOG_LEVEL_VAR}"))
+        });
+        let jobs = var(JOBS_VAR).map(|jobs| {
+            jobs.parse::<NonZeroUsize>()
+                .with_context(|| format!("invalid value for {JOBS_VAR}"))
+        });
+        let companions = var(COMPANIONS_VAR);
+        match log_level {
+            Some(Ok(level)) => self.log_level.set(level, Source::Env(LOG_LEVEL_VAR)),
+            Some(Err(err)) => errors.push(err),
+            None => {}
+        }
+        match jobs {
+            Some(Ok(jobs)) => self.jobs.set(jobs, Source::Env(JOBS_VAR)),
+            Some(Err(err)) => errors.push(err),
+            None => {}
+        }
+        if let Some(companions) = companions {
+            // Patterns are separated by commas, and an empty value clears them.
+            let companions = companions
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .map(String::from)
+                .collect();
+            self.companions.set(companions, Source::Env(COMPANIONS_VAR));
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            log_level: Setting::new(LogLevel::default()),
+            jobs: Setting::new(NonZeroUsize::MIN),
+            companions: Setting::new(Vec::new()),
+        }
+    }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let log_level = self
+            .log_level
+            .value
+            .to_possible_value()
+            .expect("no values should be skipped");
+        writeln!(f, "[options]")?;
+        writeln!(
+            f,
+            "log-level = {:?} # {}",
+            log_level.get_name(),
+            self.log_level.source
+        )?;
+        writeln!(f, "jobs = {} # {}", self.jobs.value, self.jobs.source)?;
+        writeln!(
+            f,
+            "companions = {:?} # {}",
+            self.companions.value, self.companions.source
+        )
+    }
+}
+
+/// Returns the configuration files in the order of precedence, from lowest to
+/// highest.
+fn files(current_dir: &Path) -> Vec<PathBuf> {
+    let system = if cfg!(windows) {
+        env::var_os("ProgramData").map(|dir| PathBuf::from(dir).join(r"hf\config.toml"))
+    } else {
+        Some(PathBuf::from("/etc/hf/config.toml"))
+    };
+    let user = if cfg!(windows) {
+        env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join(r"hf\config.toml"))
+    } else {
+        // Relative paths in `XDG_CONFIG_HOME` are invalid, so they are ignored.
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|dir| PathBuf::from(dir).join(".config")))
+            .map(|dir| dir.join("hf/config.toml"))
+    };
+    let project = current_dir
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_FILE_NAME))
+        .find(|path| path.is_file());
+    [system, user, project].into_iter().flatten().collect()
+}
+
+fn parse_log_level(level: &str) -> anyhow::Result<LogLevel> {
+    LogLevel::from_str(level, true).map_err(|err| anyhow!(err))
+}
+
+/// Returns [`true`] if the argument `id` is specified on the command line.
+fn is_specified(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(contents: &str) -> (tempfile::TempDir, PathBuf) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(PROJECT_FILE_NAME);
+        fs::write(&path, contents).unwrap();
+        (temp_dir, path)
+    }
+
+    #[test]
+    fn default_settings() {
+        let settings = Settings::default();
+        assert_eq!(settings.log_level, Setting::new(LogLevel::Info));
+        assert_eq!(settings.jobs, Setting::new(NonZeroUsize::MIN));
+        assert_eq!(settings.companions, Setting::new(Vec::new()));
+    }
+
+    #[test]
+    fn merge_file() {
+        let (_temp_dir, path) = write_file(
+            "[options]\nlog-level = \"warn\"\njobs = 4\ncompanions = [\"{stem}.xmp\"]\n",
+        );
+        let mut settings = Settings::default();
+        settings.merge_file(&path).unwrap();
+        let source = Source::File(path);
+        assert_eq!(settings.log_level.value, LogLevel::Warn);
+        assert_eq!(settings.log_level.source, source);
+        assert_eq!(settings.jobs.value.get(), 4);
+        assert_eq!(settings.jobs.source, source);
+        assert_eq!(settings.companions.value, ["{stem}.xmp"]);
+        assert_eq!(settings.companions.source, source);
+    }
+
+    #[test]
+    fn merge_file_keeps_unset_values() {
+        let (_temp_dir, path) = write_file("[options]\njobs = 2\n");
+        let mut settings = Settings::default();
+        settings.merge_file(&path).unwrap();
+        assert_eq!(settings.log_level, Setting::new(LogLevel::Info));
+        assert_eq!(settings.jobs.value.get(), 2);
+    }
+
+    #[test]
+    fn merge_file_with_manifest() {
+        let (_temp_dir, path) =
+            write_file("[options]\njobs = 2\n\n[[rule]]\npath = \"foo.txt\"\nstate = \"hidden\"\n");
+        let mut settings = Settings::default();
+        settings.merge_file(&path).unwrap();
+        assert_eq!(settings.jobs.value.get(), 2);
+    }
+
+    #[test]
+    fn merge_file_when_file_does_not_exist() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut settings = Settings::default();
+        settings
+            .merge_file(&temp_dir.path().join(PROJECT_FILE_NAME))
+            .unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn merge_invalid_file() {
+        for contents in [
+            "[options]\nlog-level = \"loud\"\n",
+            "[options]\njobs = 0\n",
+            "[options]\nmechanism = \"dot\"\n",
+            "jobs = 2\n",
+        ] {
+            let (_temp_dir, path) = write_file(contents);
+            assert!(Settings::default().merge_file(&path).is_err());
+        }
+    }
+
+    #[test]
+    fn merge_env() {
+        let mut settings = Settings::default();
+        let mut errors = Vec::new();
+        settings.merge_env(
+            |name| match name {
+                LOG_LEVEL_VAR => Some("DEBUG".into()),
+                COMPANIONS_VAR => Some("{stem}.xmp,{stem}.json".into()),
+                _ => None,
+            },
+            &mut errors,
+        );
+        assert!(errors.is_empty());
+        assert_eq!(settings.log_level.value, LogLevel::Debug);
+        assert_eq!(settings.log_level.source, Source::Env(LOG_LEVEL_VAR));
+        assert_eq!(settings.jobs, Setting::new(NonZeroUsize::MIN));
+        assert_eq!(settings.companions.value, ["{stem}.xmp", "{stem}.json"]);
+        assert_eq!(settings.companions.source, Source::Env(COMPANIONS_VAR));
+    }
+
+    #[test]
+    fn merge_invalid_env() {
+        let mut settings = Settings::default();
+        let mut errors = Vec::new();
+        settings.merge_env(
+            |name| match name {
+                LOG_LEVEL_VAR => Some("loud".into()),
+                JOBS_VAR => Some("0".into()),
+                COMPANIONS_VAR => Some("{stem}.xmp".into()),
+                _ => None,
+            },
+            &mut errors,
+        );
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].to_string(),
+            format!("invalid value for {LOG_LEVEL_VAR}")
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            format!("invalid value for {JOBS_VAR}")
+        );
+        assert_eq!(settings.log_level, Setting::new(LogLevel::Info));
+        assert_eq!(settings.jobs, Setting::new(NonZeroUsize::MIN));
+        assert_eq!(settings.companions.value, ["{stem}.xmp"]);
+    }
+
+    #[test]
+    fn files_in_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join("foo/bar")).unwrap();
+        fs::write(temp_dir.join(PROJECT_FILE_NAME), "").unwrap();
+        fs::write(temp_dir.join("foo").join(PROJECT_FILE_NAME), "").unwrap();
+
+        let files = files(&temp_dir.join("foo/bar"));
+        assert_eq!(
+            files.last().unwrap(),
+            &temp_dir.join("foo").join(PROJECT_FILE_NAME)
+        );
+        assert!(!files.contains(&temp_dir.join(PROJECT_FILE_NAME)));
+    }
+
+    #[test]
+    fn display_settings() {
+        let mut settings = Settings::default();
+        settings
+            .jobs
+            .set(NonZeroUsize::new(8).unwrap(), Source::Env(JOBS_VAR));
+        settings.companions.set(
+            vec![String::from("{stem}.xmp")],
+            Source::File(PathBuf::from("hf.toml")),
+        );
+        assert_eq!(
+            settings.to_string(),
+            "[options]\nlog-level = \"INFO\" # default\njobs = 8 # HF_JOBS\ncompanions = [\"{stem}.xmp\"] # hf.toml\n"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    ffi::OsString,
+    io,
+    process::{self, ExitCode, ExitStatus},
+};
+
+/// Keeps this process alive while a command is running, so that the files can
+/// be restored after the command exits.
+///
+/// On Unix, `SIGHUP` and `SIGTERM` are forwarded to the command, and `SIGINT`
+/// and `SIGQUIT` are ignored because the terminal also sends them to the
+/// command. On Windows, `Ctrl+C` is ignored for the same reason.
+#[derive(Debug)]
+pub struct SignalGuard {
+    #[cfg(unix)]
+    signals: signal_hook::iterator::Signals,
+}
+
+impl SignalGuard {
+    /// Starts handling the signals.
+    pub fn new() -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            use signal_hook::consts::{SIGHUP, SIGINT, SIGQUIT, SIGTERM};
+
+            let signals = signal_hook::iterator::Signals::new([SIGHUP, SIGINT, SIGQUIT, SIGTERM])?;
+            Ok(Self { signals })
+        }
+        #[cfg(windows)]
+        {
+            ctrlc::set_handler(|| {}).map_err(io::Error::other)?;
+            Ok(Self {})
+        }
+    }
+
+    /// Runs `command` and waits for it to exit.
+    ///
+    /// The first element of `command` is the program, and the rest are its
+    /// arguments.
+    pub fn run(self, command: &[OsString]) -> io::Result<ExitStatus> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let mut child = process::Command::new(program).args(args).spawn()?;
+
+        #[cfg(unix)]
+        {
+            use rustix::process::{Pid, Signal, kill_process};
+            use signal_hook::consts::{SIGHUP, SIGTERM};
+
+            let pid = Pid::from_child(&child);
+            let mut signals = self.signals;
+            let handle = signals.handle();
+            let forwarder = std::thread::spawn(move || {
+                for signal in &mut signals {
+                    let signal = match signal {
+                        SIGHUP => Signal::HUP,
+                        SIGTERM => Signal::TERM,
+                        _ => continue,
+                    };
+                    let _ = kill_process(pid, signal);
+                }
+            });
+            let status = child.wait();
+            handle.close();
+            let _ = forwarder.join();
+            status
+        }
+        #[cfg(windows)]
+        child.wait()
+    }
+}
+
+/// Returns the exit code which reports `status` to the parent process.
+///
+/// On Unix, if the command was terminated by a signal, this is 128 plus the
+/// signal number as in shells.
+pub fn exit_code(status: ExitStatus) -> ExitCode {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return ExitCode::from(u8::try_from(128 + signal).unwrap_or(u8::MAX));
+    }
+    status
+        .code()
+        .and_then(|code| u8::try_from(code).ok())
+        .map_or(ExitCode::FAILURE, ExitCode::from)
+}
@@ -7,12 +7,18 @@
 
 mod app;
 mod cli;
+mod config;
+mod exec;
+mod progress;
+mod prompt;
+mod tui;
+mod watch;
 
 use std::{io, process::ExitCode};
 
 fn main() -> ExitCode {
     match app::run() {
-        Ok(()) => ExitCode::SUCCESS,
+        Ok(code) => code,
         Err(err) => {
             eprintln!("Error: {err:?}");
             if let Some(e) = err.downcast_ref::<io::Error>() {
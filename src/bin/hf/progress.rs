@@ -0,0 +1,95 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    fmt,
+    io::{self, IsTerminal},
+};
+
+use console::Term;
+use hf::batch::Progress;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+
+/// The template of the progress bar.
+const TEMPLATE: &str = "{prefix:>8} [{bar:30}] {pos}/{len} files{msg}, {rate}, ETA {eta}";
+
+/// A progress bar on standard error.
+#[derive(Debug)]
+pub struct Reporter {
+    bar: ProgressBar,
+}
+
+impl Reporter {
+    /// Creates a new `Reporter` which starts with the stage named `prefix`.
+    ///
+    /// If `enabled` is [`None`], the progress bar is shown only if standard
+    /// error is a terminal.
+    pub fn new(enabled: Option<bool>, prefix: &'static str) -> Self {
+        let bar = ProgressBar::hidden().with_style(
+            ProgressStyle::with_template(TEMPLATE)
+                .expect("the template should be valid")
+                .with_key("rate", |state: &ProgressState, w: &mut dyn fmt::Write| {
+                    let _ = write!(w, "{:.0}/s", state.per_sec());
+                })
+                .progress_chars("=> "),
+        );
+        bar.set_prefix(prefix);
+        match enabled {
+            // `ProgressDrawTarget::stderr` draws nothing unless standard error is
+            // a terminal, so the terminal is wrapped to draw anyway.
+            Some(true) => {
+                bar.set_draw_target(ProgressDrawTarget::term_like(Box::new(Term::stderr())));
+            }
+            None if io::stderr().is_terminal() => {
+                bar.set_draw_target(ProgressDrawTarget::stderr());
+            }
+            _ => {}
+        }
+        Self { bar }
+    }
+
+    /// Starts a new stage named `prefix`, such as `checking`.
+    pub fn start(&self, prefix: &'static str) {
+        self.bar.set_prefix(prefix);
+        self.bar.set_message("");
+        self.bar.reset();
+    }
+
+    /// Clears the progress bar until the next stage starts.
+    pub fn clear(&self) {
+        self.bar.finish_and_clear();
+    }
+
+    /// Updates the progress bar with `progress`.
+    pub fn update(&self, progress: Progress) {
+        self.bar.set_length(progress.total() as u64);
+        self.bar.set_position(progress.processed() as u64);
+        if progress.failed() > 0 {
+            self.bar
+                .set_message(format!(", {} failed", progress.failed()));
+        }
+    }
+
+    /// Adds `delta` to the total number of files.
+    pub fn inc_length(&self, delta: usize) {
+        self.bar.inc_length(delta as u64);
+    }
+
+    /// Counts a processed file.
+    pub fn inc(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Runs `f` with the progress bar hidden, so that log messages don't get
+    /// mixed with it.
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
+    }
+}
+
+impl Drop for Reporter {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+    }
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::io::{self, BufRead, IsTerminal, Stderr, StdinLock, Write};
+
+use anyhow::bail;
+
+/// The help message which is printed for an unknown answer.
+const HELP: &str = "\
+y - yes
+n - no
+a - yes to this and all the remaining
+q - quit; no to this and all the remaining
+? - print help
+";
+
+/// Asking the user whether to proceed with each file.
+#[derive(Debug)]
+pub struct Prompt<R, W> {
+    input: R,
+    output: W,
+    all: bool,
+    quit: bool,
+}
+
+impl Prompt<StdinLock<'static>, Stderr> {
+    /// Creates a new `Prompt` which reads the answers from standard input and
+    /// writes the questions to standard error.
+    ///
+    /// Returns [`Err`] if standard input is not a terminal, because nobody can
+    /// answer the questions.
+    pub fn stdin() -> anyhow::Result<Self> {
+        let stdin = io::stdin();
+        if !stdin.is_terminal() {
+            bail!("could not prompt because standard input is not a terminal");
+        }
+        Ok(Self::new(stdin.lock(), io::stderr()))
+    }
+}
+
+impl<R: BufRead, W: Write> Prompt<R, W> {
+    /// Creates a new `Prompt` which reads the answers from `input` and writes
+    /// the questions to `output`.
+    pub const fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            all: false,
+            quit: false,
+        }
+    }
+
+    /// Asks `question`, and returns whether the answer is yes.
+    ///
+    /// No question is asked once the answer has been `all` or `quit`. The end
+    /// of the input is treated as `quit`.
+    pub fn confirm(&mut self, question: &str) -> io::Result<bool> {
+        if self.quit {
+            return Ok(false);
+        }
+        if self.all {
+            return Ok(true);
+        }
+        loop {
+            write!(self.output, "{question} [y,n,a,q,?] ")?;
+            self.output.flush()?;
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer)? == 0 {
+                writeln!(self.output)?;
+                self.quit = true;
+                return Ok(false);
+            }
+            match answer.trim().to_lowercase().as_str() {
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                "a" | "all" => {
+                    self.all = true;
+                    return Ok(true);
+                }
+                "q" | "quit" => {
+                    self.quit = true;
+                    return Ok(false);
+                }
+                _ => write!(self.output, "{HELP}")?,
+            }
+        }
+    }
+
+    /// Returns `true` if the answer has been `quit`.
+    pub const fn is_quit(&self) -> bool {
+        self.quit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn confirm_all(input: &str, n: usize) -> (Vec<bool>, String) {
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(input.as_bytes(), &mut output);
+        let answers = (0..n)
+            .map(|i| prompt.confirm(&format!("hide {i}?")).unwrap())
+            .collect();
+        (answers, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn confirm() {
+        let (answers, output) = confirm_all("y\nn\nYes\nNO\n", 4);
+        assert_eq!(answers, [true, false, true, false]);
+        assert_eq!(
+            output,
+            "hide 0? [y,n,a,q,?] hide 1? [y,n,a,q,?] hide 2? [y,n,a,q,?] hide 3? [y,n,a,q,?] "
+        );
+    }
+
+    #[test]
+    fn confirm_with_all() {
+        let (answers, output) = confirm_all("n\na\n", 4);
+        assert_eq!(answers, [false, true, true, true]);
+        assert_eq!(output, "hide 0? [y,n,a,q,?] hide 1? [y,n,a,q,?] ");
+    }
+
+    #[test]
+    fn confirm_with_quit() {
+        let mut output = Vec::new();
+        let mut prompt = Prompt::new(b"y\nq\n".as_slice(), &mut output);
+        assert!(prompt.confirm("hide 0?").unwrap());
+        assert!(!prompt.is_quit());
+        assert!(!prompt.confirm("hide 1?").unwrap());
+        assert!(prompt.is_quit());
+        assert!(!prompt.confirm("hide 2?").unwrap());
+    }
+
+    #[test]
+    fn confirm_with_unknown_answer() {
+        let (answers, output) = confirm_all("\nfoo\n?\ny\n", 1);
+        assert_eq!(answers, [true]);
+        assert_eq!(
+            output,
+            format!(
+                "hide 0? [y,n,a,q,?] {HELP}hide 0? [y,n,a,q,?] {HELP}hide 0? [y,n,a,q,?] {HELP}hide 0? [y,n,a,q,?] "
+            )
+        );
+    }
+
+    #[test]
+    fn confirm_at_end_of_input() {
+        let (answers, output) = confirm_all("y\n", 3);
+        assert_eq!(answers, [true, false, false]);
+        assert_eq!(output, "hide 0? [y,n,a,q,?] hide 1? [y,n,a,q,?] \n");
+    }
+}
@@ -0,0 +1,575 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{self, Path, PathBuf},
+};
+
+use hf::{Companions, HiddenStatus, Plan, StatusReader};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState},
+};
+
+/// The key bindings which are shown while browsing.
+const BROWSE_HELP: &str =
+    "space mark  a mark all  t toggle  u undo  enter open  backspace up  r reload  q quit";
+
+/// The key bindings which are shown while previewing a plan.
+const PREVIEW_HELP: &str = "y apply  n cancel";
+
+/// An entry of the directory which is being browsed.
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    path: PathBuf,
+    is_dir: bool,
+    /// Whether the entry is hidden, and why, or the error which occurred
+    /// while reading them.
+    status: io::Result<(bool, HiddenStatus)>,
+    marked: bool,
+}
+
+impl Entry {
+    fn read(path: PathBuf, statuses: &mut StatusReader) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let is_dir = path.is_dir();
+        let status =
+            hf::is_hidden(&path).and_then(|hidden| Ok((hidden, statuses.hidden_status(&path)?)));
+        Self {
+            name,
+            path,
+            is_dir,
+            status,
+            marked: false,
+        }
+    }
+
+    /// Returns [`true`] if this entry is hidden.
+    fn is_hidden(&self) -> bool {
+        self.status.as_ref().is_ok_and(|(hidden, _)| *hidden)
+    }
+
+    /// Returns all the reasons why this entry is hidden, including those which
+    /// toggling doesn't change, such as being listed in a `.hidden` file.
+    fn reason(&self) -> String {
+        let status = match &self.status {
+            Ok((_, status)) => status,
+            Err(err) => return format!("could not be read: {err}"),
+        };
+        status
+            .reasons()
+            .iter()
+            .fold(String::new(), |mut reason, r| {
+                if !reason.is_empty() {
+                    reason.push_str("; ");
+                }
+                let _ = write!(reason, "{r}");
+                reason
+            })
+    }
+}
+
+/// A keyboard-driven file browser which hides and shows the entries of a
+/// directory.
+///
+/// Toggling the entries makes a [`Plan`], which is previewed before it is
+/// executed. The executed plans are kept, so that they can be undone in the
+/// reverse order.
+#[derive(Debug)]
+pub struct Browser {
+    dir: PathBuf,
+    entries: Vec<Entry>,
+    table: TableState,
+    companions: Companions,
+    preview: Option<Plan>,
+    history: Vec<Plan>,
+    message: Option<String>,
+    quit: bool,
+}
+
+impl Browser {
+    /// Creates a new `Browser` which shows the entries of `dir`.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let mut browser = Self {
+            dir: path::absolute(dir)?,
+            entries: Vec::new(),
+            table: TableState::default(),
+            companions: Companions::default(),
+            preview: None,
+            history: Vec::new(),
+            message: None,
+            quit: false,
+        };
+        browser.reload()?;
+        Ok(browser)
+    }
+
+    /// Draws and handles the key events until the user quits.
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
+        while !self.is_quit() {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                self.handle(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a key event.
+    pub fn handle(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        self.message = None;
+        if self.preview.is_some() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => self.apply(),
+                KeyCode::Char('n' | 'q') | KeyCode::Esc => self.preview = None,
+                _ => {}
+            }
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('j') | KeyCode::Down => self.table.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.table.select_previous(),
+            KeyCode::Char('g') | KeyCode::Home => self.table.select_first(),
+            KeyCode::Char('G') | KeyCode::End => self.table.select_last(),
+            KeyCode::Char(' ') => {
+                if let Some(entry) = self.selected_mut() {
+                    entry.marked = !entry.marked;
+                    self.table.select_next();
+                }
+            }
+            KeyCode::Char('a') => {
+                let marked = !self.entries.iter().all(|e| e.marked);
+                for entry in &mut self.entries {
+                    entry.marked = marked;
+                }
+            }
+            KeyCode::Char('t') => self.toggle(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('l') | KeyCode::Enter | KeyCode::Right => {
+                if let Some(entry) = self.selected().filter(|e| e.is_dir) {
+                    let dir = entry.path.clone();
+                    self.change_dir(dir);
+                }
+            }
+            KeyCode::Char('h') | KeyCode::Backspace | KeyCode::Left => {
+                if let Some(dir) = self.dir.parent() {
+                    let dir = dir.to_path_buf();
+                    self.change_dir(dir);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Draws the entries, or the plan which is being previewed.
+    pub fn draw(&mut self, frame: &mut Frame<'_>) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+
+        let header = Row::new(["", "Name", "State", "Reason"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.entries.iter().map(|entry| {
+            let name = if entry.is_dir {
+                format!("{}/", entry.name)
+            } else {
+                entry.name.clone()
+            };
+            let state = match entry.status {
+                Ok((true, _)) => "hidden",
+                Ok((false, _)) => "visible",
+                Err(_) => "error",
+            };
+            let row = Row::new([
+                Cell::from(if entry.marked { "*" } else { " " }),
+                Cell::from(name),
+                Cell::from(state),
+                Cell::from(entry.reason()),
+            ]);
+            if entry.is_hidden() {
+                row.style(Style::new().fg(Color::DarkGray))
+            } else {
+                row
+            }
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Length(7),
+                Constraint::Fill(2),
+            ],
+        )
+        .header(header)
+        .block(Block::bordered().title(format!(" {} ", self.dir.display())))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, main, &mut self.table);
+
+        let help = if self.preview.is_some() {
+            PREVIEW_HELP
+        } else {
+            BROWSE_HELP
+        };
+        frame.render_widget(
+            Paragraph::new(self.message.as_deref().unwrap_or(help)),
+            footer,
+        );
+
+        if let Some(plan) = &self.preview {
+            let lines = plan
+                .steps()
+                .iter()
+                .map(|step| {
+                    Line::from(format!(
+                        "{} {} -> {}",
+                        if step.is_hide() { "hide" } else { "show" },
+                        self.relative(step.path()).display(),
+                        self.relative(step.dest()).display()
+                    ))
+                })
+                .collect::<Vec<_>>();
+            let height = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+            let area = popup(main, height.saturating_add(2));
+            frame.render_widget(Clear, area);
+            frame.render_widget(
+                Paragraph::new(lines).block(Block::bordered().title(" Plan ")),
+                area,
+            );
+        }
+    }
+
+    /// Returns [`true`] if the user has quit.
+    pub const fn is_quit(&self) -> bool {
+        self.quit
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        self.table.selected().and_then(|i| self.entries.get(i))
+    }
+
+    fn selected_mut(&mut self) -> Option<&mut Entry> {
+        self.table.selected().and_then(|i| self.entries.get_mut(i))
+    }
+
+    /// Makes a plan which toggles the marked entries, or the selected entry if
+    /// no entries are marked, and previews it.
+    fn toggle(&mut self) {
+        let mut targets = self.entries.iter().filter(|e| e.marked).collect::<Vec<_>>();
+        if targets.is_empty() {
+            targets.extend(self.selected());
+        }
+        if targets.is_empty() {
+            return;
+        }
+        let mut plan = Plan::new();
+        for entry in targets {
+            let result = if entry.is_hidden() {
+                plan.show(&entry.path, &self.companions)
+            } else {
+                plan.hide(&entry.path, &self.companions)
+            };
+            if let Err(err) = result {
+                self.message = Some(format!(
+                    "could not {} {}: {err}",
+                    if entry.is_hidden() { "show" } else { "hide" },
+                    entry.name
+                ));
+                return;
+            }
+        }
+        self.preview = Some(plan);
+    }
+
+    /// Executes the plan which is being previewed.
+    fn apply(&mut self) {
+        let Some(plan) = self.preview.take() else {
+            return;
+        };
+        match plan.execute() {
+            Ok(()) => {
+                self.message = Some(format!("{} file(s) have been renamed", plan.steps().len()));
+                self.history.push(plan);
+            }
+            Err(err) => {
+                self.message = Some(format!(
+                    "could not apply the plan, so the changes have been rolled back: {err}"
+                ));
+            }
+        }
+        self.refresh();
+    }
+
+    /// Reverts the plan which was executed last.
+    fn undo(&mut self) {
+        let Some(plan) = self.history.pop() else {
+            self.message = Some("nothing to undo".into());
+            return;
+        };
+        self.message = Some(match plan.revert() {
+            Ok(()) => format!("{} file(s) have been restored", plan.steps().len()),
+            Err(err) => format!("could not undo the changes: {err}"),
+        });
+        self.refresh();
+    }
+
+    fn change_dir(&mut self, dir: PathBuf) {
+        let prev = std::mem::replace(&mut self.dir, dir);
+        if let Err(err) = self.reload() {
+            self.message = Some(format!("could not open {}: {err}", self.dir.display()));
+            self.dir = prev;
+            self.refresh();
+            return;
+        }
+        // Returning to the parent selects the directory which was left.
+        if let Some(i) = self.entries.iter().position(|e| e.path == prev) {
+            self.table.select(Some(i));
+        }
+    }
+
+    /// Reloads the entries, and reports an error in the message line.
+    fn refresh(&mut self) {
+        if let Err(err) = self.reload() {
+            self.message = Some(format!("could not read {}: {err}", self.dir.display()));
+        }
+    }
+
+    /// Reads the entries of the directory.
+    ///
+    /// The entries are sorted by their names without the leading `.`, so that
+    /// toggling an entry doesn't move it. An entry whose status could not be
+    /// read is shown with the error, and an entry which could not be listed at
+    /// all is skipped.
+    fn reload(&mut self) -> io::Result<()> {
+        let mut statuses = StatusReader::new();
+        let mut entries = fs::read_dir(&self.dir)?
+            .filter_map(Result::ok)
+            .map(|entry| Entry::read(entry.path(), &mut statuses))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| {
+            (a.name.trim_start_matches('.'), &a.name)
+                .cmp(&(b.name.trim_start_matches('.'), &b.name))
+        });
+        let selected = self.table.selected().unwrap_or_default();
+        self.entries = entries;
+        self.table.select(if self.entries.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.entries.len() - 1))
+        });
+        Ok(())
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.dir).unwrap_or(path)
+    }
+}
+
+/// Returns the area of a popup which is centered in `area`.
+fn popup(area: Rect, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+
+    fn press(browser: &mut Browser, keys: &str) {
+        for c in keys.chars() {
+            browser.handle(KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    fn render(browser: &mut Browser) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal.draw(|frame| browser.draw(frame)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(80)
+            .map(|line| {
+                line.iter()
+                    .map(ratatui::buffer::Cell::symbol)
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    fn temp_dir() -> tempfile::TempDir {
+        tempfile::Builder::new().prefix("hf").tempdir().unwrap()
+    }
+
+    #[test]
+    fn draw() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        fs::create_dir(temp_dir.join(".bar")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        let screen = render(&mut browser);
+        assert!(screen.contains(&temp_dir.display().to_string()));
+        let bar = screen.lines().position(|l| l.contains(".bar/")).unwrap();
+        let foo = screen.lines().position(|l| l.contains("foo.txt")).unwrap();
+        assert!(bar < foo);
+        assert!(screen.lines().nth(bar).unwrap().contains("hidden"));
+        assert!(screen.lines().nth(bar).unwrap().contains("starts with"));
+        assert!(screen.lines().nth(foo).unwrap().contains("visible"));
+        assert!(screen.contains(&BROWSE_HELP[..20]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn listed_in_hidden_file() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        fs::write(temp_dir.join(".hidden"), "foo.txt\n").unwrap();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        let screen = render(&mut browser);
+        let foo = screen.lines().find(|l| l.contains("foo.txt")).unwrap();
+        assert!(foo.contains("visible"));
+        assert!(foo.contains("listed in"));
+
+        press(&mut browser, "t");
+        assert!(render(&mut browser).contains("hide foo.txt -> .foo.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn show_entries_which_could_not_be_read() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        // A `.hidden` directory can't be read as a `.hidden` file.
+        fs::create_dir(temp_dir.join(".hidden")).unwrap();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        let screen = render(&mut browser);
+        let foo = screen.lines().find(|l| l.contains("foo.txt")).unwrap();
+        assert!(foo.contains("error"));
+        assert!(foo.contains("could not be read"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn toggle_and_undo() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        File::create(temp_dir.join(".bar.txt")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        press(&mut browser, "jt");
+        let screen = render(&mut browser);
+        assert!(screen.contains("hide foo.txt -> .foo.txt"));
+        assert!(screen.contains(PREVIEW_HELP));
+        assert!(temp_dir.join("foo.txt").exists());
+
+        press(&mut browser, "y");
+        assert!(temp_dir.join(".foo.txt").exists());
+        assert!(render(&mut browser).contains("1 file(s) have been renamed"));
+
+        press(&mut browser, "u");
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(!temp_dir.join(".foo.txt").exists());
+        assert!(render(&mut browser).contains("1 file(s) have been restored"));
+
+        press(&mut browser, "u");
+        assert!(render(&mut browser).contains("nothing to undo"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn toggle_marked_entries() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        File::create(temp_dir.join(".bar.txt")).unwrap();
+        File::create(temp_dir.join("baz.txt")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        press(&mut browser, "  ");
+        let screen = render(&mut browser);
+        assert_eq!(screen.matches('*').count(), 2);
+
+        press(&mut browser, "t");
+        let screen = render(&mut browser);
+        assert!(screen.contains("show .bar.txt -> bar.txt"));
+        assert!(screen.contains("hide baz.txt -> .baz.txt"));
+        assert!(!screen.contains("foo.txt ->"));
+
+        press(&mut browser, "y");
+        assert!(temp_dir.join("bar.txt").exists());
+        assert!(temp_dir.join(".baz.txt").exists());
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(!render(&mut browser).contains('*'));
+
+        press(&mut browser, "u");
+        assert!(temp_dir.join(".bar.txt").exists());
+        assert!(temp_dir.join("baz.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cancel_preview() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        press(&mut browser, "tn");
+        assert!(!render(&mut browser).contains("->"));
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(!browser.is_quit());
+
+        press(&mut browser, "q");
+        assert!(browser.is_quit());
+    }
+
+    #[test]
+    fn change_dir() {
+        let temp_dir = temp_dir();
+        let temp_dir = temp_dir.path();
+        fs::create_dir(temp_dir.join("foo")).unwrap();
+        File::create(temp_dir.join("foo/bar.txt")).unwrap();
+
+        let mut browser = Browser::open(temp_dir).unwrap();
+        browser.handle(KeyEvent::from(KeyCode::Enter));
+        assert!(render(&mut browser).contains("bar.txt"));
+
+        browser.handle(KeyEvent::from(KeyCode::Backspace));
+        let screen = render(&mut browser);
+        assert!(screen.contains("foo/"));
+        assert!(!screen.contains("bar.txt"));
+    }
+}
@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use hf::{
+    Companions, Plan, Walk, WalkEntry,
+    manifest::{Manifest, State},
+};
+use log::{info, warn};
+use notify::{
+    Event, EventKind, RecursiveMode, Watcher,
+    event::{ModifyKind, RenameMode},
+};
+
+/// Applying rules to the entries of a directory as they are created or
+/// renamed.
+#[derive(Debug)]
+pub struct Enforcer {
+    root: PathBuf,
+    canonical_root: PathBuf,
+    manifest: Manifest,
+    recursive: bool,
+    debounce: Duration,
+    companions: Companions,
+    renamed: HashMap<PathBuf, Instant>,
+}
+
+impl Enforcer {
+    /// Creates a new `Enforcer` for the entries of `root`.
+    pub fn new(
+        root: impl Into<PathBuf>,
+        manifest: Manifest,
+        recursive: bool,
+        debounce: Duration,
+    ) -> io::Result<Self> {
+        let root = root.into();
+        let canonical_root = fs::canonicalize(&root)?;
+        Ok(Self {
+            root,
+            canonical_root,
+            manifest,
+            recursive,
+            debounce,
+            companions: Companions::default(),
+            renamed: HashMap::new(),
+        })
+    }
+
+    /// Applies the rules to the existing entries.
+    pub fn scan(&mut self) -> anyhow::Result<()> {
+        let max_depth = if self.recursive { usize::MAX } else { 1 };
+        let paths = Walk::new(&self.root)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .map(|entry| entry.map(WalkEntry::into_path))
+            .collect::<io::Result<Vec<_>>>()
+            .with_context(|| format!("could not read {}", self.root.display()))?;
+        self.apply(paths);
+        Ok(())
+    }
+
+    /// Applies the rules to the entries whenever they are created or renamed.
+    ///
+    /// This never returns unless an error occurs.
+    pub fn watch(&mut self) -> anyhow::Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).context("could not create a watcher")?;
+        let mode = if self.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(&self.root, mode)
+            .with_context(|| format!("could not watch {}", self.root.display()))?;
+        info!("watching {}", self.root.display());
+
+        while let Ok(event) = rx.recv() {
+            let mut paths = Vec::new();
+            self.collect(event, &mut paths);
+            // Wait until the events stop arriving, so that an entry which is
+            // still being written is handled only once.
+            loop {
+                match rx.recv_timeout(self.debounce) {
+                    Ok(event) => self.collect(event, &mut paths),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                }
+            }
+            self.apply(paths);
+        }
+        Ok(())
+    }
+
+    /// Adds the paths of the entries which have been created or renamed by
+    /// `event` to `paths`.
+    fn collect(&self, event: notify::Result<Event>, paths: &mut Vec<PathBuf>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                warn!("{err}");
+                return;
+            }
+        };
+        match event.kind {
+            EventKind::Create(_)
+            | EventKind::Modify(ModifyKind::Name(
+                RenameMode::To | RenameMode::Any | RenameMode::Other,
+            )) => paths.extend(event.paths),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                paths.extend(event.paths.into_iter().last());
+            }
+            _ => return,
+        }
+        // The entries of a directory which has been moved into the watched
+        // directory don't generate their own events.
+        if self.recursive {
+            let dirs = paths
+                .iter()
+                .filter(|p| p.is_dir() && !p.is_symlink())
+                .cloned()
+                .collect::<Vec<_>>();
+            for dir in dirs {
+                paths.extend(
+                    Walk::new(dir)
+                        .min_depth(1)
+                        .filter_map(Result::ok)
+                        .map(WalkEntry::into_path),
+                );
+            }
+        }
+    }
+
+    /// Hides or shows the entries at `paths` according to the rules.
+    ///
+    /// An error for one entry is logged, and doesn't prevent the others from
+    /// being handled.
+    fn apply(&mut self, mut paths: Vec<PathBuf>) {
+        // The entries which have just been renamed by this will generate events
+        // shortly, so they are ignored for a while to avoid handling them again.
+        let now = Instant::now();
+        let quiet = self.debounce * 2 + Duration::from_secs(1);
+        self.renamed.retain(|_, t| now.duration_since(*t) < quiet);
+
+        // The entries of a directory must come before the directory itself,
+        // because renaming the directory changes their paths.
+        paths.sort_unstable();
+        paths.dedup();
+        for path in paths.into_iter().rev() {
+            if self.renamed.contains_key(&path) || path.symlink_metadata().is_err() {
+                continue;
+            }
+            let Some(state) = self.state(&path) else {
+                continue;
+            };
+            if let Err(err) = self.enforce(&path, state) {
+                warn!("{err:#}");
+            }
+        }
+    }
+
+    /// Returns the state which the entry at `path` must be in, or [`None`] if
+    /// no rule matches it.
+    fn state(&self, path: &Path) -> Option<State> {
+        let relative_path = path
+            .strip_prefix(&self.root)
+            .or_else(|_| path.strip_prefix(&self.canonical_root))
+            .ok()?;
+        let depth = relative_path.components().count();
+        if depth == 0
+            || (!self.recursive && depth > 1)
+            || relative_path.components().any(|c| c.as_os_str() == ".git")
+        {
+            return None;
+        }
+        self.manifest.state(relative_path)
+    }
+
+    /// Hides or shows the entry at `path` if it is not in `state`.
+    fn enforce(&mut self, path: &Path, state: State) -> anyhow::Result<()> {
+        let hidden = state == State::Hidden;
+        if hf::is_hidden(path).with_context(|| format!("could not check {}", path.display()))?
+            == hidden
+        {
+            return Ok(());
+        }
+
+        let verb = if hidden { "hide" } else { "show" };
+        let mut plan = Plan::new();
+        if hidden {
+            plan.hide(path, &self.companions)
+        } else {
+            plan.show(path, &self.companions)
+        }
+        .and_then(|()| plan.execute())
+        .with_context(|| format!("could not {verb} {}", path.display()))?;
+
+        let now = Instant::now();
+        for step in plan.steps() {
+            self.renamed.insert(step.dest().to_path_buf(), now);
+            if step.is_companion() && step.path() != step.dest() {
+                info!(
+                    "{} has been renamed to {}",
+                    step.path().display(),
+                    step.dest().display()
+                );
+            } else if step.is_hide() {
+                info!("{} has been hidden", step.path().display());
+            } else {
+                info!("{} has been shown", step.path().display());
+            }
+        }
+        Ok(())
+    }
+}
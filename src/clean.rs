@@ -0,0 +1,327 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Finding and removing well-known junk files.
+//!
+//! Operating systems and desktop environments leave hidden metadata files,
+//! such as `.DS_Store` and `Thumbs.db`, in the directories they visit. This
+//! module finds such files by matching their names against a [`Catalogue`] of
+//! patterns.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::fs::File;
+//!
+//! use hf::clean::Catalogue;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! let temp_dir = temp_dir.path();
+//! File::create(temp_dir.join(".DS_Store")).unwrap();
+//! File::create(temp_dir.join("foo.txt")).unwrap();
+//!
+//! let junk = Catalogue::default().find(temp_dir, false).unwrap();
+//! assert_eq!(junk, [temp_dir.join(".DS_Store")]);
+//!
+//! for path in junk {
+//!     hf::clean::remove(path).unwrap();
+//! }
+//! assert!(!temp_dir.join(".DS_Store").exists());
+//! ```
+
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::Walk;
+
+/// The built-in patterns of junk files.
+const BUILTIN_PATTERNS: [&str; 6] = [
+    ".DS_Store",
+    "._*",
+    "Thumbs.db",
+    "desktop.ini",
+    ".directory",
+    ".Trash-*/",
+];
+
+/// A catalogue of file name patterns which identify junk files.
+///
+/// A pattern is matched against the whole file name. `*` in a pattern matches
+/// any sequence of characters, including an empty one. Other characters match
+/// themselves. A pattern ending with `/` only matches directories, and any
+/// other pattern only matches files which are not directories, so that a
+/// directory which happens to be named like a junk file is left alone.
+///
+/// The default catalogue contains the following patterns:
+///
+/// - `.DS_Store` (macOS Finder)
+/// - `._*` (AppleDouble files created by macOS)
+/// - `Thumbs.db` (Windows Explorer)
+/// - `desktop.ini` (Windows Explorer)
+/// - `.directory` (KDE Dolphin)
+/// - `.Trash-*/` (trash directories of removable media created by desktop
+///   environments on Linux)
+#[allow(clippy::doc_markdown)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Catalogue {
+    patterns: Vec<String>,
+}
+
+impl Catalogue {
+    /// Creates a new `Catalogue` without any patterns.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Adds a pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use hf::clean::Catalogue;
+    ///
+    /// let catalogue = Catalogue::new().pattern("*.swp").pattern("build/");
+    /// assert!(catalogue.matches(".foo.txt.swp", false));
+    /// assert!(!catalogue.matches(".DS_Store", false));
+    /// assert!(catalogue.matches("build", true));
+    /// assert!(!catalogue.matches("build", false));
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// Returns the patterns.
+    #[must_use]
+    #[inline]
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns [`true`] if `file_name` matches any pattern.
+    ///
+    /// `is_dir` tells whether the file is a directory. Patterns ending with `/`
+    /// are only checked for directories, and the other patterns are only
+    /// checked for files which are not directories.
+    #[must_use]
+    #[inline]
+    pub fn matches(&self, file_name: impl AsRef<OsStr>, is_dir: bool) -> bool {
+        let inner = |file_name: &OsStr| -> bool {
+            let file_name = file_name.as_encoded_bytes();
+            self.patterns.iter().any(|p| {
+                let (p, for_dir) = p
+                    .strip_suffix('/')
+                    .map_or((p.as_str(), false), |p| (p, true));
+                for_dir == is_dir && matches(p.as_bytes(), file_name)
+            })
+        };
+        inner(file_name.as_ref())
+    }
+
+    /// Returns the paths of the junk files in `dir`.
+    ///
+    /// If `recursive` is [`true`], the subdirectories are searched as well.
+    /// Junk directories are returned as a whole without searching their
+    /// contents, and symbolic links are not followed. The paths are sorted by
+    /// file name in each directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurred while walking `dir`.
+    #[inline]
+    pub fn find(&self, dir: impl AsRef<Path>, recursive: bool) -> io::Result<Vec<PathBuf>> {
+        let inner = |dir: &Path| -> io::Result<Vec<PathBuf>> {
+            let max_depth = if recursive { usize::MAX } else { 1 };
+            let mut junk = Vec::<PathBuf>::new();
+            for entry in Walk::new(dir)
+                .min_depth(1)
+                .max_depth(max_depth)
+                .sort_by_file_name(true)
+            {
+                let entry = entry?;
+                if junk.last().is_some_and(|j| entry.path().starts_with(j)) {
+                    continue;
+                }
+                if self.matches(entry.file_name(), entry.file_type().is_dir()) {
+                    junk.push(entry.into_path());
+                }
+            }
+            Ok(junk)
+        };
+        inner(dir.as_ref())
+    }
+}
+
+impl Default for Catalogue {
+    #[inline]
+    fn default() -> Self {
+        BUILTIN_PATTERNS
+            .into_iter()
+            .fold(Self::new(), Self::pattern)
+    }
+}
+
+/// Removes a junk file or directory.
+///
+/// Directories are removed with all their contents. Symbolic links are removed
+/// without touching their targets.
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`std::fs::remove_file`] or [`std::fs::remove_dir_all`]
+/// returns an error.
+#[inline]
+pub fn remove(path: impl AsRef<Path>) -> io::Result<()> {
+    let inner = |path: &Path| -> io::Result<()> {
+        if fs::symlink_metadata(path)?.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        }
+    };
+    inner(path.as_ref())
+}
+
+/// Returns [`true`] if `name` matches `pattern`.
+///
+/// This remembers only the last `*` and backtracks to it on a mismatch, so it
+/// runs in O(n·m) time even for patterns with many `*`.
+fn matches(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // The position after the last `*` in `pattern`, and the position in
+    // `name` which it is currently matched up to.
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some(&c) if c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => {
+                let Some((star_p, star_n)) = star else {
+                    return false;
+                };
+                p = star_p;
+                n = star_n + 1;
+                star = Some((star_p, n));
+            }
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn matches() {
+        assert!(super::matches(b".DS_Store", b".DS_Store"));
+        assert!(!super::matches(b".DS_Store", b".DS_Store~"));
+        assert!(super::matches(b"._*", b"._foo.txt"));
+        assert!(super::matches(b"._*", b"._"));
+        assert!(!super::matches(b"._*", b"foo._"));
+        assert!(super::matches(b"*.swp", b".foo.swp"));
+        assert!(super::matches(b"a*b*c", b"aXbYbZc"));
+        assert!(!super::matches(b"a*b*c", b"aXbYbZ"));
+        assert!(super::matches(b"*", b""));
+        assert!(super::matches(b"**", b"foo"));
+        assert!(super::matches(b"*a", b"aaa"));
+        assert!(!super::matches(b"a*", b""));
+    }
+
+    #[test]
+    fn matches_without_exponential_backtracking() {
+        let name = "a".repeat(1000);
+        assert!(!super::matches(b"*a*a*a*a*a*a*b", name.as_bytes()));
+        assert!(super::matches(b"*a*a*a*a*a*a*", name.as_bytes()));
+    }
+
+    #[test]
+    fn default_catalogue() {
+        let catalogue = Catalogue::default();
+        for name in [
+            ".DS_Store",
+            "._foo.txt",
+            "Thumbs.db",
+            "desktop.ini",
+            ".directory",
+        ] {
+            assert!(catalogue.matches(name, false), "{name}");
+            assert!(!catalogue.matches(name, true), "{name}");
+        }
+        assert!(catalogue.matches(".Trash-1000", true));
+        assert!(!catalogue.matches(".Trash-1000", false));
+        for name in ["foo.txt", ".bashrc", "thumbs.db.bak", ".directory.txt"] {
+            assert!(!catalogue.matches(name, false), "{name}");
+            assert!(!catalogue.matches(name, true), "{name}");
+        }
+        assert!(Catalogue::new().patterns().is_empty());
+    }
+
+    #[test]
+    fn find_and_remove() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join("foo/.Trash-1000/files")).unwrap();
+        File::create(temp_dir.join("foo/.Trash-1000/files/.DS_Store")).unwrap();
+        File::create(temp_dir.join("foo/Thumbs.db")).unwrap();
+        File::create(temp_dir.join("foo/bar.txt")).unwrap();
+        File::create(temp_dir.join(".DS_Store")).unwrap();
+
+        let catalogue = Catalogue::default();
+        assert_eq!(
+            catalogue.find(temp_dir, false).unwrap(),
+            [temp_dir.join(".DS_Store")]
+        );
+        let junk = catalogue.find(temp_dir, true).unwrap();
+        assert_eq!(
+            junk,
+            [
+                temp_dir.join(".DS_Store"),
+                temp_dir.join("foo/.Trash-1000"),
+                temp_dir.join("foo/Thumbs.db")
+            ]
+        );
+
+        for path in junk {
+            remove(path).unwrap();
+        }
+        assert!(catalogue.find(temp_dir, true).unwrap().is_empty());
+        assert!(temp_dir.join("foo/bar.txt").exists());
+    }
+
+    #[test]
+    fn find_only_directories_for_directory_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join("._keep/.DS_Store")).unwrap();
+        File::create(temp_dir.join("._keep/foo.txt")).unwrap();
+        File::create(temp_dir.join(".Trash-1000")).unwrap();
+
+        let catalogue = Catalogue::default();
+        assert!(catalogue.find(temp_dir, true).unwrap().is_empty());
+
+        let catalogue = Catalogue::new().pattern("._*/");
+        assert_eq!(
+            catalogue.find(temp_dir, true).unwrap(),
+            [temp_dir.join("._keep")]
+        );
+    }
+}
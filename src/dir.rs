@@ -0,0 +1,459 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A handle to an opened directory.
+
+use std::{
+    ffi::{OsStr, OsString},
+    fmt,
+    io::{self, Error, ErrorKind},
+    os::{
+        fd::{AsFd, BorrowedFd, OwnedFd},
+        unix::ffi::{OsStrExt, OsStringExt},
+    },
+    path::Path,
+};
+
+use rustix::fs::{self as rfs, AtFlags, Mode, OFlags};
+
+use crate::platform::imp;
+
+/// A handle to an opened directory.
+///
+/// Unlike [`hf::hide`](crate::hide) and [`hf::show`](crate::show), which
+/// resolve the whole path every time, the methods of this type operate on the
+/// entries relative to the opened directory using `openat`, `renameat` and
+/// `fstatat`. Therefore, a concurrent rename of a parent directory or a
+/// replacement of a parent directory with a symbolic link cannot redirect the
+/// operation to another location.
+///
+/// The entries are specified by their file name, which must not contain `/`
+/// and must not be `.` or `..`.
+///
+/// This is only available on Unix.
+///
+/// # Examples
+///
+/// ```
+/// use std::fs::File;
+///
+/// use hf::Dir;
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// File::create(temp_dir.path().join("foo.txt")).unwrap();
+///
+/// let dir = Dir::open(temp_dir.path()).unwrap();
+/// assert_eq!(dir.is_hidden("foo.txt").unwrap(), false);
+///
+/// dir.hide("foo.txt").unwrap();
+/// assert!(!temp_dir.path().join("foo.txt").exists());
+/// assert!(temp_dir.path().join(".foo.txt").exists());
+/// assert_eq!(dir.is_hidden(".foo.txt").unwrap(), true);
+///
+/// dir.show(".foo.txt").unwrap();
+/// assert!(temp_dir.path().join("foo.txt").exists());
+/// assert!(!temp_dir.path().join(".foo.txt").exists());
+/// ```
+pub struct Dir {
+    fd: OwnedFd,
+}
+
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` does not exist or is not a directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hf::Dir;
+    /// #
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// assert!(Dir::open(temp_dir.path()).is_ok());
+    /// assert!(Dir::open(temp_dir.path().join("foo")).is_err());
+    /// ```
+    #[inline]
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let inner = |path: &Path| -> io::Result<Self> {
+            let fd = rfs::openat(
+                rfs::CWD,
+                path,
+                OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
+                Mode::empty(),
+            )?;
+            Ok(Self { fd })
+        };
+        inner(path.as_ref())
+    }
+
+    /// Opens the directory at `path` only for operating on its entries.
+    ///
+    /// On Linux and Android, this uses `O_PATH`, so unlike [`Dir::open`], only
+    /// search permission on the directory is required, as with
+    /// [`std::fs::rename`]. The handle cannot be used to read the directory.
+    pub(crate) fn open_path(path: &Path) -> io::Result<Self> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let access = OFlags::PATH;
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let access = OFlags::RDONLY;
+        let fd = rfs::openat(
+            rfs::CWD,
+            path,
+            access | OFlags::DIRECTORY | OFlags::CLOEXEC,
+            Mode::empty(),
+        )?;
+        Ok(Self { fd })
+    }
+
+    /// Opens the subdirectory `name` of this directory.
+    ///
+    /// This does not follow symbolic links, so `name` cannot be replaced with
+    /// a symbolic link to another directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `name` is not a valid file name.
+    /// - `name` does not exist.
+    /// - `name` is not a directory or is a symbolic link.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs;
+    /// #
+    /// # use hf::Dir;
+    /// #
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// fs::create_dir(temp_dir.path().join("foo")).unwrap();
+    ///
+    /// let dir = Dir::open(temp_dir.path()).unwrap();
+    /// let sub_dir = dir.open_dir("foo").unwrap();
+    /// assert!(dir.open_dir("bar").is_err());
+    /// ```
+    #[inline]
+    pub fn open_dir(&self, name: impl AsRef<OsStr>) -> io::Result<Self> {
+        let inner = |name: &OsStr| -> io::Result<Self> {
+            let name = check_file_name(name)?;
+            let fd = rfs::openat(
+                &self.fd,
+                name,
+                OFlags::RDONLY | OFlags::DIRECTORY | OFlags::NOFOLLOW | OFlags::CLOEXEC,
+                Mode::empty(),
+            )?;
+            Ok(Self { fd })
+        };
+        inner(name.as_ref())
+    }
+
+    /// Returns [`true`] if the entry `name` of this directory is a hidden
+    /// file or directory.
+    ///
+    /// Unlike [`hf::is_hidden`](crate::is_hidden), this checks that the entry
+    /// exists. Symbolic links are not followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `name` is not a valid file name or does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// #
+    /// # use hf::Dir;
+    /// #
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// File::create(temp_dir.path().join(".foo.txt")).unwrap();
+    /// File::create(temp_dir.path().join("bar.txt")).unwrap();
+    ///
+    /// let dir = Dir::open(temp_dir.path()).unwrap();
+    /// assert_eq!(dir.is_hidden(".foo.txt").unwrap(), true);
+    /// assert_eq!(dir.is_hidden("bar.txt").unwrap(), false);
+    /// assert!(dir.is_hidden("baz.txt").is_err());
+    /// ```
+    #[inline]
+    pub fn is_hidden(&self, name: impl AsRef<OsStr>) -> io::Result<bool> {
+        let inner = |name: &OsStr| -> io::Result<bool> {
+            let name = check_file_name(name)?;
+            rfs::statat(&self.fd, name, AtFlags::SYMLINK_NOFOLLOW)?;
+            Ok(imp::is_hidden_name(name))
+        };
+        inner(name.as_ref())
+    }
+
+    /// Hides the entry `name` of this directory.
+    ///
+    /// The entry is renamed to the name with a leading `.` within this
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `name` is not a valid file name.
+    /// - `name` is already hidden.
+    /// - `renameat` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// #
+    /// # use hf::Dir;
+    /// #
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// File::create(temp_dir.path().join("foo.txt")).unwrap();
+    ///
+    /// let dir = Dir::open(temp_dir.path()).unwrap();
+    /// dir.hide("foo.txt").unwrap();
+    /// assert!(temp_dir.path().join(".foo.txt").exists());
+    /// ```
+    #[inline]
+    pub fn hide(&self, name: impl AsRef<OsStr>) -> io::Result<()> {
+        let inner = |name: &OsStr| -> io::Result<()> {
+            let name = check_file_name(name)?;
+            if imp::is_hidden_name(name) {
+                return Err(ErrorKind::InvalidInput.into());
+            }
+            let mut dest = OsString::from(".");
+            dest.push(name);
+            rfs::renameat(&self.fd, name, &self.fd, dest)?;
+            Ok(())
+        };
+        inner(name.as_ref())
+    }
+
+    /// Shows the entry `name` of this directory.
+    ///
+    /// The entry is renamed to the name without the leading `.` within this
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `name` is not a valid file name.
+    /// - `name` is not hidden.
+    /// - `renameat` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::fs::File;
+    /// #
+    /// # use hf::Dir;
+    /// #
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// File::create(temp_dir.path().join(".foo.txt")).unwrap();
+    ///
+    /// let dir = Dir::open(temp_dir.path()).unwrap();
+    /// dir.show(".foo.txt").unwrap();
+    /// assert!(temp_dir.path().join("foo.txt").exists());
+    /// ```
+    #[inline]
+    pub fn show(&self, name: impl AsRef<OsStr>) -> io::Result<()> {
+        let inner = |name: &OsStr| -> io::Result<()> {
+            let name = check_file_name(name)?;
+            let bytes = name.as_bytes();
+            let start = bytes
+                .iter()
+                .position(|&b| b != b'.')
+                .filter(|&i| i > 0)
+                .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            let dest = OsString::from_vec(bytes[start..].to_vec());
+            rfs::renameat(&self.fd, name, &self.fd, dest)?;
+            Ok(())
+        };
+        inner(name.as_ref())
+    }
+
+    /// Renames the entry `from` of this directory to `to`.
+    pub(crate) fn rename(&self, from: &OsStr, to: &OsStr) -> io::Result<()> {
+        let (from, to) = (check_file_name(from)?, check_file_name(to)?);
+        rfs::renameat(&self.fd, from, &self.fd, to)?;
+        Ok(())
+    }
+}
+
+impl AsFd for Dir {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl fmt::Debug for Dir {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dir").field("fd", &self.fd).finish()
+    }
+}
+
+impl From<Dir> for OwnedFd {
+    #[inline]
+    fn from(dir: Dir) -> Self {
+        dir.fd
+    }
+}
+
+#[cfg(feature = "cap-std")]
+impl From<cap_std::fs::Dir> for Dir {
+    /// Converts a [`cap_std::fs::Dir`] into a [`Dir`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cap_std::ambient_authority;
+    /// use hf::Dir;
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// let dir = cap_std::fs::Dir::open_ambient_dir(temp_dir.path(), ambient_authority()).unwrap();
+    /// let dir = Dir::from(dir);
+    /// ```
+    #[inline]
+    fn from(dir: cap_std::fs::Dir) -> Self {
+        let fd = dir.into();
+        Self { fd }
+    }
+}
+
+#[cfg(feature = "cap-std")]
+impl From<Dir> for cap_std::fs::Dir {
+    #[inline]
+    fn from(dir: Dir) -> Self {
+        Self::from_std_file(dir.fd.into())
+    }
+}
+
+fn check_file_name(name: &OsStr) -> io::Result<&OsStr> {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() || bytes == b"." || bytes == b".." || bytes.contains(&b'/') {
+        return Err(ErrorKind::InvalidInput.into());
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        os::unix,
+    };
+
+    use super::*;
+
+    #[test]
+    fn open() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+
+        assert!(Dir::open(temp_dir.path()).is_ok());
+        assert_eq!(
+            Dir::open(temp_dir.path().join("bar")).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            Dir::open(file_path).unwrap_err().kind(),
+            ErrorKind::NotADirectory
+        );
+    }
+
+    #[test]
+    fn open_dir_does_not_follow_symlinks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        unix::fs::symlink("foo", temp_dir.path().join("bar")).unwrap();
+
+        let dir = Dir::open(temp_dir.path()).unwrap();
+        let sub_dir = dir.open_dir("foo").unwrap();
+        File::create(temp_dir.path().join("foo/baz.txt")).unwrap();
+        sub_dir.hide("baz.txt").unwrap();
+        assert!(temp_dir.path().join("foo/.baz.txt").exists());
+        assert!(dir.open_dir("bar").is_err());
+    }
+
+    #[test]
+    fn hide_and_show() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        let dir = Dir::open(temp_dir.path()).unwrap();
+
+        dir.hide("foo").unwrap();
+        assert!(temp_dir.path().join(".foo").is_dir());
+        assert!(dir.is_hidden(".foo").unwrap());
+
+        dir.show(".foo").unwrap();
+        assert!(temp_dir.path().join("foo").is_dir());
+        assert!(!dir.is_hidden("foo").unwrap());
+    }
+
+    #[test]
+    fn operations_follow_renamed_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        File::create(temp_dir.path().join("foo/bar.txt")).unwrap();
+
+        let dir = Dir::open(temp_dir.path().join("foo")).unwrap();
+        fs::rename(temp_dir.path().join("foo"), temp_dir.path().join("baz")).unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        File::create(temp_dir.path().join("foo/bar.txt")).unwrap();
+
+        dir.hide("bar.txt").unwrap();
+        assert!(temp_dir.path().join("baz/.bar.txt").exists());
+        assert!(temp_dir.path().join("foo/bar.txt").exists());
+    }
+
+    #[test]
+    fn hide_and_show_with_invalid_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        File::create(temp_dir.path().join("foo/bar.txt")).unwrap();
+        File::create(temp_dir.path().join(".baz.txt")).unwrap();
+        File::create(temp_dir.path().join("qux.txt")).unwrap();
+        let dir = Dir::open(temp_dir.path()).unwrap();
+
+        for name in ["", ".", "..", "foo/bar.txt", "..."] {
+            assert_eq!(dir.hide(name).unwrap_err().kind(), ErrorKind::InvalidInput);
+            assert_eq!(dir.show(name).unwrap_err().kind(), ErrorKind::InvalidInput);
+        }
+        assert_eq!(
+            dir.hide(".baz.txt").unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            dir.show("qux.txt").unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            dir.hide("quux.txt").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            dir.is_hidden("..").unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[cfg(feature = "cap-std")]
+    #[test]
+    fn from_cap_std() {
+        use cap_std::ambient_authority;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        File::create(temp_dir.path().join("foo.txt")).unwrap();
+
+        let dir = cap_std::fs::Dir::open_ambient_dir(temp_dir.path(), ambient_authority()).unwrap();
+        let dir = Dir::from(dir);
+        dir.hide("foo.txt").unwrap();
+        assert!(temp_dir.path().join(".foo.txt").exists());
+
+        let dir = cap_std::fs::Dir::from(dir);
+        assert!(dir.exists(".foo.txt"));
+    }
+}
@@ -0,0 +1,413 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Extension traits for types in the standard library.
+
+#[cfg(windows)]
+use std::fs;
+use std::{
+    fs::{DirEntry, Metadata},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::platform::imp;
+
+/// Extension methods for [`Path`].
+///
+/// [`PathExt::hidden_name`] and [`PathExt::visible_name`] return [`None`] if
+/// the file is already in the requested state, which is the same as
+/// [`DirEntryExt`] and [`MetadataExt`].
+pub trait PathExt {
+    /// Returns [`true`] if the path is a hidden file or directory.
+    ///
+    /// This is equivalent to [`hf::is_hidden`](crate::is_hidden).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if [`hf::is_hidden`](crate::is_hidden) returns an
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// # use std::path::Path;
+    /// #
+    /// use hf::PathExt;
+    ///
+    /// assert_eq!(Path::new(".foo.txt").is_hidden().unwrap(), true);
+    /// assert_eq!(Path::new("foo.txt").is_hidden().unwrap(), false);
+    /// # }
+    /// ```
+    fn is_hidden(&self) -> io::Result<bool>;
+
+    /// Returns the path after hiding the file or directory.
+    ///
+    /// Returns [`None`] if the file or directory is already hidden, or the
+    /// path terminates in `..`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// - On Unix, this is equivalent to
+    ///   [`hf::unix::hidden_file_name`](crate::unix::hidden_file_name), and the
+    ///   file system is not accessed.
+    /// - On Windows, returns the path as is, because hiding does not rename the
+    ///   file. The attributes of the file are queried to determine whether it
+    ///   is already hidden, and [`None`] is returned if they could not be
+    ///   obtained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// # use std::path::Path;
+    /// #
+    /// use hf::PathExt;
+    ///
+    /// assert_eq!(
+    ///     Path::new("foo/bar.txt").hidden_name().as_deref(),
+    ///     Some(Path::new("foo/.bar.txt"))
+    /// );
+    /// assert_eq!(Path::new("foo/.bar.txt").hidden_name(), None);
+    /// # }
+    /// ```
+    fn hidden_name(&self) -> Option<PathBuf>;
+
+    /// Returns the path after showing the file or directory.
+    ///
+    /// Returns [`None`] if the file or directory is not hidden, or the path
+    /// terminates in `..`.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// - On Unix, this is equivalent to
+    ///   [`hf::unix::normal_file_name`](crate::unix::normal_file_name), and the
+    ///   file system is not accessed.
+    /// - On Windows, returns the path as is, because showing does not rename
+    ///   the file. The attributes of the file are queried to determine whether
+    ///   it is hidden, and [`None`] is returned if they could not be obtained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// # use std::path::Path;
+    /// #
+    /// use hf::PathExt;
+    ///
+    /// assert_eq!(
+    ///     Path::new("foo/.bar.txt").visible_name().as_deref(),
+    ///     Some(Path::new("foo/bar.txt"))
+    /// );
+    /// assert_eq!(Path::new("foo/bar.txt").visible_name(), None);
+    /// # }
+    /// ```
+    fn visible_name(&self) -> Option<PathBuf>;
+}
+
+impl PathExt for Path {
+    #[inline]
+    fn is_hidden(&self) -> io::Result<bool> {
+        imp::is_hidden(self)
+    }
+
+    #[inline]
+    fn hidden_name(&self) -> Option<PathBuf> {
+        #[cfg(unix)]
+        {
+            imp::hidden_name(self)
+        }
+        #[cfg(windows)]
+        {
+            fs::symlink_metadata(self).ok()?.hidden_name(self)
+        }
+    }
+
+    #[inline]
+    fn visible_name(&self) -> Option<PathBuf> {
+        #[cfg(unix)]
+        {
+            imp::visible_name(self)
+        }
+        #[cfg(windows)]
+        {
+            fs::symlink_metadata(self).ok()?.visible_name(self)
+        }
+    }
+}
+
+/// Extension methods for [`DirEntry`].
+///
+/// These methods use the information which the entry already has, so they
+/// don't allocate the path of the entry to determine whether it is hidden, and
+/// on Windows, they don't query the file system again.
+pub trait DirEntryExt {
+    /// Returns [`true`] if the entry is a hidden file or directory.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// - On Unix, returns [`true`] if the file name starts with `.`. This never
+    ///   fails.
+    /// - On Windows, returns [`true`] if the file has the hidden file
+    ///   attribute.
+    ///
+    /// # Errors
+    ///
+    /// On Windows, returns [`Err`] if [`DirEntry::metadata`] returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// # use std::fs::{self, File};
+    /// #
+    /// use hf::DirEntryExt;
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// File::create(temp_dir.path().join(".foo.txt")).unwrap();
+    ///
+    /// let entry = fs::read_dir(temp_dir.path())
+    ///     .unwrap()
+    ///     .next()
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(entry.is_hidden().unwrap(), true);
+    /// # }
+    /// ```
+    fn is_hidden(&self) -> io::Result<bool>;
+
+    /// Returns the path of the entry after hiding it.
+    ///
+    /// Returns [`None`] if the entry is already hidden. On Windows, also
+    /// returns [`None`] if [`DirEntry::metadata`] returns an error. See
+    /// [`PathExt::hidden_name`] for details.
+    fn hidden_name(&self) -> Option<PathBuf>;
+
+    /// Returns the path of the entry after showing it.
+    ///
+    /// Returns [`None`] if the entry is not hidden. On Windows, also returns
+    /// [`None`] if [`DirEntry::metadata`] returns an error. See
+    /// [`PathExt::visible_name`] for details.
+    fn visible_name(&self) -> Option<PathBuf>;
+}
+
+impl DirEntryExt for DirEntry {
+    #[inline]
+    fn is_hidden(&self) -> io::Result<bool> {
+        #[cfg(unix)]
+        {
+            Ok(imp::is_hidden_name(&self.file_name()))
+        }
+        #[cfg(windows)]
+        {
+            self.metadata().map(|m| imp::has_hidden_attribute(&m))
+        }
+    }
+
+    #[inline]
+    fn hidden_name(&self) -> Option<PathBuf> {
+        #[cfg(unix)]
+        {
+            imp::hidden_name(&self.path())
+        }
+        #[cfg(windows)]
+        {
+            self.metadata().ok()?.hidden_name(self.path())
+        }
+    }
+
+    #[inline]
+    fn visible_name(&self) -> Option<PathBuf> {
+        #[cfg(unix)]
+        {
+            imp::visible_name(&self.path())
+        }
+        #[cfg(windows)]
+        {
+            self.metadata().ok()?.visible_name(self.path())
+        }
+    }
+}
+
+/// Extension methods for [`Metadata`].
+///
+/// Metadata does not contain the file name, so these methods take the path
+/// which the metadata was obtained from.
+pub trait MetadataExt {
+    /// Returns [`true`] if the file or directory at `path`, which this
+    /// metadata was obtained from, is hidden.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// - On Unix, returns [`true`] if the file name of `path` starts with `.`.
+    ///   The metadata is not used.
+    /// - On Windows, returns [`true`] if the metadata has the hidden file
+    ///   attribute. `path` is not used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// # use std::fs::{self, File};
+    /// #
+    /// use hf::{MetadataExt, PathExt};
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// let file_path = temp_dir.path().join(".foo.txt");
+    /// File::create(&file_path).unwrap();
+    /// let metadata = fs::metadata(&file_path).unwrap();
+    /// assert_eq!(metadata.is_hidden(&file_path), true);
+    /// assert_eq!(metadata.visible_name(&file_path), file_path.visible_name());
+    /// # }
+    /// ```
+    fn is_hidden(&self, path: impl AsRef<Path>) -> bool;
+
+    /// Returns `path` after hiding the file or directory, which this metadata
+    /// was obtained from.
+    ///
+    /// Returns [`None`] if the file or directory is already hidden, or `path`
+    /// terminates in `..`. See [`PathExt::hidden_name`] for the path which is
+    /// returned.
+    fn hidden_name(&self, path: impl AsRef<Path>) -> Option<PathBuf>;
+
+    /// Returns `path` after showing the file or directory, which this metadata
+    /// was obtained from.
+    ///
+    /// Returns [`None`] if the file or directory is not hidden, or `path`
+    /// terminates in `..`. See [`PathExt::visible_name`] for the path which is
+    /// returned.
+    fn visible_name(&self, path: impl AsRef<Path>) -> Option<PathBuf>;
+}
+
+impl MetadataExt for Metadata {
+    #[inline]
+    fn is_hidden(&self, path: impl AsRef<Path>) -> bool {
+        #[cfg(unix)]
+        {
+            path.as_ref().file_name().is_some_and(imp::is_hidden_name)
+        }
+        #[cfg(windows)]
+        {
+            let _ = path;
+            imp::has_hidden_attribute(self)
+        }
+    }
+
+    #[inline]
+    fn hidden_name(&self, path: impl AsRef<Path>) -> Option<PathBuf> {
+        let path = path.as_ref();
+        if self.is_hidden(path) {
+            return None;
+        }
+        imp::hidden_name(path)
+    }
+
+    #[inline]
+    fn visible_name(&self, path: impl AsRef<Path>) -> Option<PathBuf> {
+        let path = path.as_ref();
+        if !self.is_hidden(path) {
+            return None;
+        }
+        imp::visible_name(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn path_ext() {
+        assert!(PathExt::is_hidden(Path::new("foo/.bar.txt")).unwrap());
+        assert!(!PathExt::is_hidden(Path::new(".foo/bar.txt")).unwrap());
+        assert!(PathExt::is_hidden(Path::new("foo.txt/..")).is_err());
+
+        assert_eq!(
+            Path::new("foo/bar.txt").hidden_name().unwrap(),
+            Path::new("foo/.bar.txt")
+        );
+        assert!(Path::new("foo/.bar.txt").hidden_name().is_none());
+        assert_eq!(
+            Path::new("foo/.bar.txt").visible_name().unwrap(),
+            Path::new("foo/bar.txt")
+        );
+        assert!(Path::new("foo/bar.txt").visible_name().is_none());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn path_ext() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+        assert_eq!(file_path.hidden_name().unwrap(), file_path);
+        assert!(file_path.visible_name().is_none());
+
+        crate::hide(&file_path).unwrap();
+        assert!(file_path.hidden_name().is_none());
+        assert_eq!(file_path.visible_name().unwrap(), file_path);
+
+        assert!(temp_dir.path().join("bar.txt").hidden_name().is_none());
+        assert!(Path::new("foo.txt/..").hidden_name().is_none());
+        assert!(Path::new("foo.txt/..").visible_name().is_none());
+    }
+
+    #[test]
+    fn dir_entry_ext() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+
+        let entry = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(!DirEntryExt::is_hidden(&entry).unwrap());
+        assert_eq!(entry.hidden_name(), file_path.hidden_name());
+        assert_eq!(entry.visible_name(), file_path.visible_name());
+
+        crate::hide(&file_path).unwrap();
+        let entry = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert!(DirEntryExt::is_hidden(&entry).unwrap());
+        assert!(entry.hidden_name().is_none());
+        assert_eq!(entry.visible_name(), entry.path().visible_name());
+        assert!(entry.visible_name().is_some());
+    }
+
+    #[test]
+    fn metadata_ext() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+        let metadata = fs::metadata(&file_path).unwrap();
+        assert!(!metadata.is_hidden(&file_path));
+        assert_eq!(metadata.hidden_name(&file_path), file_path.hidden_name());
+        assert!(metadata.visible_name(&file_path).is_none());
+
+        crate::hide(&file_path).unwrap();
+        let file_path = if cfg!(unix) {
+            temp_dir.path().join(".foo.txt")
+        } else {
+            file_path
+        };
+        let metadata = fs::metadata(&file_path).unwrap();
+        assert!(metadata.is_hidden(&file_path));
+        assert!(metadata.hidden_name(&file_path).is_none());
+        assert_eq!(metadata.visible_name(&file_path), file_path.visible_name());
+    }
+}
@@ -0,0 +1,590 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Keeping the index of a Git work tree in sync with hidden and shown files.
+//!
+//! On Unix, hiding and showing files rename them, so Git sees the renamed
+//! files as a deleted file and an untracked file. [`WorkTree`] renames the
+//! entries in the index as well, so the renames are staged as moves.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(unix)]
+//! # {
+//! use std::fs::File;
+//!
+//! use hf::git::{Status, WorkTree};
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! gix::init(temp_dir.path()).unwrap();
+//! let file_path = temp_dir.path().join("foo.txt");
+//! File::create(&file_path).unwrap();
+//!
+//! let mut work_tree = WorkTree::discover(temp_dir.path()).unwrap();
+//! assert_eq!(work_tree.status(&file_path).unwrap(), Status::Untracked);
+//!
+//! work_tree.hide(&file_path).unwrap();
+//! work_tree.write().unwrap();
+//! assert!(temp_dir.path().join(".foo.txt").exists());
+//! # }
+//! ```
+
+use std::{
+    fmt, fs,
+    io::{self, Error, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use gix::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    index::{self, entry::Mode},
+    objs::Kind,
+};
+
+use crate::platform::imp;
+
+/// The status of a path in a Git work tree.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Status {
+    /// The path is not tracked by the index.
+    Untracked,
+
+    /// The path is tracked, and the contents in the work tree match the
+    /// index.
+    Clean,
+
+    /// The path is tracked, but the contents in the work tree differ from the
+    /// index, or the path is in conflict.
+    Modified,
+}
+
+/// A Git work tree and its index.
+///
+/// The changes to the index are kept in memory until [`WorkTree::write`] is
+/// called.
+pub struct WorkTree {
+    workdir: PathBuf,
+    index: index::File,
+}
+
+impl WorkTree {
+    /// Discovers the Git work tree which contains `path`.
+    ///
+    /// `path` may be either a file or a directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in a Git work tree, or the index could
+    /// not be read.
+    #[inline]
+    pub fn discover(path: impl AsRef<Path>) -> io::Result<Self> {
+        let inner = |path: &Path| -> io::Result<Self> {
+            let dir = match path.parent() {
+                _ if path.is_dir() => path,
+                Some(p) if !p.as_os_str().is_empty() => p,
+                _ => Path::new("."),
+            };
+            let repo = gix::discover(dir).map_err(Error::other)?;
+            let workdir = repo
+                .workdir()
+                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "repository is bare"))?;
+            let workdir = fs::canonicalize(workdir)?;
+            let index_path = repo.index_path();
+            let index = if index_path.exists() {
+                repo.open_index().map_err(Error::other)?
+            } else {
+                index::File::from_state(index::State::new(repo.object_hash()), index_path)
+            };
+            Ok(Self { workdir, index })
+        };
+        inner(path.as_ref())
+    }
+
+    /// Returns the root directory of this work tree.
+    #[must_use]
+    #[inline]
+    pub fn workdir(&self) -> &Path {
+        &self.workdir
+    }
+
+    /// Returns the path of the index file of this work tree.
+    #[must_use]
+    #[inline]
+    pub fn index_file(&self) -> &Path {
+        self.index.path()
+    }
+
+    /// Returns the status of `path`.
+    ///
+    /// If `path` is a directory, this returns [`Status::Modified`] if any of
+    /// the tracked files in it are modified. The contents are compared as is,
+    /// so the files which are modified by the filters of Git, such as line
+    /// ending conversions, are considered modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in this work tree, or a tracked file
+    /// could not be read.
+    #[inline]
+    pub fn status(&self, path: impl AsRef<Path>) -> io::Result<Status> {
+        let inner = |path: &Path| -> io::Result<Status> {
+            let path = self.index_path(path)?;
+            let mut status = Status::Untracked;
+            for entry in self.entries(path.as_ref()) {
+                if entry.stage_raw() != 0 || !self.is_up_to_date(entry)? {
+                    return Ok(Status::Modified);
+                }
+                status = Status::Clean;
+            }
+            Ok(status)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Hides a file or directory and renames the entries in the index.
+    ///
+    /// This is equivalent to [`hf::hide`](crate::hide) if `path` is not
+    /// tracked.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in this work tree, or
+    /// [`hf::hide`](crate::hide) returns an error.
+    #[inline]
+    pub fn hide(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let dest_path =
+                imp::hidden_name(path).ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            let (from, to) = (self.index_path(path)?, self.index_path(&dest_path)?);
+            crate::hide(path)?;
+            self.rename_entries(from.as_ref(), to.as_ref());
+            Ok(())
+        };
+        inner(path.as_ref())
+    }
+
+    /// Shows a hidden file or directory and renames the entries in the index.
+    ///
+    /// This is equivalent to [`hf::show`](crate::show) if `path` is not
+    /// tracked.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in this work tree, or
+    /// [`hf::show`](crate::show) returns an error.
+    #[inline]
+    pub fn show(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let dest_path =
+                imp::visible_name(path).ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            let (from, to) = (self.index_path(path)?, self.index_path(&dest_path)?);
+            crate::show(path)?;
+            self.rename_entries(from.as_ref(), to.as_ref());
+            Ok(())
+        };
+        inner(path.as_ref())
+    }
+
+    /// Renames the entries of `path` in the index to `dest_path` without
+    /// touching the work tree.
+    ///
+    /// This is useful when the file has already been renamed, such as by
+    /// [`Plan::execute`](crate::Plan::execute).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` or `dest_path` is not in this work tree.
+    #[inline]
+    pub fn record_rename(
+        &mut self,
+        path: impl AsRef<Path>,
+        dest_path: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        let mut inner = |path: &Path, dest_path: &Path| -> io::Result<()> {
+            let (from, to) = (self.index_path(path)?, self.index_path(dest_path)?);
+            self.rename_entries(from.as_ref(), to.as_ref());
+            Ok(())
+        };
+        inner(path.as_ref(), dest_path.as_ref())
+    }
+
+    /// Removes the entries of `path` from the index.
+    ///
+    /// The files in the work tree are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in this work tree.
+    #[inline]
+    pub fn untrack(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let path = self.index_path(path)?;
+            self.index
+                .remove_entries(|_, p, _| is_same_or_child(p, path.as_ref()));
+            self.index.remove_tree();
+            Ok(())
+        };
+        inner(path.as_ref())
+    }
+
+    /// Adds `path` to the `.gitignore` file in the parent directory of
+    /// `path`.
+    ///
+    /// The pattern is anchored to the directory, so it does not match the
+    /// files with the same name in the subdirectories. Nothing is done if the
+    /// pattern already exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in this work tree, or the `.gitignore`
+    /// file could not be read or written.
+    #[inline]
+    pub fn ignore(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let inner = |path: &Path| -> io::Result<()> {
+            let gitignore_path = self.gitignore_path(path)?;
+            let path = self.index_path(path)?;
+            let name = path
+                .rsplit_once_str("/")
+                .map_or(path.as_slice(), |(_, name)| name);
+            let mut pattern = BString::from("/");
+            pattern.push_str(name);
+
+            let contents = match fs::read(&gitignore_path) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == ErrorKind::NotFound => Vec::new(),
+                Err(err) => return Err(err),
+            };
+            if contents.lines().any(|l| l.trim() == pattern.as_slice()) {
+                return Ok(());
+            }
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(gitignore_path)?;
+            if !contents.is_empty() && !contents.ends_with(b"\n") {
+                file.write_all(b"\n")?;
+            }
+            pattern.push_byte(b'\n');
+            file.write_all(&pattern)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Returns the path of the `.gitignore` file which [`WorkTree::ignore`]
+    /// adds `path` to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not in this work tree.
+    #[inline]
+    pub fn gitignore_path(&self, path: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let inner = |path: &Path| -> io::Result<PathBuf> {
+            let path = self.index_path(path)?;
+            let dir = path
+                .rsplit_once_str("/")
+                .map_or(b"".as_slice(), |(dir, _)| dir);
+            Ok(self
+                .workdir
+                .join(gix::path::from_bstr(dir.as_bstr()))
+                .join(".gitignore"))
+        };
+        inner(path.as_ref())
+    }
+
+    /// Writes the index to the repository.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the index could not be written.
+    #[inline]
+    pub fn write(&mut self) -> io::Result<()> {
+        self.index
+            .write(index::write::Options::default())
+            .map_err(Error::other)
+    }
+
+    /// Returns the path of `path` relative to the work tree as used in the
+    /// index.
+    fn index_path(&self, path: &Path) -> io::Result<BString> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+        let parent = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        let path = fs::canonicalize(parent)?.join(file_name);
+        let path = path.strip_prefix(&self.workdir).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is outside the work tree", path.display()),
+            )
+        })?;
+        let path = gix::path::to_unix_separators_on_windows(gix::path::into_bstr(path));
+        Ok(path.into_owned())
+    }
+
+    fn entries<'a>(&'a self, path: &'a BStr) -> impl Iterator<Item = &'a index::Entry> {
+        self.index
+            .entries()
+            .iter()
+            .filter(move |e| is_same_or_child(e.path(&self.index), path))
+    }
+
+    fn is_up_to_date(&self, entry: &index::Entry) -> io::Result<bool> {
+        let path = self
+            .workdir
+            .join(gix::path::from_bstr(entry.path(&self.index)));
+        let contents = if entry.mode == Mode::SYMLINK {
+            match fs::read_link(&path) {
+                Ok(target) => gix::path::into_bstr(target).into_owned().into(),
+                Err(err) if err.kind() == ErrorKind::NotFound => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        } else if entry.mode == Mode::FILE || entry.mode == Mode::FILE_EXECUTABLE {
+            match fs::read(&path) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == ErrorKind::NotFound => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        } else {
+            return Ok(true);
+        };
+        let id = gix::objs::compute_hash(self.index.object_hash(), Kind::Blob, &contents)
+            .map_err(Error::other)?;
+        Ok(id == entry.id)
+    }
+
+    fn rename_entries(&mut self, from: &BStr, to: &BStr) {
+        let entries = self
+            .entries(from)
+            .map(|e| {
+                let mut path = BString::from(to);
+                path.push_str(&e.path(&self.index)[from.len()..]);
+                (e.clone(), path)
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return;
+        }
+        self.index
+            .remove_entries(|_, p, _| is_same_or_child(p, from));
+        for (entry, path) in entries {
+            self.index.dangerously_push_entry(
+                entry.stat,
+                entry.id,
+                entry.flags,
+                entry.mode,
+                path.as_ref(),
+            );
+        }
+        self.index.sort_entries();
+        self.index.remove_tree();
+    }
+}
+
+impl fmt::Debug for WorkTree {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkTree")
+            .field("workdir", &self.workdir)
+            .finish_non_exhaustive()
+    }
+}
+
+fn is_same_or_child(path: &BStr, parent: &BStr) -> bool {
+    path.strip_prefix(parent.as_bytes())
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(b"/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    fn add(work_tree: &mut WorkTree, path: &str) {
+        let file_path = work_tree.workdir.join(path);
+        let contents = fs::read(&file_path).unwrap();
+        let id =
+            gix::objs::compute_hash(work_tree.index.object_hash(), Kind::Blob, &contents).unwrap();
+        let metadata = index::fs::Metadata::from_path_no_follow(&file_path).unwrap();
+        let stat = index::entry::Stat::from_fs(&metadata).unwrap();
+        work_tree.index.dangerously_push_entry(
+            stat,
+            id,
+            index::entry::Flags::empty(),
+            Mode::FILE,
+            path.into(),
+        );
+        work_tree.index.sort_entries();
+    }
+
+    fn index_paths(work_tree: &WorkTree) -> Vec<String> {
+        let work_tree = WorkTree::discover(work_tree.workdir()).unwrap();
+        work_tree
+            .index
+            .entries()
+            .iter()
+            .map(|e| e.path(&work_tree.index).to_string())
+            .collect()
+    }
+
+    fn init() -> (tempfile::TempDir, WorkTree) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        gix::init(temp_dir.path()).unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        fs::write(temp_dir.path().join("foo/bar.txt"), "bar").unwrap();
+        fs::write(temp_dir.path().join("foo/baz.txt"), "baz").unwrap();
+        fs::write(temp_dir.path().join("qux.txt"), "qux").unwrap();
+        let mut work_tree = WorkTree::discover(temp_dir.path()).unwrap();
+        add(&mut work_tree, "foo/bar.txt");
+        add(&mut work_tree, "foo/baz.txt");
+        add(&mut work_tree, "qux.txt");
+        work_tree.write().unwrap();
+        (temp_dir, work_tree)
+    }
+
+    #[test]
+    fn discover() {
+        let (temp_dir, _) = init();
+        let work_tree = WorkTree::discover(temp_dir.path().join("foo")).unwrap();
+        assert_eq!(
+            work_tree.workdir(),
+            fs::canonicalize(temp_dir.path()).unwrap()
+        );
+        let work_tree = WorkTree::discover(temp_dir.path().join("foo/bar.txt")).unwrap();
+        assert_eq!(
+            work_tree.workdir(),
+            fs::canonicalize(temp_dir.path()).unwrap()
+        );
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(WorkTree::discover(temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn status() {
+        let (temp_dir, work_tree) = init();
+        let root = temp_dir.path();
+        File::create(root.join("quux.txt")).unwrap();
+        assert_eq!(
+            work_tree.status(root.join("qux.txt")).unwrap(),
+            Status::Clean
+        );
+        assert_eq!(work_tree.status(root.join("foo")).unwrap(), Status::Clean);
+        assert_eq!(
+            work_tree.status(root.join("quux.txt")).unwrap(),
+            Status::Untracked
+        );
+        assert_eq!(
+            work_tree.status(root.join("fo")).unwrap(),
+            Status::Untracked
+        );
+
+        fs::write(root.join("foo/baz.txt"), "modified").unwrap();
+        assert_eq!(
+            work_tree.status(root.join("foo/baz.txt")).unwrap(),
+            Status::Modified
+        );
+        assert_eq!(
+            work_tree.status(root.join("foo")).unwrap(),
+            Status::Modified
+        );
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            work_tree
+                .status(temp_dir.path().join("foo.txt"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hide_and_show() {
+        let (temp_dir, mut work_tree) = init();
+        let root = temp_dir.path();
+
+        work_tree.hide(root.join("qux.txt")).unwrap();
+        work_tree.hide(root.join("foo")).unwrap();
+        work_tree.write().unwrap();
+        assert!(root.join(".qux.txt").exists());
+        assert!(root.join(".foo/bar.txt").exists());
+        assert_eq!(
+            index_paths(&work_tree),
+            [".foo/bar.txt", ".foo/baz.txt", ".qux.txt"]
+        );
+        assert_eq!(
+            work_tree.status(root.join(".qux.txt")).unwrap(),
+            Status::Clean
+        );
+
+        work_tree.show(root.join(".foo")).unwrap();
+        work_tree.write().unwrap();
+        assert_eq!(
+            index_paths(&work_tree),
+            [".qux.txt", "foo/bar.txt", "foo/baz.txt"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hide_untracked() {
+        let (temp_dir, mut work_tree) = init();
+        let root = temp_dir.path();
+        File::create(root.join("quux.txt")).unwrap();
+
+        work_tree.hide(root.join("quux.txt")).unwrap();
+        work_tree.write().unwrap();
+        assert!(root.join(".quux.txt").exists());
+        assert_eq!(
+            index_paths(&work_tree),
+            ["foo/bar.txt", "foo/baz.txt", "qux.txt"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn record_rename() {
+        let (temp_dir, mut work_tree) = init();
+        let root = temp_dir.path();
+
+        fs::rename(root.join("qux.txt"), root.join(".qux.txt")).unwrap();
+        work_tree
+            .record_rename(root.join("qux.txt"), root.join(".qux.txt"))
+            .unwrap();
+        work_tree.write().unwrap();
+        assert_eq!(
+            index_paths(&work_tree),
+            [".qux.txt", "foo/bar.txt", "foo/baz.txt"]
+        );
+    }
+
+    #[test]
+    fn untrack_and_ignore() {
+        let (temp_dir, mut work_tree) = init();
+        let root = temp_dir.path();
+
+        work_tree.untrack(root.join("foo")).unwrap();
+        work_tree.write().unwrap();
+        assert_eq!(index_paths(&work_tree), ["qux.txt"]);
+
+        fs::write(root.join("foo/.gitignore"), "*.log").unwrap();
+        work_tree.ignore(root.join("foo/.bar.txt")).unwrap();
+        work_tree.ignore(root.join("foo/.bar.txt")).unwrap();
+        work_tree.ignore(root.join(".qux.txt")).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("foo/.gitignore")).unwrap(),
+            "*.log\n/.bar.txt\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join(".gitignore")).unwrap(),
+            "/.qux.txt\n"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Provides predicates and an extension trait for [`ignore`].
+//!
+//! [`ignore::WalkBuilder`] skips hidden entries by default, but it considers
+//! only file names starting with `.` as hidden. [`is_hidden`] and
+//! [`is_visible`] determine whether an entry is hidden in the same way as
+//! [`DirEntryExt::is_hidden`](crate::DirEntryExt::is_hidden), so they respect
+//! the hidden file attribute on Windows. [`HiddenFilter`] and
+//! [`WalkBuilderExt::skip_hidden`] also treat the entries listed in the
+//! `.hidden` file in their parent directory as hidden on Unix.
+
+use std::sync::{Mutex, PoisonError};
+
+use ignore::{DirEntry, WalkBuilder};
+
+#[cfg(unix)]
+use crate::migrate::ListCache;
+use crate::platform::imp;
+
+/// Returns [`true`] if the entry is a hidden file or directory.
+///
+/// The root of the walk is never considered hidden, and entries from standard
+/// input are never hidden. Returns [`false`] if the metadata about the entry
+/// could not be obtained.
+#[must_use]
+#[inline]
+pub fn is_hidden(entry: &DirEntry) -> bool {
+    if entry.depth() == 0 || entry.is_stdin() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        imp::is_hidden_name(entry.file_name())
+    }
+    #[cfg(windows)]
+    {
+        entry
+            .metadata()
+            .is_ok_and(|m| imp::has_hidden_attribute(&m))
+    }
+}
+
+/// Returns [`true`] if the entry is not a hidden file or directory.
+///
+/// This is the negation of [`is_hidden`], and is suitable for
+/// [`WalkBuilder::filter_entry`].
+#[must_use]
+#[inline]
+pub fn is_visible(entry: &DirEntry) -> bool {
+    !is_hidden(entry)
+}
+
+/// A predicate which also treats the entries listed in the `.hidden` file in
+/// their parent directory as hidden.
+///
+/// On Unix, the `.hidden` file of each directory is read once and kept while
+/// the walk is in the directory, so a filter should be used for a single walk.
+/// A `.hidden` file which could not be read is treated as empty. On Windows,
+/// this is the same as [`is_hidden`].
+#[derive(Debug, Default)]
+pub struct HiddenFilter {
+    #[cfg(unix)]
+    lists: ListCache,
+}
+
+impl HiddenFilter {
+    /// Creates a new `HiddenFilter`.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns [`true`] if the entry is a hidden file or directory, or it is
+    /// listed in the `.hidden` file in its parent directory.
+    ///
+    /// The root of the walk is never considered hidden, and entries from
+    /// standard input are never hidden.
+    #[must_use]
+    #[inline]
+    pub fn is_hidden(&mut self, entry: &DirEntry) -> bool {
+        if is_hidden(entry) {
+            return true;
+        }
+        #[cfg(unix)]
+        {
+            entry.depth() > 0
+                && !entry.is_stdin()
+                && entry
+                    .path()
+                    .parent()
+                    .is_some_and(|p| self.lists.is_listed(p, entry.file_name()))
+        }
+        #[cfg(windows)]
+        {
+            false
+        }
+    }
+
+    /// Returns [`true`] if the entry is not a hidden file or directory.
+    ///
+    /// This is the negation of [`HiddenFilter::is_hidden`].
+    #[must_use]
+    #[inline]
+    pub fn is_visible(&mut self, entry: &DirEntry) -> bool {
+        !self.is_hidden(entry)
+    }
+}
+
+/// Extension methods for [`WalkBuilder`].
+pub trait WalkBuilderExt {
+    /// Skips hidden entries using the semantics of this crate.
+    ///
+    /// This disables the built-in hidden filter of [`WalkBuilder`] and adds
+    /// [`HiddenFilter::is_visible`] as the entry filter. The filter is shared
+    /// by the walkers which are built from this builder, so build a new one
+    /// for each walk. Note that this replaces any filter previously set by
+    /// [`WalkBuilder::filter_entry`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// # use std::fs::{self, File};
+    /// #
+    /// use hf::ignore::WalkBuilderExt;
+    /// use ignore::WalkBuilder;
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// fs::create_dir(temp_dir.path().join(".foo")).unwrap();
+    /// File::create(temp_dir.path().join(".foo/bar.txt")).unwrap();
+    /// File::create(temp_dir.path().join("baz.txt")).unwrap();
+    ///
+    /// let entries = WalkBuilder::new(temp_dir.path())
+    ///     .skip_hidden()
+    ///     .build()
+    ///     .map(|e| e.unwrap().file_name().to_owned())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(entries.len(), 2);
+    /// assert_eq!(entries[1], "baz.txt");
+    /// # }
+    /// ```
+    fn skip_hidden(&mut self) -> &mut Self;
+}
+
+impl WalkBuilderExt for WalkBuilder {
+    #[inline]
+    fn skip_hidden(&mut self) -> &mut Self {
+        let filter = Mutex::new(HiddenFilter::new());
+        self.hidden(false).filter_entry(move |entry| {
+            filter
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .is_visible(entry)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use super::*;
+
+    #[test]
+    fn skip_hidden() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let hidden_dir = root.join(if cfg!(unix) { ".foo" } else { "foo" });
+        fs::create_dir(&hidden_dir).unwrap();
+        File::create(hidden_dir.join("bar.txt")).unwrap();
+        File::create(root.join("baz.txt")).unwrap();
+        #[cfg(windows)]
+        crate::hide(&hidden_dir).unwrap();
+
+        let entries = WalkBuilder::new(root)
+            .skip_hidden()
+            .build()
+            .map(|e| e.unwrap().into_path())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [root.to_path_buf(), root.join("baz.txt")]);
+    }
+
+    #[test]
+    fn is_hidden() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let hidden_file = root.join(if cfg!(unix) { ".foo.txt" } else { "foo.txt" });
+        File::create(&hidden_file).unwrap();
+        #[cfg(windows)]
+        crate::hide(&hidden_file).unwrap();
+
+        let entries = WalkBuilder::new(root)
+            .hidden(false)
+            .build()
+            .filter_map(Result::ok)
+            .filter(super::is_hidden)
+            .map(DirEntry::into_path)
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [hidden_file]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hidden_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("foo")).unwrap();
+        fs::write(root.join(".hidden"), "bar.txt\n").unwrap();
+        fs::write(root.join("foo/.hidden"), "baz.txt\n").unwrap();
+        for name in ["bar.txt", "baz.txt", "foo/bar.txt", "foo/baz.txt"] {
+            File::create(root.join(name)).unwrap();
+        }
+
+        let entries = WalkBuilder::new(root)
+            .hidden(false)
+            .build()
+            .filter_map(Result::ok)
+            .filter(super::is_hidden)
+            .count();
+        assert_eq!(entries, 2);
+
+        let mut filter = HiddenFilter::new();
+        let entries = WalkBuilder::new(root)
+            .hidden(false)
+            .sort_by_file_name(Ord::cmp)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|e| filter.is_hidden(e))
+            .map(DirEntry::into_path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                root.join(".hidden"),
+                root.join("bar.txt"),
+                root.join("foo/.hidden"),
+                root.join("foo/baz.txt")
+            ]
+        );
+
+        let entries = WalkBuilder::new(root)
+            .skip_hidden()
+            .sort_by_file_name(Ord::cmp)
+            .build()
+            .map(|e| e.unwrap().into_path())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                root.to_path_buf(),
+                root.join("baz.txt"),
+                root.join("foo"),
+                root.join("foo/bar.txt")
+            ]
+        );
+    }
+}
@@ -73,9 +73,46 @@
 // Lint levels of rustc.
 #![deny(missing_docs)]
 
+pub mod batch;
+pub mod clean;
+#[cfg(unix)]
+mod dir;
+mod ext;
+#[cfg(feature = "git")]
+pub mod git;
+#[cfg(feature = "ignore")]
+pub mod ignore;
+#[cfg(unix)]
+pub mod links;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+pub mod migrate;
 mod ops;
+mod options;
+mod plan;
 mod platform;
+pub mod scheme;
+mod status;
+#[cfg(feature = "tar")]
+pub mod tar;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+mod walk;
+#[cfg(feature = "walkdir")]
+pub mod walkdir;
+#[cfg(feature = "watch")]
+pub mod watch;
 
-pub use crate::ops::{hide, is_hidden, show};
+#[cfg(unix)]
+pub use crate::dir::Dir;
 #[cfg(unix)]
 pub use crate::platform::unix;
+pub use crate::{
+    ext::{DirEntryExt, MetadataExt, PathExt},
+    ops::{hide, is_effectively_hidden, is_effectively_hidden_within, is_hidden, show},
+    options::{Options, SymlinkPolicy},
+    plan::{Companions, Plan, Step},
+    scheme::NamingScheme,
+    status::{HiddenReason, HiddenStatus, StatusReader, hidden_status},
+    walk::{ReadDir, Visibility, Walk, WalkEntry, read_dir_hidden, read_dir_visible},
+};
@@ -0,0 +1,330 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Repairing symbolic links which point at renamed files.
+//!
+//! On Unix, hiding and showing files rename them, so symbolic links which
+//! point at the renamed files become dangling. This module finds such links in
+//! a directory tree and rewrites them to point at the new names.
+//!
+//! This is only available on Unix.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::{fs::File, os::unix};
+//!
+//! use hf::links;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! let temp_dir = temp_dir.path();
+//! let file_path = temp_dir.join("foo.txt");
+//! File::create(&file_path).unwrap();
+//! unix::fs::symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+//!
+//! hf::hide(&file_path).unwrap();
+//! let renames = [(file_path, temp_dir.join(".foo.txt"))];
+//! for fix in links::find(temp_dir, &renames).unwrap() {
+//!     fix.apply().unwrap();
+//! }
+//! assert_eq!(
+//!     temp_dir.join("bar.txt").read_link().unwrap(),
+//!     temp_dir.join(".foo.txt").strip_prefix(temp_dir).unwrap()
+//! );
+//! ```
+
+use std::{
+    ffi::OsString,
+    fs, io,
+    os::unix,
+    path::{Component, Path, PathBuf},
+    process,
+};
+
+use crate::Walk;
+
+/// A symbolic link whose target should be rewritten.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LinkFix {
+    path: PathBuf,
+    old_target: PathBuf,
+    new_target: PathBuf,
+}
+
+impl LinkFix {
+    /// Returns the path of the symbolic link.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the current target of the symbolic link.
+    #[must_use]
+    #[inline]
+    pub fn old_target(&self) -> &Path {
+        &self.old_target
+    }
+
+    /// Returns the target of the symbolic link after rewriting it.
+    ///
+    /// The target is relative if and only if the current target is relative.
+    #[must_use]
+    #[inline]
+    pub fn new_target(&self) -> &Path {
+        &self.new_target
+    }
+
+    /// Rewrites the symbolic link to point at the new target.
+    ///
+    /// The new link is created next to the current link and then renamed over
+    /// it, so the link is replaced atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the new link could not be created or renamed.
+    #[inline]
+    pub fn apply(&self) -> io::Result<()> {
+        let mut temp_name = OsString::from(".");
+        temp_name.push(self.path.file_name().unwrap_or_default());
+        temp_name.push(format!(".hf-{}", process::id()));
+        let temp_path = self.path.with_file_name(temp_name);
+        unix::fs::symlink(&self.new_target, &temp_path)?;
+        fs::rename(&temp_path, &self.path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+}
+
+/// Finds symbolic links under `root` which point at the files renamed by
+/// `renames`.
+///
+/// Each element of `renames` is a pair of the path before and after renaming,
+/// such as [`Step::path`](crate::Step::path) and
+/// [`Step::dest`](crate::Step::dest) of each step in
+/// [`Plan::steps`](crate::Plan::steps). Links which point into a renamed
+/// directory are also found.
+///
+/// This can be called either before or after the files are renamed, but the
+/// fixes must be applied after. [`LinkFix::path`] is the path of the link
+/// after renaming, which differs from the current path if the link is in a
+/// renamed directory or is renamed itself.
+///
+/// The targets are resolved lexically, so `..` in the targets are resolved
+/// without following symbolic links. Symbolic links to directories under
+/// `root` are not followed.
+///
+/// # Errors
+///
+/// Returns [`Err`] if `root` or the parent directory of any path in `renames`
+/// could not be canonicalized, or an error occurred while walking `root`.
+#[inline]
+pub fn find<P: AsRef<Path>, Q: AsRef<Path>>(
+    root: impl AsRef<Path>,
+    renames: &[(P, Q)],
+) -> io::Result<Vec<LinkFix>> {
+    let renames = renames
+        .iter()
+        .map(|(from, to)| Ok((normalize(from.as_ref())?, normalize(to.as_ref())?)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut fixes = Vec::new();
+    for entry in Walk::new(fs::canonicalize(root)?).sort_by_file_name(true) {
+        let entry = entry?;
+        if !entry.file_type().is_symlink() {
+            continue;
+        }
+        let old_target = fs::read_link(entry.path())?;
+        let Some(link_dir) = entry.path().parent() else {
+            continue;
+        };
+        let mut new_target = old_target.clone();
+        for (from, to) in &renames {
+            if let Some(target) = rewrite(link_dir, &new_target, from, to) {
+                new_target = target;
+            }
+        }
+        if new_target != old_target {
+            fixes.push(LinkFix {
+                path: rebase(entry.path(), &renames),
+                old_target,
+                new_target,
+            });
+        }
+    }
+    Ok(fixes)
+}
+
+/// Returns the absolute path of `path` whose parent directory is canonical.
+fn normalize(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    Ok(fs::canonicalize(parent)?.join(file_name))
+}
+
+/// Returns `path` after renaming the files by `renames`.
+///
+/// The path is unchanged if it has already been renamed.
+fn rebase(path: &Path, renames: &[(PathBuf, PathBuf)]) -> PathBuf {
+    let mut path = path.to_path_buf();
+    for (from, to) in renames {
+        if let Ok(rest) = path.strip_prefix(from) {
+            path = if rest.as_os_str().is_empty() {
+                to.clone()
+            } else {
+                to.join(rest)
+            };
+        }
+    }
+    path
+}
+
+/// Rewrites the component of `target` which refers to `from` to refer to `to`.
+///
+/// Returns [`None`] if `target` does not pass through `from`.
+fn rewrite(link_dir: &Path, target: &Path, from: &Path, to: &Path) -> Option<PathBuf> {
+    let mut current = link_dir.to_path_buf();
+    let mut new_target = PathBuf::new();
+    let mut is_rewritten = false;
+    for component in target.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                current = PathBuf::from(component.as_os_str());
+                new_target.push(component);
+            }
+            Component::CurDir => new_target.push(component),
+            Component::ParentDir => {
+                current.pop();
+                new_target.push(component);
+            }
+            Component::Normal(name) => {
+                current.push(name);
+                if current == from {
+                    current = to.to_path_buf();
+                    new_target.push(to.file_name()?);
+                    is_rewritten = true;
+                } else {
+                    new_target.push(name);
+                }
+            }
+        }
+    }
+    is_rewritten.then_some(new_target)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn rewrite() {
+        let link_dir = Path::new("/foo/bar");
+        let (from, to) = (Path::new("/foo/baz"), Path::new("/foo/.baz"));
+        assert_eq!(
+            super::rewrite(link_dir, Path::new("../baz"), from, to).unwrap(),
+            Path::new("../.baz")
+        );
+        assert_eq!(
+            super::rewrite(link_dir, Path::new("./../baz/qux.txt"), from, to).unwrap(),
+            Path::new("./../.baz/qux.txt")
+        );
+        assert_eq!(
+            super::rewrite(link_dir, Path::new("/foo/baz/qux.txt"), from, to).unwrap(),
+            Path::new("/foo/.baz/qux.txt")
+        );
+        assert!(super::rewrite(link_dir, Path::new("baz"), from, to).is_none());
+        assert!(super::rewrite(link_dir, Path::new("../bazz"), from, to).is_none());
+        assert!(super::rewrite(link_dir, Path::new("/foo/baz/.."), from, to).is_some());
+    }
+
+    #[test]
+    fn find_and_apply() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(temp_dir.path()).unwrap();
+        fs::create_dir_all(root.join("foo/bar")).unwrap();
+        File::create(root.join("foo/baz.txt")).unwrap();
+        unix::fs::symlink("../baz.txt", root.join("foo/bar/relative")).unwrap();
+        unix::fs::symlink(root.join("foo/baz.txt"), root.join("absolute")).unwrap();
+        unix::fs::symlink("foo", root.join("dir")).unwrap();
+        unix::fs::symlink("foo/bar", root.join("unrelated")).unwrap();
+
+        let renames = [(root.join("foo/baz.txt"), root.join("foo/.baz.txt"))];
+        crate::hide(root.join("foo/baz.txt")).unwrap();
+        let fixes = find(&root, &renames).unwrap();
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(fixes[0].path(), root.join("absolute"));
+        assert_eq!(fixes[0].old_target(), root.join("foo/baz.txt"));
+        assert_eq!(fixes[0].new_target(), root.join("foo/.baz.txt"));
+        assert_eq!(fixes[1].path(), root.join("foo/bar/relative"));
+        assert_eq!(fixes[1].new_target(), Path::new("../.baz.txt"));
+
+        for fix in fixes {
+            fix.apply().unwrap();
+        }
+        assert!(root.join("absolute").exists());
+        assert!(root.join("foo/bar/relative").exists());
+        assert_eq!(fs::read_dir(root.join("foo/bar")).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn find_links_into_renamed_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = &fs::canonicalize(temp_dir.path()).unwrap();
+        fs::create_dir(root.join("foo")).unwrap();
+        File::create(root.join("foo/bar.txt")).unwrap();
+        unix::fs::symlink("foo/bar.txt", root.join("baz")).unwrap();
+        unix::fs::symlink("../foo/bar.txt", root.join("foo/qux")).unwrap();
+
+        let renames = [(root.join("foo"), root.join(".foo"))];
+        let fixes = find(root, &renames).unwrap();
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(fixes[0].path(), root.join("baz"));
+        assert_eq!(fixes[0].new_target(), Path::new(".foo/bar.txt"));
+        assert_eq!(fixes[1].path(), root.join(".foo/qux"));
+        assert_eq!(fixes[1].new_target(), Path::new("../.foo/bar.txt"));
+
+        // The fixes found before renaming are applied to the new paths.
+        crate::hide(root.join("foo")).unwrap();
+        let mut after = find(root, &renames).unwrap();
+        // `.foo` is now walked before `baz`.
+        after.reverse();
+        assert_eq!(after, fixes);
+        for fix in fixes {
+            fix.apply().unwrap();
+        }
+        assert!(root.join("baz").exists());
+        assert!(root.join(".foo/qux").exists());
+        assert!(!root.join("foo").exists());
+    }
+
+    #[test]
+    fn rebase() {
+        let renames = [
+            (PathBuf::from("/foo"), PathBuf::from("/.foo")),
+            (PathBuf::from("/bar.txt"), PathBuf::from("/.bar.txt")),
+        ];
+        assert_eq!(
+            super::rebase(Path::new("/foo/baz"), &renames),
+            Path::new("/.foo/baz")
+        );
+        assert_eq!(
+            super::rebase(Path::new("/bar.txt"), &renames),
+            Path::new("/.bar.txt")
+        );
+        assert_eq!(
+            super::rebase(Path::new("/.foo/baz"), &renames),
+            Path::new("/.foo/baz")
+        );
+        assert_eq!(
+            super::rebase(Path::new("/foobar"), &renames),
+            Path::new("/foobar")
+        );
+    }
+}
@@ -0,0 +1,454 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Declaring which files must be hidden or visible.
+//!
+//! A manifest is a TOML file with a list of rules. Each rule has either a
+//! `path` or a `glob`, and the `state` which the matching files must be in:
+//!
+//! ```toml
+//! [[rule]]
+//! glob = "**/*.log"
+//! state = "hidden"
+//!
+//! [[rule]]
+//! path = "logs/important.log"
+//! state = "visible"
+//! ```
+//!
+//! The paths and the globs are relative to the directory which contains the
+//! manifest, and `/` is used as the path separator. If a file matches more
+//! than one rule, the last one wins. Files which don't match any rule are left
+//! as they are.
+//!
+//! The `[options]` table is ignored, because it is reserved for the
+//! configuration of the `hf` command, which shares `hf.toml` with the
+//! manifest.
+//!
+//! On Unix, hiding a file renames it, so the rules match the visible names of
+//! the files and their ancestor directories. For example, the path
+//! `notes/todo.txt` matches `notes/todo.txt`, `.notes/todo.txt` and
+//! `.notes/.todo.txt`. Globs also match the actual paths, so `**/.cache`
+//! matches `.cache`.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(unix)]
+//! # {
+//! use std::fs::File;
+//!
+//! use hf::manifest::{Manifest, State};
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! let temp_dir = temp_dir.path();
+//! File::create(temp_dir.join("foo.log")).unwrap();
+//! File::create(temp_dir.join(".bar.txt")).unwrap();
+//!
+//! let manifest = r#"
+//! [[rule]]
+//! glob = "*.log"
+//! state = "hidden"
+//!
+//! [[rule]]
+//! path = "bar.txt"
+//! state = "visible"
+//! "#
+//! .parse::<Manifest>()
+//! .unwrap();
+//!
+//! let drift = manifest.audit(temp_dir).unwrap();
+//! assert_eq!(drift.len(), 2);
+//! assert_eq!(drift[0].path(), temp_dir.join("foo.log"));
+//! assert_eq!(drift[0].state(), State::Hidden);
+//! assert_eq!(drift[1].path(), temp_dir.join(".bar.txt"));
+//! assert_eq!(drift[1].state(), State::Visible);
+//! # }
+//! ```
+
+use std::{
+    ffi::OsString,
+    fmt, fs,
+    io::{self, Error, ErrorKind},
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, de::IgnoredAny};
+
+use crate::Walk;
+
+/// The state which files must be in.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    /// The files must be hidden.
+    Hidden,
+
+    /// The files must be visible.
+    Visible,
+}
+
+impl fmt::Display for State {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hidden => write!(f, "hidden"),
+            Self::Visible => write!(f, "visible"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Pattern {
+    Path(PathBuf),
+    Glob(GlobMatcher),
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    pattern: Pattern,
+    state: State,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawManifest {
+    #[serde(default)]
+    rule: Vec<RawRule>,
+    #[serde(default, rename = "options")]
+    _options: IgnoredAny,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRule {
+    path: Option<String>,
+    glob: Option<String>,
+    state: State,
+}
+
+/// A list of rules which declare which files must be hidden or visible.
+#[derive(Clone, Debug, Default)]
+pub struct Manifest {
+    rules: Vec<Rule>,
+}
+
+impl Manifest {
+    /// Creates a new `Manifest` without any rules.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Reads a manifest from the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the file could not be read, or it is not a valid
+    /// manifest.
+    #[inline]
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Adds a rule which matches the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `path` is not a relative path without `..`.
+    #[inline]
+    pub fn path(mut self, path: impl AsRef<Path>, state: State) -> io::Result<Self> {
+        let path = path.as_ref();
+        if path.file_name().is_none()
+            || !path.components().all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a relative path without `..`", path.display()),
+            ));
+        }
+        self.rules.push(Rule {
+            pattern: Pattern::Path(logical_path(path)),
+            state,
+        });
+        Ok(self)
+    }
+
+    /// Adds a rule which matches the files matching `glob`.
+    ///
+    /// `*` and `?` don't match `/`, and `**` matches any number of directories.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if `glob` is not a valid glob.
+    #[inline]
+    pub fn glob(mut self, glob: &str, state: State) -> io::Result<Self> {
+        let glob = GlobBuilder::new(glob)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        self.rules.push(Rule {
+            pattern: Pattern::Glob(glob.compile_matcher()),
+            state,
+        });
+        Ok(self)
+    }
+
+    /// Returns the state which the file at `path` must be in, or [`None`] if
+    /// no rule matches it.
+    ///
+    /// `path` is relative to the directory which contains the manifest.
+    #[must_use]
+    #[inline]
+    pub fn state(&self, path: impl AsRef<Path>) -> Option<State> {
+        let inner = |path: &Path| -> Option<State> {
+            let logical_path = logical_path(path);
+            self.rules
+                .iter()
+                .rev()
+                .find(|r| match &r.pattern {
+                    Pattern::Path(p) => *p == logical_path,
+                    Pattern::Glob(g) => g.is_match(path) || g.is_match(&logical_path),
+                })
+                .map(|r| r.state)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Returns the files under `root` which are not in the state declared by
+    /// this manifest.
+    ///
+    /// The entries of a directory come before the directory itself, so the
+    /// files can be hidden or shown in this order even if renaming a directory
+    /// changes the paths of its entries. `.git` directories are skipped, and
+    /// symbolic links are not followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurred while walking `root`, or the
+    /// information about a file could not be read.
+    #[inline]
+    pub fn audit(&self, root: impl AsRef<Path>) -> io::Result<Vec<Drift>> {
+        let inner = |root: &Path| -> io::Result<Vec<Drift>> {
+            let mut drift = Vec::new();
+            for entry in Walk::new(root).min_depth(1).sort_by_file_name(true) {
+                let entry = entry?;
+                let relative_path = entry
+                    .path()
+                    .strip_prefix(root)
+                    .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+                if relative_path.components().any(|c| c.as_os_str() == ".git") {
+                    continue;
+                }
+                let Some(state) = self.state(relative_path) else {
+                    continue;
+                };
+                if crate::is_hidden(entry.path())? != (state == State::Hidden) {
+                    drift.push(Drift {
+                        path: entry.into_path(),
+                        state,
+                    });
+                }
+            }
+            drift.reverse();
+            Ok(drift)
+        };
+        inner(root.as_ref())
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = toml::from_str::<RawManifest>(s)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        raw.rule
+            .into_iter()
+            .try_fold(Self::new(), |manifest, rule| match (rule.path, rule.glob) {
+                (Some(path), None) => manifest.path(path, rule.state),
+                (None, Some(glob)) => manifest.glob(&glob, rule.state),
+                _ => Err(Error::from(ErrorKind::InvalidInput)),
+            })
+            .map_err(|err| {
+                let message = if err.get_ref().is_some() {
+                    format!("invalid rule: {err}")
+                } else {
+                    String::from("a rule must have either `path` or `glob`")
+                };
+                Error::new(ErrorKind::InvalidData, message)
+            })
+    }
+}
+
+/// A file which is not in the state declared by a [`Manifest`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Drift {
+    path: PathBuf,
+    state: State,
+}
+
+impl Drift {
+    /// Returns the path of the file.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the state which the file must be in.
+    #[must_use]
+    #[inline]
+    pub const fn state(&self) -> State {
+        self.state
+    }
+}
+
+/// Returns `path` with all the components made visible.
+fn logical_path(path: &Path) -> PathBuf {
+    path.components()
+        .map(|c| match c {
+            #[cfg(unix)]
+            Component::Normal(name) => {
+                use crate::scheme::{Dot, NamingScheme};
+
+                Dot.visible_name(name)
+                    .unwrap_or_else(|| name.to_os_string())
+            }
+            c => OsString::from(c.as_os_str()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let manifest = r#"
+            [[rule]]
+            glob = "**/*.log"
+            state = "hidden"
+
+            [[rule]]
+            path = "logs/important.log"
+            state = "visible"
+        "#
+        .parse::<Manifest>()
+        .unwrap();
+        assert_eq!(manifest.state("foo.log"), Some(State::Hidden));
+        assert_eq!(manifest.state("logs/foo.log"), Some(State::Hidden));
+        assert_eq!(manifest.state("logs/important.log"), Some(State::Visible));
+        assert_eq!(manifest.state("foo.txt"), None);
+        assert!("".parse::<Manifest>().unwrap().state("foo.txt").is_none());
+    }
+
+    #[test]
+    fn parse_with_options() {
+        let manifest = r#"
+            [options]
+            log-level = "warn"
+
+            [[rule]]
+            path = "foo.txt"
+            state = "hidden"
+        "#
+        .parse::<Manifest>()
+        .unwrap();
+        assert_eq!(manifest.state("foo.txt"), Some(State::Hidden));
+    }
+
+    #[test]
+    fn parse_invalid_manifest() {
+        for manifest in [
+            "[[rule]]\nstate = \"hidden\"",
+            "[[rule]]\npath = \"foo\"\nglob = \"*\"\nstate = \"hidden\"",
+            "[[rule]]\npath = \"foo\"\nstate = \"invisible\"",
+            "[[rule]]\npath = \"../foo\"\nstate = \"hidden\"",
+            "[[rule]]\npath = \"/foo\"\nstate = \"hidden\"",
+            "[[rule]]\nglob = \"[\"\nstate = \"hidden\"",
+            "[[rules]]\npath = \"foo\"\nstate = \"hidden\"",
+        ] {
+            assert_eq!(
+                manifest.parse::<Manifest>().unwrap_err().kind(),
+                ErrorKind::InvalidData,
+                "{manifest}"
+            );
+        }
+        assert_eq!(
+            "[[rule]]\nstate = \"hidden\""
+                .parse::<Manifest>()
+                .unwrap_err()
+                .to_string(),
+            "a rule must have either `path` or `glob`"
+        );
+    }
+
+    #[test]
+    fn glob_does_not_match_separator() {
+        let manifest = Manifest::new().glob("*.log", State::Hidden).unwrap();
+        assert_eq!(manifest.state("foo.log"), Some(State::Hidden));
+        assert_eq!(manifest.state("foo/bar.log"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn state_matches_visible_names() {
+        let manifest = Manifest::new()
+            .path("foo/bar.txt", State::Hidden)
+            .unwrap()
+            .glob("**/.cache", State::Hidden)
+            .unwrap()
+            .glob("*.txt", State::Visible)
+            .unwrap();
+        assert_eq!(manifest.state("foo/.bar.txt"), Some(State::Hidden));
+        assert_eq!(manifest.state("foo/bar.txt"), Some(State::Hidden));
+        assert_eq!(manifest.state(".foo/bar.txt"), Some(State::Hidden));
+        assert_eq!(manifest.state("foo/baz.txt"), None);
+        assert_eq!(manifest.state("foo/.cache"), Some(State::Hidden));
+        assert_eq!(manifest.state(".baz.txt"), Some(State::Visible));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn audit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join("build/out")).unwrap();
+        fs::create_dir(temp_dir.join(".git")).unwrap();
+        File::create(temp_dir.join(".git/foo.log")).unwrap();
+        File::create(temp_dir.join("build/out/foo.log")).unwrap();
+        File::create(temp_dir.join(".bar.log")).unwrap();
+        File::create(temp_dir.join("baz.txt")).unwrap();
+
+        let manifest = Manifest::new()
+            .glob("**/*.log", State::Hidden)
+            .unwrap()
+            .path("build", State::Hidden)
+            .unwrap()
+            .path("baz.txt", State::Visible)
+            .unwrap();
+        let drift = manifest.audit(temp_dir).unwrap();
+        assert_eq!(
+            drift,
+            [
+                Drift {
+                    path: temp_dir.join("build/out/foo.log"),
+                    state: State::Hidden
+                },
+                Drift {
+                    path: temp_dir.join("build"),
+                    state: State::Hidden
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,967 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Converting hidden files between hiding mechanisms.
+//!
+//! A file system may hide files in a different way from the operating system
+//! which uses it. For example, a Samba share or a FAT file system mounted on
+//! Linux ignores the leading `.` which hides files on Unix. A [`Migration`]
+//! reads which files are hidden with one [`Mechanism`] and hides them with
+//! another, renaming them back to their visible names where appropriate.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::fs::{self, File};
+//!
+//! use hf::migrate::{Mechanism, Migration};
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! let temp_dir = temp_dir.path();
+//! File::create(temp_dir.join(".foo.txt")).unwrap();
+//! File::create(temp_dir.join("bar.txt")).unwrap();
+//!
+//! let mut migration = Migration::new(Mechanism::Dot, Mechanism::HiddenList);
+//! migration.find(temp_dir, false).unwrap();
+//! assert_eq!(migration.steps().len(), 1);
+//!
+//! migration.execute().unwrap();
+//! assert!(temp_dir.join("foo.txt").exists());
+//! assert_eq!(
+//!     fs::read_to_string(temp_dir.join(".hidden")).unwrap(),
+//!     "foo.txt\n"
+//! );
+//! assert!(
+//!     Mechanism::HiddenList
+//!         .is_hidden(temp_dir.join("foo.txt"))
+//!         .unwrap()
+//! );
+//! ```
+
+#[cfg(all(unix, any(feature = "walkdir", feature = "ignore")))]
+use std::collections::HashSet;
+use std::{
+    ffi::OsStr,
+    fmt, fs,
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    Walk,
+    scheme::{Dot, NamingScheme},
+};
+
+/// The name of the file which lists the hidden entries of a directory.
+pub(crate) const HIDDEN_LIST: &str = ".hidden";
+
+/// A mechanism which marks files as hidden.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Mechanism {
+    /// The file name starts with `.`.
+    Dot,
+
+    /// The hidden file attribute of the file system.
+    ///
+    /// This is supported on Windows, and on Linux if the file is on a FAT file
+    /// system. On Linux, only regular files and directories can have this
+    /// attribute.
+    Attribute,
+
+    /// The file name is listed in the `.hidden` file in the parent directory.
+    ///
+    /// This is read by file managers such as GNOME Files and Dolphin.
+    HiddenList,
+
+    /// The hidden bit of the DOS attributes which Samba stores in the
+    /// `user.DOSATTRIB` extended attribute.
+    ///
+    /// This is only supported on Linux. The attributes can only be changed if
+    /// they are stored in the legacy format, which is a hexadecimal string
+    /// such as `0x22`.
+    Samba,
+}
+
+impl Mechanism {
+    /// Returns [`true`] if the file is hidden with this mechanism.
+    ///
+    /// Symbolic links are not followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the file does not exist, this mechanism is not
+    /// supported, or the information about the file could not be read.
+    #[inline]
+    pub fn is_hidden(self, path: impl AsRef<Path>) -> io::Result<bool> {
+        let inner = |path: &Path| -> io::Result<bool> {
+            fs::symlink_metadata(path)?;
+            match self {
+                Self::Dot => Ok(path.file_name().is_some_and(|n| Dot.is_hidden_name(n))),
+                Self::Attribute => attribute::is_hidden(path),
+                Self::HiddenList => {
+                    let (list_path, file_name) = split_path(path)?;
+                    is_listed(&list_path, file_name)
+                }
+                Self::Samba => samba::is_hidden(path),
+            }
+        };
+        inner(path.as_ref())
+    }
+
+    /// Returns [`true`] if this mechanism hides files by renaming them.
+    #[must_use]
+    #[inline]
+    pub const fn renames(self) -> bool {
+        matches!(self, Self::Dot)
+    }
+
+    /// Returns the path after hiding `path` with this mechanism.
+    fn hidden_path(self, path: &Path) -> Option<PathBuf> {
+        if self.renames() {
+            Dot.hidden_path(path)
+        } else {
+            path.file_name().map(|_| path.to_path_buf())
+        }
+    }
+
+    /// Returns the path after showing `path` with this mechanism.
+    fn visible_path(self, path: &Path) -> Option<PathBuf> {
+        if self.renames() {
+            Dot.visible_path(path)
+        } else {
+            path.file_name().map(|_| path.to_path_buf())
+        }
+    }
+
+    /// Hides `path`, which is renamed to `dest`.
+    fn hide(self, path: &Path, dest: &Path) -> io::Result<()> {
+        match self {
+            Self::Dot => rename(path, dest),
+            Self::Attribute => attribute::set_hidden(path, true),
+            Self::HiddenList => {
+                let (list_path, file_name) = split_path(path)?;
+                update_list(&list_path, file_name, true)
+            }
+            Self::Samba => samba::set_hidden(path, true),
+        }
+    }
+
+    /// Shows `path`, which is renamed to `dest`.
+    fn show(self, path: &Path, dest: &Path) -> io::Result<()> {
+        match self {
+            Self::Dot => rename(path, dest),
+            Self::Attribute => attribute::set_hidden(path, false),
+            Self::HiddenList => {
+                let (list_path, file_name) = split_path(path)?;
+                update_list(&list_path, file_name, false)
+            }
+            Self::Samba => samba::set_hidden(path, false),
+        }
+    }
+}
+
+impl fmt::Display for Mechanism {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dot => write!(f, "dot"),
+            Self::Attribute => write!(f, "attribute"),
+            Self::HiddenList => write!(f, "hidden-list"),
+            Self::Samba => write!(f, "samba"),
+        }
+    }
+}
+
+/// A step of a [`Migration`], which converts a hidden file.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MigrationStep {
+    path: PathBuf,
+    visible: PathBuf,
+    dest: PathBuf,
+}
+
+impl MigrationStep {
+    /// Returns the path of the file before this step.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of the file after this step.
+    #[must_use]
+    #[inline]
+    pub fn dest(&self) -> &Path {
+        &self.dest
+    }
+}
+
+/// A conversion of hidden files from one [`Mechanism`] to another, which is
+/// executed as a whole.
+///
+/// The steps are checked when they are added, so that no existing files are
+/// overwritten. If a step fails while executing the migration, the steps which
+/// have already been executed are reverted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Migration {
+    from: Mechanism,
+    to: Mechanism,
+    steps: Vec<MigrationStep>,
+}
+
+impl Migration {
+    /// Creates a new empty `Migration` from `from` to `to`.
+    #[must_use]
+    #[inline]
+    pub const fn new(from: Mechanism, to: Mechanism) -> Self {
+        Self {
+            from,
+            to,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Returns the mechanism which the files are hidden with before the
+    /// migration.
+    #[must_use]
+    #[inline]
+    pub const fn from(&self) -> Mechanism {
+        self.from
+    }
+
+    /// Returns the mechanism which the files are hidden with after the
+    /// migration.
+    #[must_use]
+    #[inline]
+    pub const fn to(&self) -> Mechanism {
+        self.to
+    }
+
+    /// Adds a step to convert `path` if it is hidden with the source
+    /// mechanism.
+    ///
+    /// Returns [`true`] if a step has been added. The steps are executed in the
+    /// order in which they are added, so the entries of a directory must be
+    /// added before the directory itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `path` does not exist.
+    /// - [`Mechanism::is_hidden`] of the source mechanism returns an error.
+    /// - `path` is already in this migration.
+    /// - The file after renaming already exists.
+    #[inline]
+    pub fn add(&mut self, path: impl AsRef<Path>) -> io::Result<bool> {
+        let mut inner = |path: &Path| -> io::Result<bool> {
+            if self.from == self.to || !self.from.is_hidden(path)? {
+                return Ok(false);
+            }
+            let visible = self
+                .from
+                .visible_path(path)
+                .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            let dest = self
+                .to
+                .hidden_path(&visible)
+                .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            if self
+                .steps
+                .iter()
+                .any(|s| s.path == path || s.dest == path || s.dest == dest)
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("{} is already planned", path.display()),
+                ));
+            }
+            for p in [&visible, &dest] {
+                if p != path && fs::symlink_metadata(p).is_ok() {
+                    return Err(Error::new(
+                        ErrorKind::AlreadyExists,
+                        format!("{} already exists", p.display()),
+                    ));
+                }
+            }
+            self.steps.push(MigrationStep {
+                path: path.to_path_buf(),
+                visible,
+                dest,
+            });
+            Ok(true)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Adds steps to convert the hidden files in `dir`.
+    ///
+    /// If `recursive` is [`true`], the subdirectories are searched as well, and
+    /// the entries of a directory are added before the directory itself.
+    /// Symbolic links are not followed. The `.hidden` files are skipped if
+    /// either mechanism is [`Mechanism::HiddenList`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if an error occurred while walking `dir`, or
+    /// [`Migration::add`] returns an error.
+    #[inline]
+    pub fn find(&mut self, dir: impl AsRef<Path>, recursive: bool) -> io::Result<()> {
+        let mut inner = |dir: &Path| -> io::Result<()> {
+            let max_depth = if recursive { usize::MAX } else { 1 };
+            let skip_list = self.from == Mechanism::HiddenList || self.to == Mechanism::HiddenList;
+            let mut paths = Vec::new();
+            for entry in Walk::new(dir)
+                .min_depth(1)
+                .max_depth(max_depth)
+                .sort_by_file_name(true)
+            {
+                let entry = entry?;
+                if skip_list && entry.file_name() == HIDDEN_LIST {
+                    continue;
+                }
+                paths.push(entry.into_path());
+            }
+            for path in paths.into_iter().rev() {
+                self.add(path)?;
+            }
+            Ok(())
+        };
+        inner(dir.as_ref())
+    }
+
+    /// Returns the steps of this migration.
+    #[must_use]
+    #[inline]
+    pub fn steps(&self) -> &[MigrationStep] {
+        &self.steps
+    }
+
+    /// Returns [`true`] if this migration has no steps.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Executes this migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any step fails. In that case, the steps which have
+    /// already been executed are reverted in the reverse order as far as
+    /// possible.
+    #[inline]
+    pub fn execute(&self) -> io::Result<()> {
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Err(err) = self.apply(step) {
+                for step in self.steps[..i].iter().rev() {
+                    let _ = self.revert(step);
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&self, step: &MigrationStep) -> io::Result<()> {
+        self.from.show(&step.path, &step.visible)?;
+        if let Err(err) = self.to.hide(&step.visible, &step.dest) {
+            let _ = self.from.hide(&step.visible, &step.path);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    fn revert(&self, step: &MigrationStep) -> io::Result<()> {
+        self.to.show(&step.dest, &step.visible)?;
+        self.from.hide(&step.visible, &step.path)
+    }
+}
+
+/// Renames `path` to `dest` without overwriting an existing file.
+fn rename(path: &Path, dest: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(dest).is_ok() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", dest.display()),
+        ));
+    }
+    #[cfg(unix)]
+    {
+        crate::platform::imp::rename(path, dest)
+    }
+    #[cfg(windows)]
+    {
+        fs::rename(path, dest)
+    }
+}
+
+/// Splits `path` into the path of the `.hidden` file in the parent directory
+/// and the file name.
+fn split_path(path: &Path) -> io::Result<(PathBuf, &OsStr)> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+    let list_path = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.join(HIDDEN_LIST),
+        _ => PathBuf::from(HIDDEN_LIST),
+    };
+    Ok((list_path, file_name))
+}
+
+/// Returns [`true`] if `file_name` is listed in the `.hidden` file at
+/// `list_path`.
+pub(crate) fn is_listed(list_path: &Path, file_name: &OsStr) -> io::Result<bool> {
+    let contents = read_list(list_path)?;
+    let file_name = file_name.as_encoded_bytes();
+    let is_listed = lines(&contents).any(|l| l == file_name);
+    Ok(is_listed)
+}
+
+/// Adds `file_name` to or removes it from the `.hidden` file at `list_path`.
+///
+/// The `.hidden` file is created if it does not exist, and removed if it
+/// becomes empty.
+fn update_list(list_path: &Path, file_name: &OsStr, listed: bool) -> io::Result<()> {
+    let contents = read_list(list_path)?;
+    let file_name = file_name.as_encoded_bytes();
+    let mut names = lines(&contents)
+        .filter(|l| !l.is_empty() && *l != file_name)
+        .collect::<Vec<_>>();
+    if listed {
+        names.push(file_name);
+    }
+    if names.is_empty() {
+        return match fs::remove_file(list_path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    let mut contents = names.join(&b'\n');
+    contents.push(b'\n');
+    fs::write(list_path, contents)
+}
+
+/// The parsed `.hidden` files of the directories which are being walked.
+///
+/// Only the lists of the directory which was looked up last and its ancestors
+/// are kept, so each `.hidden` file is read once per directory during a
+/// depth-first walk, and the cache does not grow beyond the depth of the walk.
+#[cfg(all(unix, any(feature = "walkdir", feature = "ignore")))]
+#[derive(Debug, Default)]
+pub(crate) struct ListCache {
+    lists: Vec<(PathBuf, HashSet<Vec<u8>>)>,
+}
+
+#[cfg(all(unix, any(feature = "walkdir", feature = "ignore")))]
+impl ListCache {
+    /// Returns [`true`] if `file_name` is listed in the `.hidden` file in
+    /// `dir`.
+    ///
+    /// A `.hidden` file which could not be read is treated as empty.
+    pub(crate) fn is_listed(&mut self, dir: &Path, file_name: &OsStr) -> bool {
+        self.lists.retain(|(d, _)| dir.starts_with(d));
+        if self.lists.last().is_none_or(|(d, _)| d != dir) {
+            let contents = read_list(&dir.join(HIDDEN_LIST)).unwrap_or_default();
+            let names = lines(&contents)
+                .filter(|l| !l.is_empty())
+                .map(<[u8]>::to_vec)
+                .collect();
+            self.lists.push((dir.to_path_buf(), names));
+        }
+        self.lists
+            .last()
+            .is_some_and(|(_, names)| names.contains(file_name.as_encoded_bytes()))
+    }
+}
+
+/// Reads the `.hidden` file at `list_path`, which is empty if it does not
+/// exist.
+pub(crate) fn read_list(list_path: &Path) -> io::Result<Vec<u8>> {
+    match fs::read(list_path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        result => result,
+    }
+}
+
+/// Returns the lines of a `.hidden` file.
+pub(crate) fn lines(contents: &[u8]) -> impl Iterator<Item = &[u8]> {
+    contents
+        .split(|&b| b == b'\n')
+        .map(|l| l.strip_suffix(b"\r").unwrap_or(l))
+}
+
+/// The hidden bit of the DOS attributes.
+#[cfg(any(windows, target_os = "linux", target_os = "android"))]
+const DOS_HIDDEN: u32 = 0x2;
+
+#[cfg(windows)]
+mod attribute {
+    use std::{io, path::Path};
+
+    use crate::platform::imp;
+
+    pub fn is_hidden(path: &Path) -> io::Result<bool> {
+        imp::is_hidden(path)
+    }
+
+    pub fn set_hidden(path: &Path, hidden: bool) -> io::Result<()> {
+        if hidden {
+            imp::hide(path)
+        } else {
+            imp::show(path)
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod attribute {
+    use std::{
+        io::{self, Error, ErrorKind},
+        os::fd::{AsFd, OwnedFd},
+        path::Path,
+    };
+
+    use rustix::{
+        fs::{self as rfs, FileType, Mode, OFlags},
+        io::Errno,
+        ioctl::{self, Getter, Opcode, Setter, opcode},
+    };
+
+    use super::DOS_HIDDEN;
+
+    // `FAT_IOCTL_GET_ATTRIBUTES` and `FAT_IOCTL_SET_ATTRIBUTES` in
+    // `<linux/msdos_fs.h>`.
+    const GET_ATTRIBUTES: Opcode = opcode::read::<u32>(b'r', 0x10);
+    const SET_ATTRIBUTES: Opcode = opcode::write::<u32>(b'r', 0x11);
+
+    /// Opens `path` if it is a regular file or a directory.
+    ///
+    /// Symbolic links are not followed, and opening a FIFO does not block.
+    /// Returns [`None`] for any other type of file.
+    fn open(path: &Path) -> io::Result<Option<OwnedFd>> {
+        let flags = OFlags::RDONLY | OFlags::NONBLOCK | OFlags::NOFOLLOW | OFlags::CLOEXEC;
+        let fd = match rfs::open(path, flags, Mode::empty()) {
+            Ok(fd) => fd,
+            Err(Errno::LOOP) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        match FileType::from_raw_mode(rfs::fstat(&fd)?.st_mode) {
+            FileType::RegularFile | FileType::Directory => Ok(Some(fd)),
+            _ => Ok(None),
+        }
+    }
+
+    fn get_attributes(file: impl AsFd) -> io::Result<u32> {
+        // SAFETY: `FAT_IOCTL_GET_ATTRIBUTES` writes a `u32`.
+        unsafe { ioctl::ioctl(file, Getter::<GET_ATTRIBUTES, u32>::new()) }.map_err(map_err)
+    }
+
+    fn map_err(errno: Errno) -> Error {
+        if errno == Errno::NOTTY {
+            Error::new(
+                ErrorKind::Unsupported,
+                "the file system does not support the hidden file attribute",
+            )
+        } else {
+            errno.into()
+        }
+    }
+
+    pub fn is_hidden(path: &Path) -> io::Result<bool> {
+        let Some(file) = open(path)? else {
+            return Ok(false);
+        };
+        let attributes = get_attributes(&file)?;
+        Ok(attributes & DOS_HIDDEN != 0)
+    }
+
+    pub fn set_hidden(path: &Path, hidden: bool) -> io::Result<()> {
+        let Some(file) = open(path)? else {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "the hidden file attribute can only be set on regular files and directories",
+            ));
+        };
+        let attributes = get_attributes(&file)?;
+        let attributes = if hidden {
+            attributes | DOS_HIDDEN
+        } else {
+            attributes & !DOS_HIDDEN
+        };
+        // SAFETY: `FAT_IOCTL_SET_ATTRIBUTES` reads a `u32`.
+        unsafe { ioctl::ioctl(&file, Setter::<SET_ATTRIBUTES, u32>::new(attributes)) }
+            .map_err(map_err)
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "android")))]
+mod attribute {
+    use std::{
+        io::{self, Error, ErrorKind},
+        path::Path,
+    };
+
+    fn unsupported() -> Error {
+        Error::new(
+            ErrorKind::Unsupported,
+            "the hidden file attribute is not supported on this platform",
+        )
+    }
+
+    pub fn is_hidden(_: &Path) -> io::Result<bool> {
+        Err(unsupported())
+    }
+
+    pub fn set_hidden(_: &Path, _: bool) -> io::Result<()> {
+        Err(unsupported())
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod samba {
+    use std::{
+        io::{self, Error, ErrorKind},
+        path::Path,
+    };
+
+    use rustix::{
+        fs::{self as rfs, XattrFlags},
+        io::Errno,
+    };
+
+    use super::DOS_HIDDEN;
+
+    const DOSATTRIB: &str = "user.DOSATTRIB";
+
+    /// The stored DOS attributes.
+    struct Attributes {
+        value: u32,
+        has_blob: bool,
+    }
+
+    /// Returns the DOS attributes, or [`None`] if they are not stored.
+    ///
+    /// Both the legacy and the current formats of Samba start with the
+    /// attributes as a hexadecimal string such as `0x22`. In the current
+    /// format, the string is terminated by NUL and followed by a versioned blob
+    /// which also contains the attributes.
+    fn get_attributes(path: &Path) -> io::Result<Option<Attributes>> {
+        let mut value = [0; 256];
+        let len = match rfs::lgetxattr(path, DOSATTRIB, &mut value) {
+            Ok(len) => len,
+            Err(Errno::NODATA) => return Ok(None),
+            Err(Errno::NOTSUP) => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "the file system does not support extended attributes",
+                ));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let value = &value[..len];
+        let has_blob = value
+            .iter()
+            .position(|&b| b == 0)
+            .is_some_and(|i| i + 1 < value.len());
+        let attributes = value
+            .strip_prefix(b"0x")
+            .map(|v| v.split(|&b| b == 0).next().unwrap_or_default())
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .and_then(|v| u32::from_str_radix(v, 16).ok())
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("invalid {DOSATTRIB} of {}", path.display()),
+                )
+            })?;
+        Ok(Some(Attributes {
+            value: attributes,
+            has_blob,
+        }))
+    }
+
+    pub fn is_hidden(path: &Path) -> io::Result<bool> {
+        let attributes = get_attributes(path)?.map_or(0, |a| a.value);
+        Ok(attributes & DOS_HIDDEN != 0)
+    }
+
+    /// Sets the hidden bit, writing the attributes in the legacy format, which
+    /// Samba still reads.
+    ///
+    /// The attributes in the current format are not rewritten, because Samba
+    /// reads them from the blob, which would have to be kept consistent with
+    /// the rest of its fields.
+    pub fn set_hidden(path: &Path, hidden: bool) -> io::Result<()> {
+        let attributes = match get_attributes(path)? {
+            Some(Attributes { has_blob: true, .. }) => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    format!(
+                        "{DOSATTRIB} of {} is in a format which can't be rewritten",
+                        path.display()
+                    ),
+                ));
+            }
+            Some(attributes) => attributes.value,
+            None => 0,
+        };
+        let attributes = if hidden {
+            attributes | DOS_HIDDEN
+        } else {
+            attributes & !DOS_HIDDEN
+        };
+        let value = format!("0x{attributes:x}");
+        rfs::lsetxattr(path, DOSATTRIB, value.as_bytes(), XattrFlags::empty())?;
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod samba {
+    use std::{
+        io::{self, Error, ErrorKind},
+        path::Path,
+    };
+
+    fn unsupported() -> Error {
+        Error::new(
+            ErrorKind::Unsupported,
+            "the DOS attributes of Samba are not supported on this platform",
+        )
+    }
+
+    pub fn is_hidden(_: &Path) -> io::Result<bool> {
+        Err(unsupported())
+    }
+
+    pub fn set_hidden(_: &Path, _: bool) -> io::Result<()> {
+        Err(unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn display_mechanism() {
+        assert_eq!(Mechanism::Dot.to_string(), "dot");
+        assert_eq!(Mechanism::Attribute.to_string(), "attribute");
+        assert_eq!(Mechanism::HiddenList.to_string(), "hidden-list");
+        assert_eq!(Mechanism::Samba.to_string(), "samba");
+    }
+
+    #[test]
+    fn update_list() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let list_path = temp_dir.path().join(HIDDEN_LIST);
+        fs::write(&list_path, "foo.txt\r\n\nbar.txt").unwrap();
+
+        super::update_list(&list_path, OsStr::new("baz.txt"), true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&list_path).unwrap(),
+            "foo.txt\nbar.txt\nbaz.txt\n"
+        );
+        assert!(is_listed(&list_path, OsStr::new("bar.txt")).unwrap());
+        for name in ["foo.txt", "bar.txt", "baz.txt"] {
+            super::update_list(&list_path, OsStr::new(name), false).unwrap();
+        }
+        assert!(!list_path.exists());
+        assert!(!is_listed(&list_path, OsStr::new("foo.txt")).unwrap());
+    }
+
+    #[cfg(all(unix, any(feature = "walkdir", feature = "ignore")))]
+    #[test]
+    fn list_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("foo")).unwrap();
+        fs::write(root.join(HIDDEN_LIST), "foo\nbar.txt\n").unwrap();
+        fs::write(root.join("foo").join(HIDDEN_LIST), "baz.txt\n").unwrap();
+
+        let mut cache = ListCache::default();
+        assert!(cache.is_listed(root, OsStr::new("bar.txt")));
+        assert!(cache.is_listed(&root.join("foo"), OsStr::new("baz.txt")));
+        assert_eq!(cache.lists.len(), 2);
+
+        // The cached list is used until the walk leaves the directory.
+        fs::write(root.join(HIDDEN_LIST), "qux.txt\n").unwrap();
+        assert!(cache.is_listed(root, OsStr::new("foo")));
+        assert_eq!(cache.lists.len(), 1);
+        assert!(!cache.is_listed(&root.join("bar"), OsStr::new("bar.txt")));
+        assert!(!cache.is_listed(root, OsStr::new("")));
+    }
+
+    #[test]
+    fn dot_to_hidden_list_and_back() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join(".foo/bar")).unwrap();
+        File::create(temp_dir.join(".foo/.baz.txt")).unwrap();
+        File::create(temp_dir.join("qux.txt")).unwrap();
+
+        let mut migration = Migration::new(Mechanism::Dot, Mechanism::HiddenList);
+        migration.find(temp_dir, true).unwrap();
+        assert_eq!(migration.steps().len(), 2);
+        assert_eq!(migration.steps()[0].path(), temp_dir.join(".foo/.baz.txt"));
+        assert_eq!(migration.steps()[1].dest(), temp_dir.join("foo"));
+        migration.execute().unwrap();
+        assert!(temp_dir.join("foo/baz.txt").exists());
+        assert!(
+            Mechanism::HiddenList
+                .is_hidden(temp_dir.join("foo"))
+                .unwrap()
+        );
+        assert!(
+            Mechanism::HiddenList
+                .is_hidden(temp_dir.join("foo/baz.txt"))
+                .unwrap()
+        );
+        assert!(
+            !Mechanism::HiddenList
+                .is_hidden(temp_dir.join("qux.txt"))
+                .unwrap()
+        );
+
+        let mut migration = Migration::new(Mechanism::HiddenList, Mechanism::Dot);
+        migration.find(temp_dir, true).unwrap();
+        migration.execute().unwrap();
+        assert!(temp_dir.join(".foo/.baz.txt").exists());
+        assert!(!temp_dir.join(HIDDEN_LIST).exists());
+        assert!(!temp_dir.join(".foo").join(HIDDEN_LIST).exists());
+    }
+
+    #[test]
+    fn add_refuses_conflicts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in [".foo.txt", "foo.txt", "bar.txt"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+
+        let mut migration = Migration::new(Mechanism::Dot, Mechanism::HiddenList);
+        assert_eq!(
+            migration.add(temp_dir.join(".foo.txt")).unwrap_err().kind(),
+            ErrorKind::AlreadyExists
+        );
+        assert!(!migration.add(temp_dir.join("bar.txt")).unwrap());
+        assert_eq!(
+            migration.add(temp_dir.join(".baz.txt")).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert!(migration.is_empty());
+
+        let mut migration = Migration::new(Mechanism::Dot, Mechanism::Dot);
+        assert!(!migration.add(temp_dir.join(".foo.txt")).unwrap());
+    }
+
+    #[test]
+    fn execute_rolls_back_on_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in [".foo.txt", ".bar.txt"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+
+        let mut migration = Migration::new(Mechanism::Dot, Mechanism::HiddenList);
+        migration.add(temp_dir.join(".foo.txt")).unwrap();
+        migration.add(temp_dir.join(".bar.txt")).unwrap();
+        File::create(temp_dir.join("bar.txt")).unwrap();
+
+        assert_eq!(
+            migration.execute().unwrap_err().kind(),
+            ErrorKind::AlreadyExists
+        );
+        assert!(temp_dir.join(".foo.txt").exists());
+        assert!(!temp_dir.join("foo.txt").exists());
+        assert!(!temp_dir.join(HIDDEN_LIST).exists());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn attribute_is_unsupported_on_other_file_systems() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+
+        // The temporary directory is unlikely to be on a FAT file system.
+        if let Err(err) = Mechanism::Attribute.is_hidden(&file_path) {
+            assert_eq!(err.kind(), ErrorKind::Unsupported);
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn attribute_with_special_files() {
+        use rustix::fs::{CWD, FileType, Mode};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        let fifo_path = temp_dir.join("foo");
+        rustix::fs::mknodat(CWD, &fifo_path, FileType::Fifo, Mode::RUSR | Mode::WUSR, 0).unwrap();
+        let link_path = temp_dir.join("bar");
+        std::os::unix::fs::symlink("baz", &link_path).unwrap();
+
+        for path in [fifo_path, link_path] {
+            assert!(!Mechanism::Attribute.is_hidden(&path).unwrap());
+            assert_eq!(
+                Mechanism::Attribute.hide(&path, &path).unwrap_err().kind(),
+                ErrorKind::Unsupported
+            );
+        }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn samba() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+
+        // The temporary directory may not support extended attributes.
+        if let Err(err) = Mechanism::Samba.is_hidden(&file_path) {
+            assert_eq!(err.kind(), ErrorKind::Unsupported);
+            return;
+        }
+        let blob = b"0x20\0\x04\0";
+        rustix::fs::lsetxattr(
+            &file_path,
+            "user.DOSATTRIB",
+            blob,
+            rustix::fs::XattrFlags::empty(),
+        )
+        .unwrap();
+        assert!(!Mechanism::Samba.is_hidden(&file_path).unwrap());
+        assert_eq!(
+            Mechanism::Samba
+                .hide(&file_path, &file_path)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Unsupported
+        );
+        let mut value = [0; 8];
+        let len = rustix::fs::lgetxattr(&file_path, "user.DOSATTRIB", &mut value).unwrap();
+        assert_eq!(&value[..len], blob);
+
+        rustix::fs::lsetxattr(
+            &file_path,
+            "user.DOSATTRIB",
+            b"0x20",
+            rustix::fs::XattrFlags::empty(),
+        )
+        .unwrap();
+        Mechanism::Samba.hide(&file_path, &file_path).unwrap();
+        assert!(Mechanism::Samba.is_hidden(&file_path).unwrap());
+        let mut value = [0; 8];
+        let len = rustix::fs::lgetxattr(&file_path, "user.DOSATTRIB", &mut value).unwrap();
+        assert_eq!(&value[..len], b"0x22");
+        Mechanism::Samba.show(&file_path, &file_path).unwrap();
+        assert!(!Mechanism::Samba.is_hidden(&file_path).unwrap());
+    }
+}
@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use std::{io, path::Path};
+use std::{
+    io::{self, Error, ErrorKind},
+    path::{Component, Path},
+};
 
 use crate::platform::imp;
 
@@ -13,6 +16,14 @@ use crate::platform::imp;
 /// - On Unix, returns [`true`] if the file name starts with `.`.
 /// - On Windows, returns [`true`] if the file has the hidden file attribute.
 ///
+/// Only the file itself is checked, so a file in a hidden directory is not
+/// considered hidden. Use
+/// [`is_effectively_hidden`](crate::is_effectively_hidden) to also check the
+/// ancestor directories.
+///
+/// Symbolic links are not followed. Use [`Options`](crate::Options) to change
+/// this behavior.
+///
 /// # Errors
 ///
 /// ## On Unix
@@ -80,6 +91,122 @@ pub fn is_hidden(path: impl AsRef<Path>) -> io::Result<bool> {
     inner(path.as_ref())
 }
 
+/// Returns [`true`] if the path is hidden or is in a hidden directory.
+///
+/// Unlike [`hf::is_hidden`](crate::is_hidden), this also returns [`true`] if
+/// any ancestor directory in `path` is hidden, so it reports whether the file
+/// shows up in file managers. Use [`is_effectively_hidden_within`] to ignore
+/// the ancestor directories above a root.
+///
+/// # Platform-specific behavior
+///
+/// - On Unix, the check is lexical: returns [`true`] if the file name or any
+///   ancestor directory name in `path` starts with `.`. The file system is not
+///   accessed, and `..` is not resolved.
+/// - On Windows, returns [`true`] if the file or any ancestor directory in
+///   `path` has the hidden file attribute. The root directory of a drive is not
+///   checked.
+///
+/// The ancestor directories are taken from `path` as given, so relative paths
+/// are not resolved against the current directory. Symbolic links are not
+/// followed.
+///
+/// # Errors
+///
+/// ## On Unix
+///
+/// Returns [`Err`] if `path` terminates in `..`.
+///
+/// ## On Windows
+///
+/// Returns [`Err`] if metadata about the file or an ancestor directory could
+/// not be obtained.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// assert_eq!(hf::is_hidden("foo/.bar/baz.txt").unwrap(), false);
+/// assert_eq!(hf::is_effectively_hidden("foo/.bar/baz.txt").unwrap(), true);
+/// assert_eq!(hf::is_effectively_hidden("foo/bar/baz.txt").unwrap(), false);
+/// # }
+/// ```
+#[inline]
+pub fn is_effectively_hidden(path: impl AsRef<Path>) -> io::Result<bool> {
+    let inner = |path: &Path| -> io::Result<bool> { effectively_hidden(path, path) };
+    inner(path.as_ref())
+}
+
+/// Returns [`true`] if the path is hidden or is in a hidden directory under
+/// `root`.
+///
+/// This is the same as [`is_effectively_hidden`], except that only the entries
+/// below `root` are checked. `root` itself and its ancestor directories are
+/// not checked, so this is useful when listing the contents of a directory
+/// which may itself be hidden. If `path` is the same as `root`, returns
+/// [`false`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`is_effectively_hidden`] returns an error, or `path`
+/// does not start with `root`. `root` is compared lexically.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// assert_eq!(
+///     hf::is_effectively_hidden_within(".config/foo/bar.txt", ".config").unwrap(),
+///     false
+/// );
+/// assert_eq!(
+///     hf::is_effectively_hidden_within(".config/.foo/bar.txt", ".config").unwrap(),
+///     true
+/// );
+/// assert!(hf::is_effectively_hidden_within("foo/bar.txt", "baz").is_err());
+/// # }
+/// ```
+#[inline]
+pub fn is_effectively_hidden_within(
+    path: impl AsRef<Path>,
+    root: impl AsRef<Path>,
+) -> io::Result<bool> {
+    let inner = |path: &Path, root: &Path| -> io::Result<bool> {
+        let relative = path.strip_prefix(root).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not under {}", path.display(), root.display()),
+            )
+        })?;
+        if relative.as_os_str().is_empty() {
+            return Ok(false);
+        }
+        effectively_hidden(path, relative)
+    };
+    inner(path.as_ref(), root.as_ref())
+}
+
+/// Returns [`true`] if `path` or any of its ancestors which corresponds to a
+/// component of `relative` is hidden.
+fn effectively_hidden(path: &Path, relative: &Path) -> io::Result<bool> {
+    if imp::is_hidden(path)? {
+        return Ok(true);
+    }
+    let depth = relative.components().count();
+    for ancestor in path.ancestors().skip(1).take(depth.saturating_sub(1)) {
+        if matches!(
+            ancestor.components().next_back(),
+            Some(Component::Normal(_))
+        ) && imp::is_hidden(ancestor)?
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Hides a file or directory.
 ///
 /// # Platform-specific behavior
@@ -87,6 +214,9 @@ pub fn is_hidden(path: impl AsRef<Path>) -> io::Result<bool> {
 /// - On Unix, this function renames the file to start with `.`.
 /// - On Windows, this function sets the hidden file attribute to the file.
 ///
+/// If `path` is a symbolic link, this function hides the link itself, not its
+/// target. Use [`Options`](crate::Options) to change this behavior.
+///
 /// # Errors
 ///
 /// ## On Unix
@@ -173,6 +303,9 @@ pub fn hide(path: impl AsRef<Path>) -> io::Result<()> {
 ///   than `.`.
 /// - On Windows, this function clears the hidden file attribute to the file.
 ///
+/// If `path` is a symbolic link, this function shows the link itself, not its
+/// target. Use [`Options`](crate::Options) to change this behavior.
+///
 /// # Errors
 ///
 /// ## On Unix
@@ -258,3 +391,63 @@ pub fn show(path: impl AsRef<Path>) -> io::Result<()> {
     let inner = |path: &Path| -> io::Result<()> { imp::show(path) };
     inner(path.as_ref())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn is_effectively_hidden() {
+        assert!(super::is_effectively_hidden(".foo.txt").unwrap());
+        assert!(super::is_effectively_hidden("foo/.bar/baz.txt").unwrap());
+        assert!(super::is_effectively_hidden("/.foo/bar/baz.txt").unwrap());
+        assert!(super::is_effectively_hidden("./.foo/bar.txt").unwrap());
+        assert!(!super::is_effectively_hidden("foo/bar/baz.txt").unwrap());
+        assert!(!super::is_effectively_hidden("../foo/bar.txt").unwrap());
+        assert_eq!(
+            super::is_effectively_hidden(".foo/bar.txt/..")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn is_effectively_hidden_within() {
+        assert!(!super::is_effectively_hidden_within(".foo/bar/baz.txt", ".foo").unwrap());
+        assert!(!super::is_effectively_hidden_within(".foo/bar", ".foo/bar").unwrap());
+        assert!(super::is_effectively_hidden_within(".foo/.bar/baz.txt", ".foo").unwrap());
+        assert!(super::is_effectively_hidden_within(".foo/bar/.baz.txt", ".foo").unwrap());
+        assert!(
+            super::is_effectively_hidden_within("/home/.foo/.bar/baz.txt", "/home/.foo").unwrap()
+        );
+        assert_eq!(
+            super::is_effectively_hidden_within("foo/bar.txt", "baz")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn is_effectively_hidden_within() {
+        use std::fs::{self, File};
+
+        // The temporary directory may be in a hidden directory such as
+        // `AppData`, so only the entries under it are checked.
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join("foo/bar")).unwrap();
+        File::create(temp_dir.join("foo/bar/baz.txt")).unwrap();
+        let file_path = temp_dir.join("foo/bar/baz.txt");
+        assert!(!super::is_effectively_hidden_within(&file_path, temp_dir).unwrap());
+
+        crate::hide(temp_dir.join("foo")).unwrap();
+        assert!(super::is_effectively_hidden(&file_path).unwrap());
+        assert!(super::is_effectively_hidden_within(&file_path, temp_dir).unwrap());
+        assert!(!super::is_effectively_hidden_within(&file_path, temp_dir.join("foo")).unwrap());
+    }
+}
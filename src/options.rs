@@ -0,0 +1,285 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Options for the operations.
+
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+use crate::platform::imp;
+
+/// How symbolic links are handled by the operations.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum SymlinkPolicy {
+    /// Operate on the symbolic link itself.
+    ///
+    /// On Unix, the link is renamed. On Windows, the hidden file attribute of
+    /// the link is changed. The target of the link is never touched, and
+    /// dangling links can be hidden and shown.
+    #[default]
+    Link,
+
+    /// Operate on the final target of the symbolic link.
+    ///
+    /// Chains of symbolic links are resolved until a file which is not a
+    /// symbolic link is reached. The operation fails if the link is dangling.
+    Target,
+
+    /// Refuse to operate on a symbolic link.
+    ///
+    /// The operation fails with [`ErrorKind::InvalidInput`] if the path is a
+    /// symbolic link.
+    Refuse,
+}
+
+/// Options for [`hf::hide`](crate::hide), [`hf::show`](crate::show) and
+/// [`hf::is_hidden`](crate::is_hidden).
+///
+/// The functions of the crate root are equivalent to the methods of the
+/// default options.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::{fs::File, os::unix};
+/// #
+/// use hf::{Options, SymlinkPolicy};
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// let temp_dir = temp_dir.path();
+/// File::create(temp_dir.join("foo.txt")).unwrap();
+/// unix::fs::symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+///
+/// let options = Options::new().symlink_policy(SymlinkPolicy::Target);
+/// options.hide(temp_dir.join("bar.txt")).unwrap();
+/// assert!(temp_dir.join(".foo.txt").exists());
+/// assert!(temp_dir.join("bar.txt").is_symlink());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Options {
+    symlink_policy: SymlinkPolicy,
+}
+
+impl Options {
+    /// Creates a new `Options` with the default values.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            symlink_policy: SymlinkPolicy::Link,
+        }
+    }
+
+    /// Sets how symbolic links are handled.
+    ///
+    /// The default is [`SymlinkPolicy::Link`].
+    #[must_use]
+    #[inline]
+    pub const fn symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlink_policy = policy;
+        self
+    }
+
+    /// Returns [`true`] if the path is a hidden file or directory.
+    ///
+    /// See [`hf::is_hidden`](crate::is_hidden) for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if [`hf::is_hidden`](crate::is_hidden) returns an
+    /// error, or the path is a symbolic link which could not be handled by the
+    /// policy.
+    #[inline]
+    pub fn is_hidden(&self, path: impl AsRef<Path>) -> io::Result<bool> {
+        let inner = |path: &Path| -> io::Result<bool> { imp::is_hidden(&self.resolve(path)?) };
+        inner(path.as_ref())
+    }
+
+    /// Hides a file or directory.
+    ///
+    /// See [`hf::hide`](crate::hide) for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if [`hf::hide`](crate::hide) returns an error, or the
+    /// path is a symbolic link which could not be handled by the policy.
+    #[inline]
+    pub fn hide(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let inner = |path: &Path| -> io::Result<()> { imp::hide(&self.resolve(path)?) };
+        inner(path.as_ref())
+    }
+
+    /// Shows a hidden file or directory.
+    ///
+    /// See [`hf::show`](crate::show) for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if [`hf::show`](crate::show) returns an error, or the
+    /// path is a symbolic link which could not be handled by the policy.
+    #[inline]
+    pub fn show(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let inner = |path: &Path| -> io::Result<()> { imp::show(&self.resolve(path)?) };
+        inner(path.as_ref())
+    }
+
+    /// Returns the path which the operations should be applied to.
+    fn resolve(self, path: &Path) -> io::Result<Cow<'_, Path>> {
+        let is_symlink = || path.is_symlink();
+        match self.symlink_policy {
+            SymlinkPolicy::Target if is_symlink() => fs::canonicalize(path).map(Cow::Owned),
+            SymlinkPolicy::Refuse if is_symlink() => Err(Error::from(ErrorKind::InvalidInput)),
+            _ => Ok(Cow::Borrowed(path)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    #[cfg(unix)]
+    use std::os::unix::fs::symlink;
+    #[cfg(windows)]
+    use std::os::windows::fs::symlink_file as symlink;
+
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(Options::default(), Options::new());
+        assert_eq!(SymlinkPolicy::default(), SymlinkPolicy::Link);
+    }
+
+    #[test]
+    fn regular_file_with_any_policy() {
+        for policy in [
+            SymlinkPolicy::Link,
+            SymlinkPolicy::Target,
+            SymlinkPolicy::Refuse,
+        ] {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let file_path = temp_dir.path().join("foo.txt");
+            File::create(&file_path).unwrap();
+
+            let options = Options::new().symlink_policy(policy);
+            assert!(!options.is_hidden(&file_path).unwrap());
+            options.hide(&file_path).unwrap();
+            let file_path = crate::PathExt::hidden_name(file_path.as_path()).unwrap();
+            assert!(options.is_hidden(&file_path).unwrap());
+            options.show(&file_path).unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hide_link() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+
+        let options = Options::new();
+        options.hide(temp_dir.join("bar.txt")).unwrap();
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(temp_dir.join(".bar.txt").is_symlink());
+        assert!(options.is_hidden(temp_dir.join(".bar.txt")).unwrap());
+
+        options.show(temp_dir.join(".bar.txt")).unwrap();
+        assert!(temp_dir.join("bar.txt").is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hide_target() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+
+        let options = Options::new().symlink_policy(SymlinkPolicy::Target);
+        assert!(!options.is_hidden(temp_dir.join("bar.txt")).unwrap());
+        options.hide(temp_dir.join("bar.txt")).unwrap();
+        assert!(!temp_dir.join("foo.txt").exists());
+        assert!(temp_dir.join(".foo.txt").exists());
+        assert!(temp_dir.join("bar.txt").is_symlink());
+
+        symlink(".foo.txt", temp_dir.join("baz.txt")).unwrap();
+        assert!(options.is_hidden(temp_dir.join("baz.txt")).unwrap());
+        options.show(temp_dir.join("baz.txt")).unwrap();
+        assert!(temp_dir.join("foo.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hide_target_of_link_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+        symlink("bar.txt", temp_dir.join("baz.txt")).unwrap();
+
+        Options::new()
+            .symlink_policy(SymlinkPolicy::Target)
+            .hide(temp_dir.join("baz.txt"))
+            .unwrap();
+        assert!(temp_dir.join(".foo.txt").exists());
+        assert!(temp_dir.join("bar.txt").is_symlink());
+        assert!(temp_dir.join("baz.txt").is_symlink());
+    }
+
+    #[test]
+    fn dangling_link() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        let link_path = temp_dir.join("foo.txt");
+        symlink("bar.txt", &link_path).unwrap();
+
+        assert_eq!(
+            Options::new()
+                .symlink_policy(SymlinkPolicy::Target)
+                .hide(&link_path)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            Options::new()
+                .symlink_policy(SymlinkPolicy::Refuse)
+                .hide(&link_path)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+
+        Options::new().hide(&link_path).unwrap();
+        let link_path = crate::PathExt::hidden_name(link_path.as_path()).unwrap();
+        assert!(link_path.is_symlink());
+        assert!(Options::new().is_hidden(&link_path).unwrap());
+    }
+
+    #[test]
+    fn refuse() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+
+        let options = Options::new().symlink_policy(SymlinkPolicy::Refuse);
+        for result in [
+            options.is_hidden(temp_dir.join("bar.txt")).map(|_| ()),
+            options.hide(temp_dir.join("bar.txt")),
+            options.show(temp_dir.join("bar.txt")),
+        ] {
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+        }
+        assert!(temp_dir.join("bar.txt").is_symlink());
+    }
+}
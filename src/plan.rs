@@ -0,0 +1,738 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Planned operations with companion files and rollback.
+
+use std::{
+    collections::BTreeSet,
+    ffi::{OsStr, OsString},
+    fs,
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use crate::{NamingScheme, platform::imp};
+
+/// The pattern of AppleDouble files, which macOS creates to store metadata on
+/// file systems which don't support it.
+#[allow(clippy::doc_markdown)]
+const APPLE_DOUBLE: &str = "._{name}";
+
+/// Patterns of companion files which follow the main file when it is hidden
+/// or shown.
+///
+/// A pattern is a file name which may contain the following placeholders:
+///
+/// - `{name}`: the file name of the main file.
+/// - `{stem}`: the file name of the main file without the extension.
+///
+/// For example, `{stem}.xmp` matches `IMG_1.xmp` for `IMG_1.jpg`, and
+/// `._{name}` matches the AppleDouble file `._IMG_1.jpg`. The companion files
+/// are in the same directory as the main file.
+///
+/// # Platform-specific behavior
+///
+/// - On Unix, a companion file is renamed to the pattern applied to the new
+///   name of the main file. For example, when `IMG_1.jpg` is hidden,
+///   `IMG_1.xmp` is renamed to `.IMG_1.xmp` and `._IMG_1.jpg` is renamed to
+///   `._.IMG_1.jpg`. The default contains the pattern of AppleDouble files.
+/// - On Windows, the hidden file attribute of a companion file is changed in
+///   the same way as the main file. The default is empty.
+#[allow(clippy::doc_markdown)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Companions {
+    patterns: Vec<String>,
+}
+
+impl Companions {
+    /// Creates a new `Companions` without any patterns.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Adds a pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::path::Path;
+    /// #
+    /// use hf::Companions;
+    ///
+    /// let companions = Companions::new().pattern("{stem}.xmp");
+    /// assert_eq!(
+    ///     companions.paths("photos/IMG_1.jpg"),
+    ///     [Path::new("photos/IMG_1.xmp")]
+    /// );
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    /// Adds the pattern of AppleDouble files, `._{name}`.
+    #[allow(clippy::doc_markdown)]
+    #[must_use]
+    #[inline]
+    pub fn apple_double(self) -> Self {
+        self.pattern(APPLE_DOUBLE)
+    }
+
+    /// Returns the patterns.
+    #[must_use]
+    #[inline]
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns the paths of the companion files of `path`.
+    ///
+    /// The paths are returned whether the files exist or not. The patterns
+    /// which would result in an invalid file name or `path` itself are
+    /// ignored, as are all patterns if the file name of `path` is not valid
+    /// UTF-8.
+    #[must_use]
+    #[inline]
+    pub fn paths(&self, path: impl AsRef<Path>) -> Vec<PathBuf> {
+        let inner = |path: &Path| -> Vec<PathBuf> {
+            let Some(file_name) = path.file_name() else {
+                return Vec::new();
+            };
+            self.patterns
+                .iter()
+                .filter_map(|p| expand(p, file_name))
+                .map(|n| path.with_file_name(n))
+                .collect()
+        };
+        inner(path.as_ref())
+    }
+}
+
+impl Default for Companions {
+    #[inline]
+    fn default() -> Self {
+        let companions = Self::new();
+        #[cfg(unix)]
+        let companions = companions.apple_double();
+        companions
+    }
+}
+
+/// A step of a [`Plan`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Step {
+    path: PathBuf,
+    dest: PathBuf,
+    hide: bool,
+    is_companion: bool,
+}
+
+impl Step {
+    /// Returns the path of the file before this step.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of the file after this step.
+    ///
+    /// On Windows, this is the same as [`Step::path`] unless the step was
+    /// added with a [`NamingScheme`].
+    #[must_use]
+    #[inline]
+    pub fn dest(&self) -> &Path {
+        &self.dest
+    }
+
+    /// Returns [`true`] if this step hides the file, and [`false`] if this
+    /// step shows the file.
+    #[must_use]
+    #[inline]
+    pub const fn is_hide(&self) -> bool {
+        self.hide
+    }
+
+    /// Returns [`true`] if the file is a companion file.
+    #[must_use]
+    #[inline]
+    pub const fn is_companion(&self) -> bool {
+        self.is_companion
+    }
+
+    pub(crate) fn apply(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            crate::platform::imp::rename(&self.path, &self.dest)
+        }
+        #[cfg(windows)]
+        if self.path != self.dest {
+            fs::rename(&self.path, &self.dest)
+        } else if self.hide {
+            crate::hide(&self.path)
+        } else {
+            crate::show(&self.path)
+        }
+    }
+
+    pub(crate) fn revert(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            crate::platform::imp::rename(&self.dest, &self.path)
+        }
+        #[cfg(windows)]
+        if self.path != self.dest {
+            fs::rename(&self.dest, &self.path)
+        } else if self.hide {
+            crate::show(&self.path)
+        } else {
+            crate::hide(&self.path)
+        }
+    }
+}
+
+/// A plan of hiding and showing files, which is executed as a whole.
+///
+/// The steps are checked when they are added, so that the files exist and no
+/// existing files are overwritten. If a step fails while executing the plan,
+/// the steps which have already been executed are reverted.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// use std::fs::File;
+///
+/// use hf::{Companions, Plan};
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// let temp_dir = temp_dir.path();
+/// File::create(temp_dir.join("IMG_1.jpg")).unwrap();
+/// File::create(temp_dir.join("IMG_1.xmp")).unwrap();
+///
+/// let companions = Companions::default().pattern("{stem}.xmp");
+/// let mut plan = Plan::new();
+/// plan.hide(temp_dir.join("IMG_1.jpg"), &companions).unwrap();
+/// assert_eq!(plan.steps().len(), 2);
+///
+/// plan.execute().unwrap();
+/// assert!(temp_dir.join(".IMG_1.jpg").exists());
+/// assert!(temp_dir.join(".IMG_1.xmp").exists());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Plan {
+    steps: Vec<Step>,
+    // Used to detect conflicts without scanning all the steps.
+    paths: BTreeSet<PathBuf>,
+    dests: BTreeSet<PathBuf>,
+}
+
+impl Plan {
+    /// Creates a new empty `Plan`.
+    #[must_use]
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            paths: BTreeSet::new(),
+            dests: BTreeSet::new(),
+        }
+    }
+
+    /// Adds steps to hide `path` and its companion files which exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `path` does not exist.
+    /// - `path` could not be hidden, such as it is already hidden on Unix.
+    /// - `path` or a companion file is already in this plan.
+    /// - The file after renaming already exists on Unix.
+    #[inline]
+    pub fn hide(&mut self, path: impl AsRef<Path>, companions: &Companions) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let dest =
+                imp::hidden_name(path).ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            self.push(path, &dest, true, companions)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Adds steps to show `path` and its companion files which exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `path` does not exist.
+    /// - `path` could not be shown, such as it is not hidden on Unix.
+    /// - `path` or a companion file is already in this plan.
+    /// - The file after renaming already exists on Unix.
+    #[inline]
+    pub fn show(&mut self, path: impl AsRef<Path>, companions: &Companions) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let dest =
+                imp::visible_name(path).ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            self.push(path, &dest, false, companions)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Adds steps to hide `path` and its companion files which exist by
+    /// renaming them with `scheme`.
+    ///
+    /// Unlike [`Plan::hide`], the files are renamed on every platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `path` does not exist.
+    /// - `path` could not be hidden, such as it is already hidden under
+    ///   `scheme`.
+    /// - `path` or a companion file is already in this plan.
+    /// - The file after renaming already exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs::File;
+    ///
+    /// use hf::{Companions, Plan, scheme::Suffix};
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// let temp_dir = temp_dir.path();
+    /// File::create(temp_dir.join("foo.txt")).unwrap();
+    ///
+    /// let mut plan = Plan::new();
+    /// plan.hide_with(
+    ///     temp_dir.join("foo.txt"),
+    ///     &Suffix::new("~"),
+    ///     &Companions::new(),
+    /// )
+    /// .unwrap();
+    /// plan.execute().unwrap();
+    /// assert!(temp_dir.join("foo.txt~").exists());
+    /// ```
+    #[inline]
+    pub fn hide_with(
+        &mut self,
+        path: impl AsRef<Path>,
+        scheme: &(impl NamingScheme + ?Sized),
+        companions: &Companions,
+    ) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let dest = scheme
+                .hidden_path(path)
+                .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            self.push(path, &dest, true, companions)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Adds steps to show `path` and its companion files which exist by
+    /// renaming them with `scheme`.
+    ///
+    /// Unlike [`Plan::show`], the files are renamed on every platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any of the following are true:
+    ///
+    /// - `path` does not exist.
+    /// - `path` could not be shown, such as it is not hidden under `scheme`.
+    /// - `path` or a companion file is already in this plan.
+    /// - The file after renaming already exists.
+    #[inline]
+    pub fn show_with(
+        &mut self,
+        path: impl AsRef<Path>,
+        scheme: &(impl NamingScheme + ?Sized),
+        companions: &Companions,
+    ) -> io::Result<()> {
+        let mut inner = |path: &Path| -> io::Result<()> {
+            let dest = scheme
+                .visible_path(path)
+                .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+            self.push(path, &dest, false, companions)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Returns the steps of this plan.
+    #[must_use]
+    #[inline]
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns [`true`] if this plan has no steps.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Moves all the steps of `other` to the end of this plan.
+    ///
+    /// This allows plans for different files to be made concurrently, and
+    /// then combined. Only the conflicts between the steps are checked, so the
+    /// file system is not accessed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if a file in `other` is already in this plan. In that
+    /// case, this plan is not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::fs::File;
+    ///
+    /// use hf::{Companions, Plan};
+    ///
+    /// let temp_dir = tempfile::tempdir().unwrap();
+    /// let temp_dir = temp_dir.path();
+    /// File::create(temp_dir.join("foo.txt")).unwrap();
+    ///
+    /// let companions = Companions::default();
+    /// let mut plan = Plan::new();
+    /// plan.hide(temp_dir.join("foo.txt"), &companions).unwrap();
+    /// let mut other = Plan::new();
+    /// other.hide(temp_dir.join("foo.txt"), &companions).unwrap();
+    /// assert!(plan.append(other).is_err());
+    /// assert_eq!(plan.steps().len(), 1);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: Self) -> io::Result<()> {
+        for step in &other.steps {
+            self.check_conflict(&step.path, &step.dest)?;
+        }
+        self.paths.extend(other.paths);
+        self.dests.extend(other.dests);
+        self.steps.extend(other.steps);
+        Ok(())
+    }
+
+    /// Executes this plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any step fails. In that case, the steps which have
+    /// already been executed are reverted in the reverse order as far as
+    /// possible.
+    #[inline]
+    pub fn execute(&self) -> io::Result<()> {
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Err(err) = step.apply() {
+                for step in self.steps[..i].iter().rev() {
+                    let _ = step.revert();
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reverts this plan after it has been executed.
+    ///
+    /// The steps are reverted in the reverse order. If a step fails, the
+    /// remaining steps are still reverted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if any step fails to be reverted. In that case, the
+    /// first error is returned.
+    #[inline]
+    pub fn revert(&self) -> io::Result<()> {
+        let mut result = Ok(());
+        for step in self.steps.iter().rev() {
+            if let Err(err) = step.revert() {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+
+    fn push(
+        &mut self,
+        path: &Path,
+        dest: &Path,
+        hide: bool,
+        companions: &Companions,
+    ) -> io::Result<()> {
+        let mut steps = vec![self.step(path, dest, hide, false)?];
+        let companion_dests = companions.paths(dest);
+        for (path, dest) in companions.paths(path).into_iter().zip(companion_dests) {
+            if fs::symlink_metadata(&path).is_ok() {
+                steps.push(self.step(&path, &dest, hide, true)?);
+            }
+        }
+        for step in steps {
+            self.paths.insert(step.path.clone());
+            self.dests.insert(step.dest.clone());
+            self.steps.push(step);
+        }
+        Ok(())
+    }
+
+    /// Returns an error if `path` or `dest` conflicts with the steps of this
+    /// plan.
+    fn check_conflict(&self, path: &Path, dest: &Path) -> io::Result<()> {
+        if self.paths.contains(path) || self.dests.contains(path) || self.dests.contains(dest) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is already planned", path.display()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn step(&self, path: &Path, dest: &Path, hide: bool, is_companion: bool) -> io::Result<Step> {
+        fs::symlink_metadata(path)?;
+        self.check_conflict(path, dest)?;
+        if path != dest && fs::symlink_metadata(dest).is_ok() {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} already exists", dest.display()),
+            ));
+        }
+        Ok(Step {
+            path: path.to_path_buf(),
+            dest: dest.to_path_buf(),
+            hide,
+            is_companion,
+        })
+    }
+}
+
+/// Applies `pattern` to `file_name`.
+#[allow(clippy::literal_string_with_formatting_args)]
+fn expand(pattern: &str, file_name: &OsStr) -> Option<OsString> {
+    let name = file_name.to_str()?;
+    let stem = Path::new(name).file_stem().and_then(OsStr::to_str)?;
+    let expanded = pattern.replace("{name}", name).replace("{stem}", stem);
+    let is_valid = !expanded.is_empty()
+        && expanded != "."
+        && expanded != ".."
+        && expanded != name
+        && !expanded.contains(['/', '\\']);
+    is_valid.then(|| expanded.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn expand() {
+        let file_name = OsStr::new("IMG_1.jpg");
+        assert_eq!(super::expand("{stem}.xmp", file_name).unwrap(), "IMG_1.xmp");
+        assert_eq!(super::expand("._{name}", file_name).unwrap(), "._IMG_1.jpg");
+        assert_eq!(
+            super::expand("{name}.{stem}", file_name).unwrap(),
+            "IMG_1.jpg.IMG_1"
+        );
+        assert!(super::expand("{name}", file_name).is_none());
+        assert!(super::expand("{stem}.jpg", file_name).is_none());
+        assert!(super::expand("foo/{name}", file_name).is_none());
+        assert!(super::expand("..", file_name).is_none());
+        assert_eq!(
+            super::expand("{stem}.xmp", OsStr::new(".bashrc")).unwrap(),
+            ".bashrc.xmp"
+        );
+    }
+
+    #[test]
+    fn companions() {
+        assert!(Companions::new().patterns().is_empty());
+        #[cfg(unix)]
+        assert_eq!(Companions::default().patterns(), ["._{name}"]);
+        #[cfg(windows)]
+        assert!(Companions::default().patterns().is_empty());
+
+        let companions = Companions::new().apple_double().pattern("{stem}.xmp");
+        assert_eq!(
+            companions.paths("foo/IMG_1.jpg"),
+            [Path::new("foo/._IMG_1.jpg"), Path::new("foo/IMG_1.xmp")]
+        );
+        assert!(companions.paths("foo/..").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hide_and_show_with_companions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in ["IMG_1.jpg", "IMG_1.xmp", "._IMG_1.jpg", "IMG_2.jpg"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+        let companions = Companions::default().pattern("{stem}.xmp");
+
+        let mut plan = Plan::new();
+        plan.hide(temp_dir.join("IMG_1.jpg"), &companions).unwrap();
+        plan.hide(temp_dir.join("IMG_2.jpg"), &companions).unwrap();
+        assert_eq!(plan.steps().len(), 4);
+        assert!(!plan.steps()[0].is_companion());
+        assert!(plan.steps()[1].is_companion());
+        assert_eq!(plan.steps()[1].path(), temp_dir.join("._IMG_1.jpg"));
+        assert_eq!(plan.steps()[1].dest(), temp_dir.join("._.IMG_1.jpg"));
+        plan.execute().unwrap();
+        for name in [".IMG_1.jpg", ".IMG_1.xmp", "._.IMG_1.jpg", ".IMG_2.jpg"] {
+            assert!(temp_dir.join(name).exists());
+        }
+
+        let mut plan = Plan::new();
+        plan.show(temp_dir.join(".IMG_1.jpg"), &companions).unwrap();
+        plan.execute().unwrap();
+        for name in ["IMG_1.jpg", "IMG_1.xmp", "._IMG_1.jpg"] {
+            assert!(temp_dir.join(name).exists());
+        }
+    }
+
+    #[allow(clippy::literal_string_with_formatting_args)]
+    #[test]
+    fn hide_and_show_with_scheme() {
+        use crate::scheme::Prefix;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in ["bashrc", "bashrc.xmp", "dot_profile"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+        let scheme = Prefix::new("dot_");
+        let companions = Companions::new().pattern("{name}.xmp");
+
+        let mut plan = Plan::new();
+        plan.hide_with(temp_dir.join("bashrc"), &scheme, &companions)
+            .unwrap();
+        assert_eq!(
+            plan.hide_with(temp_dir.join("dot_profile"), &scheme, &companions)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+        plan.execute().unwrap();
+        assert!(temp_dir.join("dot_bashrc").exists());
+        assert!(temp_dir.join("dot_bashrc.xmp").exists());
+
+        let mut plan = Plan::new();
+        plan.show_with(temp_dir.join("dot_profile"), &scheme, &companions)
+            .unwrap();
+        plan.execute().unwrap();
+        assert!(temp_dir.join("profile").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plan_refuses_conflicts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in ["foo.txt", ".foo.txt", "bar.txt", "._bar.txt", "._.bar.txt"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+        let companions = Companions::default();
+
+        let mut plan = Plan::new();
+        assert_eq!(
+            plan.hide(temp_dir.join("foo.txt"), &companions)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::AlreadyExists
+        );
+        assert_eq!(
+            plan.hide(temp_dir.join("bar.txt"), &companions)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::AlreadyExists
+        );
+        assert_eq!(
+            plan.hide(temp_dir.join("baz.txt"), &companions)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            plan.hide(temp_dir.join(".foo.txt"), &companions)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+        assert!(plan.is_empty());
+
+        fs::remove_file(temp_dir.join(".foo.txt")).unwrap();
+        plan.hide(temp_dir.join("foo.txt"), &companions).unwrap();
+        assert_eq!(
+            plan.hide(temp_dir.join("foo.txt"), &companions)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(plan.steps().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_rolls_back_on_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in ["foo.txt", "._foo.txt", "bar.txt"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+        let companions = Companions::default();
+
+        let mut plan = Plan::new();
+        plan.hide(temp_dir.join("foo.txt"), &companions).unwrap();
+        plan.hide(temp_dir.join("bar.txt"), &companions).unwrap();
+        fs::remove_file(temp_dir.join("bar.txt")).unwrap();
+
+        assert_eq!(plan.execute().unwrap_err().kind(), ErrorKind::NotFound);
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(temp_dir.join("._foo.txt").exists());
+        assert!(!temp_dir.join(".foo.txt").exists());
+        assert!(!temp_dir.join("._.foo.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn revert() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        for name in ["foo.txt", "._foo.txt", ".bar.txt"] {
+            File::create(temp_dir.join(name)).unwrap();
+        }
+        let companions = Companions::default();
+
+        let mut plan = Plan::new();
+        plan.hide(temp_dir.join("foo.txt"), &companions).unwrap();
+        plan.show(temp_dir.join(".bar.txt"), &companions).unwrap();
+        plan.execute().unwrap();
+        assert!(temp_dir.join(".foo.txt").exists());
+        assert!(temp_dir.join("._.foo.txt").exists());
+        assert!(temp_dir.join("bar.txt").exists());
+
+        plan.revert().unwrap();
+        assert!(temp_dir.join("foo.txt").exists());
+        assert!(temp_dir.join("._foo.txt").exists());
+        assert!(temp_dir.join(".bar.txt").exists());
+        assert!(!temp_dir.join(".foo.txt").exists());
+        assert!(!temp_dir.join("bar.txt").exists());
+    }
+}
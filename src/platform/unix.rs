@@ -11,25 +11,78 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use crate::{
+    Dir,
+    scheme::{Dot, NamingScheme},
+};
+
+#[inline]
+pub(crate) fn is_hidden_name(file_name: &OsStr) -> bool {
+    file_name.to_string_lossy().starts_with('.')
+}
+
 #[inline]
 pub(crate) fn is_hidden(path: &Path) -> io::Result<bool> {
     let file_name = path
         .file_name()
         .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
-    let is_hidden = file_name.to_string_lossy().starts_with('.');
+    let is_hidden = is_hidden_name(file_name);
     Ok(is_hidden)
 }
 
+#[inline]
+pub(crate) fn hidden_name(path: &Path) -> Option<PathBuf> {
+    hidden_file_name(path)
+}
+
+#[inline]
+pub(crate) fn visible_name(path: &Path) -> Option<PathBuf> {
+    normal_file_name(path)
+}
+
 #[inline]
 pub(crate) fn hide(path: &Path) -> io::Result<()> {
     let dest_path = hidden_file_name(path).ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
-    fs::rename(path, dest_path)
+    rename(path, &dest_path)
 }
 
 #[inline]
 pub(crate) fn show(path: &Path) -> io::Result<()> {
     let dest_path = normal_file_name(path).ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
-    fs::rename(path, dest_path)
+    rename(path, &dest_path)
+}
+
+/// Renames `path` to `dest_path`, which must be in the same directory.
+///
+/// The file is renamed relative to the opened parent directory. If the parent
+/// directory could not be opened because it is not readable, which does not
+/// happen on Linux and Android, this falls back to renaming by the paths.
+#[inline]
+pub(crate) fn rename(path: &Path, dest_path: &Path) -> io::Result<()> {
+    let (parent, file_name) = split_path(path)?;
+    let dest_name = dest_path
+        .file_name()
+        .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+    match Dir::open_path(parent) {
+        Ok(dir) => dir.rename(file_name, dest_name),
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            fs::rename(path, parent.join(dest_name))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Splits `path` into the parent directory and the file name, so that the
+/// file can be renamed relative to the opened parent directory.
+pub(crate) fn split_path(path: &Path) -> io::Result<(&Path, &OsStr)> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::from(ErrorKind::InvalidInput))?;
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    Ok((parent, file_name))
 }
 
 /// Returns the path after making `path` invisible.
@@ -57,15 +110,7 @@ pub(crate) fn show(path: &Path) -> io::Result<()> {
 /// ```
 #[inline]
 pub fn hidden_file_name(path: impl AsRef<Path>) -> Option<PathBuf> {
-    let inner = |path: &Path| -> Option<PathBuf> {
-        let file_name = path
-            .file_name()
-            .map(OsStr::to_string_lossy)
-            .filter(|n| !n.starts_with('.'))?;
-        let dest_path = path.with_file_name(String::from('.') + &file_name);
-        Some(dest_path)
-    };
-    inner(path.as_ref())
+    Dot.hidden_path(path.as_ref())
 }
 
 /// Returns the path after making `path` visible.
@@ -93,20 +138,12 @@ pub fn hidden_file_name(path: impl AsRef<Path>) -> Option<PathBuf> {
 /// ```
 #[inline]
 pub fn normal_file_name(path: impl AsRef<Path>) -> Option<PathBuf> {
-    let inner = |path: &Path| -> Option<PathBuf> {
-        let file_name = path
-            .file_name()
-            .map(OsStr::to_string_lossy)
-            .filter(|n| n.starts_with('.'))?;
-        let dest_path = path.with_file_name(file_name.trim_start_matches('.'));
-        Some(dest_path)
-    };
-    inner(path.as_ref())
+    Dot.visible_path(path.as_ref())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs::File;
+    use std::fs::{self, File};
 
     use super::*;
 
@@ -226,6 +263,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hide_and_show_in_unreadable_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join("foo");
+        fs::create_dir(&dir).unwrap();
+        File::create(dir.join("bar.txt")).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o300)).unwrap();
+
+        let result =
+            super::hide(&dir.join("bar.txt")).and_then(|()| super::show(&dir.join(".bar.txt")));
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        result.unwrap();
+        assert!(dir.join("bar.txt").exists());
+    }
+
     #[test]
     fn hide_when_hidden_file() {
         {
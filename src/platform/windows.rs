@@ -5,20 +5,26 @@
 //! Provides functionality for Windows.
 
 use std::{
-    fs,
+    fs::{self, Metadata},
     io::{self, Error},
     os::windows::fs::MetadataExt,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use windows::{Win32::Storage::FileSystem, core::HSTRING};
 
 fn get_file_attributes(path: &Path) -> io::Result<FileSystem::FILE_FLAGS_AND_ATTRIBUTES> {
-    let attributes = fs::metadata(path)?.file_attributes();
+    let attributes = fs::symlink_metadata(path)?.file_attributes();
     let attributes = FileSystem::FILE_FLAGS_AND_ATTRIBUTES(attributes);
     Ok(attributes)
 }
 
+#[inline]
+pub fn has_hidden_attribute(metadata: &Metadata) -> bool {
+    let attributes = FileSystem::FILE_FLAGS_AND_ATTRIBUTES(metadata.file_attributes());
+    attributes.contains(FileSystem::FILE_ATTRIBUTE_HIDDEN)
+}
+
 #[inline]
 pub fn is_hidden(path: &Path) -> io::Result<bool> {
     let attributes = get_file_attributes(path)?;
@@ -26,6 +32,16 @@ pub fn is_hidden(path: &Path) -> io::Result<bool> {
     Ok(is_hidden)
 }
 
+#[inline]
+pub fn hidden_name(path: &Path) -> Option<PathBuf> {
+    path.file_name().map(|_| path.to_path_buf())
+}
+
+#[inline]
+pub fn visible_name(path: &Path) -> Option<PathBuf> {
+    path.file_name().map(|_| path.to_path_buf())
+}
+
 #[inline]
 pub fn hide(path: &Path) -> io::Result<()> {
     let attributes = get_file_attributes(path)? | FileSystem::FILE_ATTRIBUTE_HIDDEN;
@@ -0,0 +1,365 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Naming schemes which mark files as hidden by their names.
+//!
+//! On Unix, a file is hidden if its name starts with `.`, which is the [`Dot`]
+//! scheme. Some tools use other conventions, such as the `dot_` prefix of
+//! chezmoi, the trailing `~` of backup files or the `_` prefix of drafts.
+//! These can be handled with [`Prefix`] and [`Suffix`], or with a custom
+//! implementation of [`NamingScheme`].
+//!
+//! Unlike the hidden file attribute on Windows, these schemes always hide and
+//! show files by renaming them, on every platform. Use
+//! [`Plan::hide_with`](crate::Plan::hide_with) and
+//! [`Plan::show_with`](crate::Plan::show_with) to rename files with a scheme.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::path::Path;
+//!
+//! use hf::scheme::{NamingScheme, Prefix};
+//!
+//! let scheme = Prefix::new("dot_");
+//! assert_eq!(
+//!     scheme.hidden_path(Path::new("foo/bashrc")).as_deref(),
+//!     Some(Path::new("foo/dot_bashrc"))
+//! );
+//! assert_eq!(
+//!     scheme.visible_path(Path::new("foo/dot_bashrc")).as_deref(),
+//!     Some(Path::new("foo/bashrc"))
+//! );
+//! ```
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+/// A convention which marks files as hidden by their names.
+pub trait NamingScheme {
+    /// Returns [`true`] if `file_name` is hidden under this scheme.
+    fn is_hidden_name(&self, file_name: &OsStr) -> bool;
+
+    /// Returns the file name after making `file_name` hidden.
+    ///
+    /// Returns [`None`] if `file_name` is already hidden or could not be
+    /// hidden.
+    fn hidden_name(&self, file_name: &OsStr) -> Option<OsString>;
+
+    /// Returns the file name after making `file_name` visible.
+    ///
+    /// Returns [`None`] if `file_name` is not hidden or could not be made
+    /// visible.
+    fn visible_name(&self, file_name: &OsStr) -> Option<OsString>;
+
+    /// Returns the path after making `path` hidden.
+    ///
+    /// Returns [`None`] if `path` terminates in `..` or [`hidden_name`] returns
+    /// [`None`].
+    ///
+    /// [`hidden_name`]: NamingScheme::hidden_name
+    #[inline]
+    fn hidden_path(&self, path: &Path) -> Option<PathBuf> {
+        let file_name = self.hidden_name(path.file_name()?)?;
+        Some(path.with_file_name(file_name))
+    }
+
+    /// Returns the path after making `path` visible.
+    ///
+    /// Returns [`None`] if `path` terminates in `..` or [`visible_name`]
+    /// returns [`None`].
+    ///
+    /// [`visible_name`]: NamingScheme::visible_name
+    #[inline]
+    fn visible_path(&self, path: &Path) -> Option<PathBuf> {
+        let file_name = self.visible_name(path.file_name()?)?;
+        Some(path.with_file_name(file_name))
+    }
+}
+
+/// The scheme of Unix, which hides files by prefixing `.` to their names.
+///
+/// Making a file visible removes all the leading `.`s.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Dot;
+
+impl NamingScheme for Dot {
+    #[inline]
+    fn is_hidden_name(&self, file_name: &OsStr) -> bool {
+        strip_prefix(file_name, ".").is_some()
+    }
+
+    #[inline]
+    fn hidden_name(&self, file_name: &OsStr) -> Option<OsString> {
+        if self.is_hidden_name(file_name) {
+            return None;
+        }
+        let mut hidden_name = OsString::from(".");
+        hidden_name.push(file_name);
+        Some(hidden_name)
+    }
+
+    #[inline]
+    fn visible_name(&self, file_name: &OsStr) -> Option<OsString> {
+        let mut visible_name = strip_prefix(file_name, ".")?;
+        while let Some(name) = strip_prefix(&visible_name, ".") {
+            visible_name = name;
+        }
+        (!visible_name.is_empty()).then_some(visible_name)
+    }
+}
+
+/// A scheme which hides files by prefixing a string to their names, such as
+/// `dot_` or `_`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Prefix {
+    prefix: String,
+}
+
+impl Prefix {
+    /// Creates a new `Prefix` with the given prefix.
+    #[must_use]
+    #[inline]
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+
+    /// Returns the prefix.
+    #[must_use]
+    #[inline]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+
+impl NamingScheme for Prefix {
+    #[inline]
+    fn is_hidden_name(&self, file_name: &OsStr) -> bool {
+        strip_prefix(file_name, &self.prefix).is_some_and(|n| !n.is_empty())
+    }
+
+    #[inline]
+    fn hidden_name(&self, file_name: &OsStr) -> Option<OsString> {
+        if self.prefix.is_empty() || self.is_hidden_name(file_name) {
+            return None;
+        }
+        let mut hidden_name = OsString::from(&self.prefix);
+        hidden_name.push(file_name);
+        Some(hidden_name)
+    }
+
+    #[inline]
+    fn visible_name(&self, file_name: &OsStr) -> Option<OsString> {
+        strip_prefix(file_name, &self.prefix).filter(|n| !n.is_empty() && !self.prefix.is_empty())
+    }
+}
+
+/// A scheme which hides files by appending a string to their names, such as
+/// `~`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Suffix {
+    suffix: String,
+}
+
+impl Suffix {
+    /// Creates a new `Suffix` with the given suffix.
+    #[must_use]
+    #[inline]
+    pub fn new(suffix: impl Into<String>) -> Self {
+        Self {
+            suffix: suffix.into(),
+        }
+    }
+
+    /// Returns the suffix.
+    #[must_use]
+    #[inline]
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+}
+
+impl NamingScheme for Suffix {
+    #[inline]
+    fn is_hidden_name(&self, file_name: &OsStr) -> bool {
+        strip_suffix(file_name, &self.suffix).is_some_and(|n| !n.is_empty())
+    }
+
+    #[inline]
+    fn hidden_name(&self, file_name: &OsStr) -> Option<OsString> {
+        if self.suffix.is_empty() || self.is_hidden_name(file_name) {
+            return None;
+        }
+        let mut hidden_name = file_name.to_os_string();
+        hidden_name.push(&self.suffix);
+        Some(hidden_name)
+    }
+
+    #[inline]
+    fn visible_name(&self, file_name: &OsStr) -> Option<OsString> {
+        strip_suffix(file_name, &self.suffix).filter(|n| !n.is_empty() && !self.suffix.is_empty())
+    }
+}
+
+impl<T: NamingScheme + ?Sized> NamingScheme for &T {
+    #[inline]
+    fn is_hidden_name(&self, file_name: &OsStr) -> bool {
+        (**self).is_hidden_name(file_name)
+    }
+
+    #[inline]
+    fn hidden_name(&self, file_name: &OsStr) -> Option<OsString> {
+        (**self).hidden_name(file_name)
+    }
+
+    #[inline]
+    fn visible_name(&self, file_name: &OsStr) -> Option<OsString> {
+        (**self).visible_name(file_name)
+    }
+}
+
+impl<T: NamingScheme + ?Sized> NamingScheme for Box<T> {
+    #[inline]
+    fn is_hidden_name(&self, file_name: &OsStr) -> bool {
+        (**self).is_hidden_name(file_name)
+    }
+
+    #[inline]
+    fn hidden_name(&self, file_name: &OsStr) -> Option<OsString> {
+        (**self).hidden_name(file_name)
+    }
+
+    #[inline]
+    fn visible_name(&self, file_name: &OsStr) -> Option<OsString> {
+        (**self).visible_name(file_name)
+    }
+}
+
+/// Returns `name` without `prefix`.
+fn strip_prefix(name: &OsStr, prefix: &str) -> Option<OsString> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = name.as_bytes().strip_prefix(prefix.as_bytes())?;
+        Some(OsStr::from_bytes(name).to_os_string())
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+        let (name, prefix) = (
+            name.encode_wide().collect::<Vec<_>>(),
+            prefix.encode_utf16().collect::<Vec<_>>(),
+        );
+        let name = name.strip_prefix(prefix.as_slice())?;
+        Some(OsString::from_wide(name))
+    }
+}
+
+/// Returns `name` without `suffix`.
+fn strip_suffix(name: &OsStr, suffix: &str) -> Option<OsString> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = name.as_bytes().strip_suffix(suffix.as_bytes())?;
+        Some(OsStr::from_bytes(name).to_os_string())
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+        let (name, suffix) = (
+            name.encode_wide().collect::<Vec<_>>(),
+            suffix.encode_utf16().collect::<Vec<_>>(),
+        );
+        let name = name.strip_suffix(suffix.as_slice())?;
+        Some(OsString::from_wide(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(name: &str) -> &OsStr {
+        OsStr::new(name)
+    }
+
+    #[test]
+    fn dot() {
+        assert!(Dot.is_hidden_name(name(".foo")));
+        assert!(!Dot.is_hidden_name(name("foo")));
+        assert_eq!(Dot.hidden_name(name("foo")).unwrap(), ".foo");
+        assert!(Dot.hidden_name(name(".foo")).is_none());
+        assert_eq!(Dot.visible_name(name(".foo")).unwrap(), "foo");
+        assert_eq!(Dot.visible_name(name("..foo")).unwrap(), "foo");
+        assert!(Dot.visible_name(name("foo")).is_none());
+        assert!(Dot.visible_name(name("...")).is_none());
+    }
+
+    #[test]
+    fn prefix() {
+        let scheme = Prefix::new("dot_");
+        assert_eq!(scheme.prefix(), "dot_");
+        assert!(scheme.is_hidden_name(name("dot_bashrc")));
+        assert!(!scheme.is_hidden_name(name("dot_")));
+        assert!(!scheme.is_hidden_name(name("bashrc")));
+        assert_eq!(scheme.hidden_name(name("bashrc")).unwrap(), "dot_bashrc");
+        assert!(scheme.hidden_name(name("dot_bashrc")).is_none());
+        assert_eq!(scheme.visible_name(name("dot_bashrc")).unwrap(), "bashrc");
+        assert_eq!(
+            scheme.visible_name(name("dot_dot_bashrc")).unwrap(),
+            "dot_bashrc"
+        );
+        assert!(scheme.visible_name(name("bashrc")).is_none());
+        assert!(scheme.visible_name(name("dot_")).is_none());
+
+        assert!(Prefix::new("").hidden_name(name("foo")).is_none());
+        assert!(Prefix::new("").visible_name(name("foo")).is_none());
+    }
+
+    #[test]
+    fn suffix() {
+        let scheme = Suffix::new("~");
+        assert_eq!(scheme.suffix(), "~");
+        assert!(scheme.is_hidden_name(name("foo.txt~")));
+        assert!(!scheme.is_hidden_name(name("~")));
+        assert_eq!(scheme.hidden_name(name("foo.txt")).unwrap(), "foo.txt~");
+        assert!(scheme.hidden_name(name("foo.txt~")).is_none());
+        assert_eq!(scheme.visible_name(name("foo.txt~")).unwrap(), "foo.txt");
+        assert!(scheme.visible_name(name("foo.txt")).is_none());
+    }
+
+    #[test]
+    fn paths() {
+        let scheme: Box<dyn NamingScheme> = Box::new(Prefix::new("#"));
+        assert_eq!(
+            scheme.hidden_path(Path::new("foo/bar.txt")).unwrap(),
+            Path::new("foo/#bar.txt")
+        );
+        assert_eq!(
+            scheme.visible_path(Path::new("foo/#bar.txt")).unwrap(),
+            Path::new("foo/bar.txt")
+        );
+        assert!(scheme.hidden_path(Path::new("foo/..")).is_none());
+        assert!(Dot.visible_path(Path::new("foo/bar.txt")).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let file_name = OsStr::from_bytes(b"\xFF.txt");
+        let hidden_name = Dot.hidden_name(file_name).unwrap();
+        assert_eq!(hidden_name.as_bytes(), b".\xFF.txt");
+        assert_eq!(Dot.visible_name(&hidden_name).unwrap(), file_name);
+    }
+}
@@ -0,0 +1,460 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Reporting why a file or directory is hidden.
+
+use std::{
+    ffi::OsStr,
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::platform::imp;
+
+/// A reason why a file or directory is hidden.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HiddenReason {
+    /// The file name starts with `.`.
+    ///
+    /// This is only reported on Unix.
+    DotPrefix,
+
+    /// The file has the hidden file attribute.
+    ///
+    /// This is only reported on Windows.
+    Attribute,
+
+    /// The file name is listed in the `.hidden` file at the given path.
+    ///
+    /// The `.hidden` file in a directory lists the names of the entries which
+    /// file managers such as GNOME Files and Dolphin should hide. This is only
+    /// reported on Unix.
+    HiddenList(PathBuf),
+
+    /// The ancestor directory at the given path is hidden.
+    ///
+    /// An ancestor is hidden if its file name starts with `.` on Unix, or it
+    /// has the hidden file attribute on Windows.
+    HiddenAncestor(PathBuf),
+
+    /// The file has the `UF_HIDDEN` file flag.
+    ///
+    /// This is only reported on macOS.
+    FileFlag,
+
+    /// The invisible flag is set in the `com.apple.FinderInfo` extended
+    /// attribute.
+    ///
+    /// This is only reported on macOS.
+    FinderInfo,
+}
+
+impl fmt::Display for HiddenReason {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DotPrefix => write!(f, "the file name starts with `.`"),
+            Self::Attribute => write!(f, "the hidden file attribute is set"),
+            Self::HiddenList(path) => {
+                write!(f, "the file name is listed in {}", path.display())
+            }
+            Self::HiddenAncestor(path) => {
+                write!(f, "the ancestor directory {} is hidden", path.display())
+            }
+            Self::FileFlag => write!(f, "the `UF_HIDDEN` file flag is set"),
+            Self::FinderInfo => write!(
+                f,
+                "the invisible flag is set in the `com.apple.FinderInfo` extended attribute"
+            ),
+        }
+    }
+}
+
+/// The reasons why a file or directory is hidden, returned by
+/// [`hf::hidden_status`](crate::hidden_status).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct HiddenStatus {
+    path: PathBuf,
+    reasons: Vec<HiddenReason>,
+}
+
+impl HiddenStatus {
+    /// Returns the path of the file.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the reasons why the file is hidden.
+    ///
+    /// The reasons about the file itself come first, followed by the hidden
+    /// ancestor directories from the nearest one.
+    #[must_use]
+    #[inline]
+    pub fn reasons(&self) -> &[HiddenReason] {
+        &self.reasons
+    }
+
+    /// Returns [`true`] if the file is hidden for any reason.
+    ///
+    /// Unlike [`hf::is_hidden`](crate::is_hidden), this also returns [`true`]
+    /// if the file is only hidden because of an ancestor directory or a
+    /// `.hidden` file.
+    #[must_use]
+    #[inline]
+    pub fn is_hidden(&self) -> bool {
+        !self.reasons.is_empty()
+    }
+}
+
+/// Returns every reason why the path is hidden.
+///
+/// The ancestor directories are taken from `path` as given, so relative paths
+/// are not resolved against the current directory. Symbolic links are not
+/// followed.
+///
+/// # Platform-specific behavior
+///
+/// - On Unix, the dot prefix, the `.hidden` file in the parent directory and
+///   the ancestor directories starting with `.` are checked. On macOS, the
+///   `UF_HIDDEN` file flag and the `com.apple.FinderInfo` extended attribute
+///   are also checked.
+/// - On Windows, the hidden file attribute of the file and the ancestor
+///   directories is checked.
+///
+/// # Errors
+///
+/// Returns [`Err`] if metadata about the file or an ancestor directory could
+/// not be obtained, or the `.hidden` file exists but could not be read.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::{self, File};
+/// #
+/// use hf::HiddenReason;
+///
+/// let temp_dir = tempfile::Builder::new().prefix("hf").tempdir().unwrap();
+/// let temp_dir = temp_dir.path();
+/// fs::create_dir(temp_dir.join(".foo")).unwrap();
+/// File::create(temp_dir.join(".foo/.bar.txt")).unwrap();
+///
+/// let status = hf::hidden_status(temp_dir.join(".foo/.bar.txt")).unwrap();
+/// assert!(status.is_hidden());
+/// assert_eq!(
+///     status.reasons(),
+///     [
+///         HiddenReason::DotPrefix,
+///         HiddenReason::HiddenAncestor(temp_dir.join(".foo"))
+///     ]
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn hidden_status(path: impl AsRef<Path>) -> io::Result<HiddenStatus> {
+    status(path.as_ref(), crate::migrate::is_listed)
+}
+
+/// A reader which reports why files are hidden like [`hidden_status`], but
+/// reads each `.hidden` file only once.
+///
+/// Only the `.hidden` file which was read last is kept, so this is meant for
+/// looking up the entries of a directory one after another. Changes to the
+/// `.hidden` file are not noticed until a file in another directory is looked
+/// up, or a new `StatusReader` is created.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::{self, File};
+/// #
+/// use hf::{HiddenReason, StatusReader};
+///
+/// let temp_dir = tempfile::Builder::new().prefix("hf").tempdir().unwrap();
+/// let temp_dir = temp_dir.path();
+/// File::create(temp_dir.join("foo.txt")).unwrap();
+/// File::create(temp_dir.join("bar.txt")).unwrap();
+/// fs::write(temp_dir.join(".hidden"), "foo.txt\n").unwrap();
+///
+/// let mut reader = StatusReader::new();
+/// assert_eq!(
+///     reader
+///         .hidden_status(temp_dir.join("foo.txt"))
+///         .unwrap()
+///         .reasons(),
+///     [HiddenReason::HiddenList(temp_dir.join(".hidden"))]
+/// );
+/// assert!(
+///     !reader
+///         .hidden_status(temp_dir.join("bar.txt"))
+///         .unwrap()
+///         .is_hidden()
+/// );
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct StatusReader {
+    list: Option<(PathBuf, Vec<u8>)>,
+}
+
+impl StatusReader {
+    /// Creates a new `StatusReader`.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every reason why the path is hidden.
+    ///
+    /// See [`hidden_status`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if metadata about the file or an ancestor directory
+    /// could not be obtained, or the `.hidden` file exists but could not be
+    /// read.
+    #[inline]
+    pub fn hidden_status(&mut self, path: impl AsRef<Path>) -> io::Result<HiddenStatus> {
+        let list = &mut self.list;
+        status(path.as_ref(), |list_path, file_name| {
+            if list.as_ref().is_none_or(|(p, _)| p != list_path) {
+                *list = None;
+                let contents = crate::migrate::read_list(list_path)?;
+                *list = Some((list_path.to_path_buf(), contents));
+            }
+            let contents = list.as_ref().map_or(&[][..], |(_, c)| c);
+            let file_name = file_name.as_encoded_bytes();
+            Ok(crate::migrate::lines(contents).any(|l| l == file_name))
+        })
+    }
+}
+
+/// Returns every reason why the path is hidden, calling `is_listed` with the
+/// path of the `.hidden` file and the file name to check whether the file is
+/// listed in it.
+fn status(
+    path: &Path,
+    is_listed: impl FnOnce(&Path, &OsStr) -> io::Result<bool>,
+) -> io::Result<HiddenStatus> {
+    let metadata = fs::symlink_metadata(path)?;
+    let mut reasons = Vec::new();
+    if let Some(file_name) = path.file_name() {
+        #[cfg(unix)]
+        {
+            if imp::is_hidden_name(file_name) {
+                reasons.push(HiddenReason::DotPrefix);
+            }
+            let list_path = match path.parent() {
+                Some(p) if !p.as_os_str().is_empty() => p.join(".hidden"),
+                _ => PathBuf::from(".hidden"),
+            };
+            if is_listed(&list_path, file_name)? {
+                reasons.push(HiddenReason::HiddenList(list_path));
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = is_listed;
+        #[cfg(target_os = "macos")]
+        {
+            use std::os::macos::fs::MetadataExt;
+
+            // `UF_HIDDEN` in `<sys/stat.h>`.
+            if metadata.st_flags() & 0x8000 != 0 {
+                reasons.push(HiddenReason::FileFlag);
+            }
+            if has_invisible_finder_flag(path) {
+                reasons.push(HiddenReason::FinderInfo);
+            }
+        }
+    }
+    #[cfg(windows)]
+    if imp::has_hidden_attribute(&metadata) {
+        reasons.push(HiddenReason::Attribute);
+    }
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let _ = metadata;
+    for ancestor in path.ancestors().skip(1) {
+        if matches!(
+            ancestor.components().next_back(),
+            Some(Component::Normal(_))
+        ) && imp::is_hidden(ancestor)?
+        {
+            reasons.push(HiddenReason::HiddenAncestor(ancestor.to_path_buf()));
+        }
+    }
+    Ok(HiddenStatus {
+        path: path.to_path_buf(),
+        reasons,
+    })
+}
+
+/// Returns [`true`] if the invisible flag is set in the `com.apple.FinderInfo`
+/// extended attribute of `path`.
+#[cfg(target_os = "macos")]
+fn has_invisible_finder_flag(path: &Path) -> bool {
+    let mut finder_info = [0; 32];
+    // The Finder flags are stored as a big-endian `u16` at offset 8, and
+    // `kIsInvisible` is `0x4000`.
+    matches!(
+        rustix::fs::lgetxattr(path, "com.apple.FinderInfo", &mut finder_info),
+        Ok(len) if len > 8 && finder_info[8] & 0x40 != 0
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    // The default prefix of `tempfile` starts with `.`, which would make the
+    // temporary directory a hidden ancestor.
+    fn tempdir() -> tempfile::TempDir {
+        tempfile::Builder::new().prefix("hf").tempdir().unwrap()
+    }
+
+    #[test]
+    fn display_reason() {
+        assert_eq!(
+            HiddenReason::HiddenAncestor(PathBuf::from("foo")).to_string(),
+            "the ancestor directory foo is hidden"
+        );
+        assert_eq!(
+            HiddenReason::HiddenList(PathBuf::from(".hidden")).to_string(),
+            "the file name is listed in .hidden"
+        );
+    }
+
+    #[test]
+    fn visible_file() {
+        let temp_dir = tempdir();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+
+        let status = hidden_status(&file_path).unwrap();
+        assert_eq!(status.path(), file_path);
+        assert!(status.reasons().is_empty());
+        assert!(!status.is_hidden());
+    }
+
+    #[test]
+    fn non_existent_file() {
+        let temp_dir = tempdir();
+        assert_eq!(
+            hidden_status(temp_dir.path().join("foo.txt"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hidden_ancestors() {
+        let temp_dir = tempdir();
+        let temp_dir = temp_dir.path();
+        fs::create_dir_all(temp_dir.join(".foo/bar/.baz")).unwrap();
+        File::create(temp_dir.join(".foo/bar/.baz/qux.txt")).unwrap();
+
+        let status = hidden_status(temp_dir.join(".foo/bar/.baz/qux.txt")).unwrap();
+        assert_eq!(
+            status.reasons(),
+            [
+                HiddenReason::HiddenAncestor(temp_dir.join(".foo/bar/.baz")),
+                HiddenReason::HiddenAncestor(temp_dir.join(".foo"))
+            ]
+        );
+        assert!(
+            hidden_status(temp_dir.join(".foo/bar/.."))
+                .unwrap()
+                .reasons()
+                .contains(&HiddenReason::HiddenAncestor(temp_dir.join(".foo")))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hidden_list() {
+        let temp_dir = tempdir();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        File::create(temp_dir.join("bar.txt")).unwrap();
+        fs::write(temp_dir.join(".hidden"), "baz.txt\r\nfoo.txt\n").unwrap();
+
+        assert_eq!(
+            hidden_status(temp_dir.join("foo.txt")).unwrap().reasons(),
+            [HiddenReason::HiddenList(temp_dir.join(".hidden"))]
+        );
+        assert!(!hidden_status(temp_dir.join("bar.txt")).unwrap().is_hidden());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn status_reader() {
+        let temp_dir = tempdir();
+        let temp_dir = temp_dir.path();
+        fs::create_dir(temp_dir.join("foo")).unwrap();
+        File::create(temp_dir.join("foo/bar.txt")).unwrap();
+        File::create(temp_dir.join("foo/baz.txt")).unwrap();
+        fs::write(temp_dir.join("foo/.hidden"), "bar.txt\n").unwrap();
+
+        let mut reader = StatusReader::new();
+        assert_eq!(
+            reader
+                .hidden_status(temp_dir.join("foo/bar.txt"))
+                .unwrap()
+                .reasons(),
+            [HiddenReason::HiddenList(temp_dir.join("foo/.hidden"))]
+        );
+
+        // The `.hidden` file is read again only for another directory.
+        fs::write(temp_dir.join("foo/.hidden"), "baz.txt\n").unwrap();
+        assert!(
+            !reader
+                .hidden_status(temp_dir.join("foo/baz.txt"))
+                .unwrap()
+                .is_hidden()
+        );
+        assert!(
+            !reader
+                .hidden_status(temp_dir.join("foo"))
+                .unwrap()
+                .is_hidden()
+        );
+        assert!(
+            reader
+                .hidden_status(temp_dir.join("foo/baz.txt"))
+                .unwrap()
+                .is_hidden()
+        );
+        assert_eq!(
+            reader
+                .hidden_status(temp_dir.join("qux.txt"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn attribute() {
+        let temp_dir = tempdir();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+        crate::hide(&file_path).unwrap();
+
+        assert_eq!(
+            hidden_status(&file_path).unwrap().reasons(),
+            [HiddenReason::Attribute]
+        );
+    }
+}
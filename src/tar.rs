@@ -0,0 +1,652 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Provides functionality for tar archives.
+//!
+//! In a tar archive, hidden members are members whose file name starts with a
+//! dot character (`.`), regardless of the platform. Hiding or showing a member
+//! renames it in the same way as [`hf::unix::hidden_file_name`] and
+//! [`hf::unix::normal_file_name`]. If the member is a directory, the paths of
+//! its children are renamed as well.
+//!
+//! The archive may be uncompressed or compressed with gzip or Zstandard. The
+//! compression format is detected from the input, and the output is written
+//! with the same compression format.
+//!
+//! [`hf::unix::hidden_file_name`]: https://docs.rs/hf/latest/hf/unix/fn.hidden_file_name.html
+//! [`hf::unix::normal_file_name`]: https://docs.rs/hf/latest/hf/unix/fn.normal_file_name.html
+
+use std::{
+    borrow::Cow,
+    io::{self, BufRead, BufReader, Error, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{bufread::MultiGzDecoder, write::GzEncoder};
+use tar::{Archive, Builder, EntryType, Header};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+const PAX_PATH: &str = "path";
+
+const PAX_LINKPATH: &str = "linkpath";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Mode {
+    Hide,
+    Show,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn detect(buf: &[u8]) -> Self {
+        if buf.starts_with(&GZIP_MAGIC) {
+            Self::Gzip
+        } else if buf.starts_with(&ZSTD_MAGIC) {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+}
+
+enum Encoder<W: Write> {
+    None(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    fn new(writer: W, compression: Compression) -> io::Result<Self> {
+        let encoder = match compression {
+            Compression::None => Self::None(writer),
+            Compression::Gzip => Self::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+            Compression::Zstd => Self::Zstd(zstd::Encoder::new(writer, 0)?),
+        };
+        Ok(encoder)
+    }
+
+    fn finish(self) -> io::Result<W> {
+        match self {
+            Self::None(mut w) => w.flush().map(|()| w),
+            Self::Gzip(e) => e.finish(),
+            Self::Zstd(e) => e.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::None(w) => w.write(buf),
+            Self::Gzip(e) => e.write(buf),
+            Self::Zstd(e) => e.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::None(w) => w.flush(),
+            Self::Gzip(e) => e.flush(),
+            Self::Zstd(e) => e.flush(),
+        }
+    }
+}
+
+/// Hides members of a tar archive.
+///
+/// This function reads a tar archive from `reader`, renames `members` and the
+/// children of `members` to start with `.`, and writes the archive to
+/// `writer`. Members that are not affected are copied as is.
+///
+/// Returns the renamed members as pairs of the path before and after renaming.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - The file name of any of `members` starts with `.`.
+/// - Any of `members` terminates in `..`.
+/// - The archive could not be read or written.
+///
+/// # Examples
+///
+/// ```
+/// # use std::path::Path;
+/// #
+/// let mut builder = tar::Builder::new(Vec::new());
+/// let mut header = tar::Header::new_ustar();
+/// header.set_size(3);
+/// builder
+///     .append_data(&mut header, "foo/bar.txt", b"bar".as_slice())
+///     .unwrap();
+/// let archive = builder.into_inner().unwrap();
+///
+/// let mut output = Vec::new();
+/// let renamed = hf::tar::hide(archive.as_slice(), &mut output, &["foo"]).unwrap();
+/// assert_eq!(
+///     renamed,
+///     [(
+///         Path::new("foo/bar.txt").into(),
+///         Path::new(".foo/bar.txt").into()
+///     )]
+/// );
+///
+/// let mut archive = tar::Archive::new(output.as_slice());
+/// let entry = archive.entries().unwrap().next().unwrap().unwrap();
+/// assert_eq!(entry.path().unwrap(), Path::new(".foo/bar.txt"));
+/// ```
+pub fn hide(
+    reader: impl Read,
+    writer: impl Write,
+    members: &[impl AsRef<Path>],
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    rewrite(reader, writer, members, Mode::Hide)
+}
+
+/// Shows hidden members of a tar archive.
+///
+/// This function reads a tar archive from `reader`, renames `members` and the
+/// children of `members` to start with a character other than `.`, and writes
+/// the archive to `writer`. Members that are not affected are copied as is.
+///
+/// Returns the renamed members as pairs of the path before and after renaming.
+///
+/// # Errors
+///
+/// Returns [`Err`] if any of the following are true:
+///
+/// - The file name of any of `members` does not start with `.`.
+/// - Any of `members` terminates in `..`.
+/// - The archive could not be read or written.
+///
+/// # Examples
+///
+/// ```
+/// # use std::path::Path;
+/// #
+/// let mut builder = tar::Builder::new(Vec::new());
+/// let mut header = tar::Header::new_ustar();
+/// header.set_size(3);
+/// builder
+///     .append_data(&mut header, ".foo.txt", b"foo".as_slice())
+///     .unwrap();
+/// let archive = builder.into_inner().unwrap();
+///
+/// let mut output = Vec::new();
+/// hf::tar::show(archive.as_slice(), &mut output, &[".foo.txt"]).unwrap();
+///
+/// let mut archive = tar::Archive::new(output.as_slice());
+/// let entry = archive.entries().unwrap().next().unwrap().unwrap();
+/// assert_eq!(entry.path().unwrap(), Path::new("foo.txt"));
+/// ```
+pub fn show(
+    reader: impl Read,
+    writer: impl Write,
+    members: &[impl AsRef<Path>],
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    rewrite(reader, writer, members, Mode::Show)
+}
+
+fn rewrite(
+    reader: impl Read,
+    writer: impl Write,
+    members: &[impl AsRef<Path>],
+    mode: Mode,
+) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let targets = members
+        .iter()
+        .map(|m| {
+            let target = split(&path_to_bytes(m.as_ref()))
+                .into_iter()
+                .map(<[u8]>::to_vec)
+                .collect::<Vec<_>>();
+            match target.last() {
+                Some(name) if rename_component(name, mode).is_some() => Ok(target),
+                _ => Err(Error::from(ErrorKind::InvalidInput)),
+            }
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut reader = BufReader::new(reader);
+    let compression = Compression::detect(reader.fill_buf()?);
+    let reader: Box<dyn Read> = match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(reader)?),
+    };
+    let mut archive = Archive::new(reader);
+    let mut builder = Builder::new(Encoder::new(writer, compression)?);
+
+    let mut renamed = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let mut header = entry.header().clone();
+        if header.entry_type().is_pax_global_extensions() {
+            builder.append(&header, &mut entry)?;
+            continue;
+        }
+
+        let path = entry.path_bytes().into_owned();
+        let new_path = rewrite_path(&path, &targets, mode);
+        let link_name = entry.link_name_bytes().map(Cow::into_owned);
+        let new_link_name = link_name
+            .as_deref()
+            .filter(|_| header.entry_type() == EntryType::Link)
+            .and_then(|l| rewrite_path(l, &targets, mode));
+        let path = new_path.as_deref().map_or(path.as_slice(), |p| p);
+        let link_name = new_link_name.as_deref().or(link_name.as_deref());
+
+        let pax_extensions = entry
+            .pax_extensions()?
+            .map(|extensions| {
+                extensions
+                    .map(|e| {
+                        let e = e?;
+                        let key = e.key().map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+                        Ok((key.to_owned(), e.value_bytes().to_vec()))
+                    })
+                    .collect::<io::Result<Vec<_>>>()
+            })
+            .transpose()?;
+        if let Some(mut extensions) = pax_extensions {
+            for (key, value) in &mut extensions {
+                match key.as_str() {
+                    PAX_PATH => path.clone_into(value),
+                    PAX_LINKPATH => {
+                        if let Some(link_name) = link_name {
+                            link_name.clone_into(value);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            builder.append_pax_extensions(
+                extensions.iter().map(|(k, v)| (k.as_str(), v.as_slice())),
+            )?;
+            if extensions.iter().any(|(k, _)| k == PAX_PATH) {
+                set_truncated_path(&mut header, path);
+                if let Some(link_name) = link_name {
+                    if extensions.iter().any(|(k, _)| k == PAX_LINKPATH) {
+                        set_truncated_link_name(&mut header, link_name);
+                    } else {
+                        header.set_link_name(bytes_to_path(link_name))?;
+                    }
+                }
+                header.set_cksum();
+                builder.append(&header, &mut entry)?;
+            } else {
+                append(&mut builder, &mut header, path, link_name, &mut entry)?;
+            }
+        } else {
+            append(&mut builder, &mut header, path, link_name, &mut entry)?;
+        }
+
+        if let Some(new_path) = new_path {
+            renamed.push((
+                bytes_to_path(&entry.path_bytes()).into_owned(),
+                bytes_to_path(&new_path).into_owned(),
+            ));
+        }
+    }
+    builder.into_inner()?.finish()?;
+    Ok(renamed)
+}
+
+fn append<W: Write>(
+    builder: &mut Builder<W>,
+    header: &mut Header,
+    path: &[u8],
+    link_name: Option<&[u8]>,
+    data: impl Read,
+) -> io::Result<()> {
+    match link_name {
+        Some(link_name)
+            if header.entry_type().is_hard_link() || header.entry_type().is_symlink() =>
+        {
+            builder.append_link(header, bytes_to_path(path), bytes_to_path(link_name))
+        }
+        _ => builder.append_data(header, bytes_to_path(path), data),
+    }
+}
+
+fn set_truncated_path(header: &mut Header, path: &[u8]) {
+    if header.set_path(bytes_to_path(path)).is_err() {
+        let name = &mut header.as_old_mut().name;
+        let len = path.len().min(name.len());
+        name.fill(0);
+        name[..len].copy_from_slice(&path[..len]);
+        if let Some(ustar) = header.as_ustar_mut() {
+            ustar.prefix.fill(0);
+        }
+    }
+}
+
+fn set_truncated_link_name(header: &mut Header, link_name: &[u8]) {
+    if header.set_link_name(bytes_to_path(link_name)).is_err() {
+        let name = &mut header.as_old_mut().linkname;
+        let len = link_name.len().min(name.len());
+        name.fill(0);
+        name[..len].copy_from_slice(&link_name[..len]);
+    }
+}
+
+fn rewrite_path(path: &[u8], targets: &[Vec<Vec<u8>>], mode: Mode) -> Option<Vec<u8>> {
+    let is_dir = path.ends_with(b"/");
+    let mut components = split(path)
+        .into_iter()
+        .map(Cow::Borrowed)
+        .collect::<Vec<_>>();
+    let mut is_renamed = false;
+    for target in targets {
+        let depth = target.len();
+        if components.len() >= depth && target.iter().zip(split(path)).all(|(t, c)| *t == c) {
+            if let Some(name) = rename_component(&components[depth - 1], mode) {
+                components[depth - 1] = Cow::Owned(name);
+                is_renamed = true;
+            }
+        }
+    }
+    is_renamed.then(|| {
+        let mut path = components.join(&b'/');
+        if is_dir {
+            path.push(b'/');
+        }
+        path
+    })
+}
+
+fn rename_component(name: &[u8], mode: Mode) -> Option<Vec<u8>> {
+    match mode {
+        Mode::Hide if !name.starts_with(b".") => Some([b".", name].concat()),
+        Mode::Show if name.starts_with(b".") => {
+            let pos = name.iter().position(|&b| b != b'.')?;
+            Some(name[pos..].to_vec())
+        }
+        _ => None,
+    }
+}
+
+fn split(path: &[u8]) -> Vec<&[u8]> {
+    path.split(|&b| b == b'/')
+        .filter(|c| !c.is_empty() && *c != b".")
+        .collect()
+}
+
+fn path_to_bytes(path: &Path) -> Cow<'_, [u8]> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        Cow::Borrowed(path.as_os_str().as_bytes())
+    }
+    #[cfg(not(unix))]
+    {
+        match path.to_string_lossy() {
+            Cow::Borrowed(p) => Cow::Owned(p.replace('\\', "/").into_bytes()),
+            Cow::Owned(p) => Cow::Owned(p.replace('\\', "/").into_bytes()),
+        }
+    }
+}
+
+fn bytes_to_path(bytes: &[u8]) -> Cow<'_, Path> {
+    #[cfg(unix)]
+    {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        Cow::Borrowed(Path::new(OsStr::from_bytes(bytes)))
+    }
+    #[cfg(not(unix))]
+    {
+        match String::from_utf8_lossy(bytes) {
+            Cow::Borrowed(p) => Cow::Borrowed(Path::new(p)),
+            Cow::Owned(p) => Cow::Owned(PathBuf::from(p)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = Builder::new(Vec::new());
+        for (path, data) in entries {
+            let mut header = Header::new_gnu();
+            if path.ends_with('/') {
+                header.set_entry_type(EntryType::Directory);
+            }
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, path, *data).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn paths(archive: &[u8]) -> Vec<PathBuf> {
+        Archive::new(archive)
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn hide() {
+        let archive = build(&[
+            ("foo/", b""),
+            ("foo/bar.txt", b"bar"),
+            ("foo.txt", b"foo"),
+            ("baz.txt", b"baz"),
+        ]);
+        let mut output = Vec::new();
+        let renamed = super::hide(archive.as_slice(), &mut output, &["foo", "foo.txt"]).unwrap();
+        assert_eq!(
+            renamed,
+            [
+                (PathBuf::from("foo/"), PathBuf::from(".foo/")),
+                (PathBuf::from("foo/bar.txt"), PathBuf::from(".foo/bar.txt")),
+                (PathBuf::from("foo.txt"), PathBuf::from(".foo.txt")),
+            ]
+        );
+        assert_eq!(
+            paths(&output),
+            [
+                Path::new(".foo"),
+                Path::new(".foo/bar.txt"),
+                Path::new(".foo.txt"),
+                Path::new("baz.txt")
+            ]
+        );
+    }
+
+    #[test]
+    fn hide_nested_members() {
+        let archive = build(&[("foo/bar/baz.txt", b"baz")]);
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &["./foo", "foo/bar/"]).unwrap();
+        assert_eq!(paths(&output), [Path::new(".foo/.bar/baz.txt")]);
+    }
+
+    #[test]
+    fn hide_preserves_data() {
+        let archive = build(&[("foo.txt", b"Hello, world!\n")]);
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &["foo.txt"]).unwrap();
+        let mut archive = Archive::new(output.as_slice());
+        let mut entry = archive.entries().unwrap().next().unwrap().unwrap();
+        let mut data = String::new();
+        entry.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "Hello, world!\n");
+    }
+
+    #[test]
+    fn hide_with_gnu_long_name() {
+        let long_name = format!("{}/foo.txt", "a".repeat(120));
+        let archive = build(&[(&long_name, b"foo")]);
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &[&long_name]).unwrap();
+        assert_eq!(
+            paths(&output),
+            [PathBuf::from(format!("{}/.foo.txt", "a".repeat(120)))]
+        );
+    }
+
+    #[test]
+    fn hide_with_pax_long_name() {
+        let long_name = format!("{}/foo.txt", "a".repeat(120));
+        let mut builder = Builder::new(Vec::new());
+        builder
+            .append_pax_extensions([
+                (PAX_PATH, long_name.as_bytes()),
+                ("mtime", b"1234567890.5".as_slice()),
+            ])
+            .unwrap();
+        let mut header = Header::new_ustar();
+        header.as_old_mut().name[..7].copy_from_slice(b"foo.txt");
+        header.set_size(3);
+        header.set_cksum();
+        builder.append(&header, b"foo".as_slice()).unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &[&long_name]).unwrap();
+        let mut archive = Archive::new(output.as_slice());
+        let mut entry = archive.entries().unwrap().next().unwrap().unwrap();
+        assert_eq!(
+            entry.path().unwrap(),
+            PathBuf::from(format!("{}/.foo.txt", "a".repeat(120)))
+        );
+        let extensions = entry
+            .pax_extensions()
+            .unwrap()
+            .unwrap()
+            .map(|e| e.unwrap().key().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(extensions, [PAX_PATH, "mtime"]);
+    }
+
+    #[test]
+    fn hide_with_hard_link() {
+        let mut builder = Builder::new(Vec::new());
+        let mut header = Header::new_gnu();
+        header.set_size(3);
+        builder
+            .append_data(&mut header, "foo.txt", b"foo".as_slice())
+            .unwrap();
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Link);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "bar.txt", "foo.txt")
+            .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &["foo.txt"]).unwrap();
+        let mut archive = Archive::new(output.as_slice());
+        let entry = archive.entries().unwrap().nth(1).unwrap().unwrap();
+        assert_eq!(entry.path().unwrap(), Path::new("bar.txt"));
+        assert_eq!(entry.link_name().unwrap().unwrap(), Path::new(".foo.txt"));
+    }
+
+    #[test]
+    fn hide_with_gzip() {
+        let archive = build(&[("foo.txt", b"foo")]);
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&archive).unwrap();
+        let archive = encoder.finish().unwrap();
+
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &["foo.txt"]).unwrap();
+        assert!(output.starts_with(&GZIP_MAGIC));
+        let output = MultiGzDecoder::new(output.as_slice());
+        let mut archive = Archive::new(output);
+        let entry = archive.entries().unwrap().next().unwrap().unwrap();
+        assert_eq!(entry.path().unwrap(), Path::new(".foo.txt"));
+    }
+
+    #[test]
+    fn hide_with_zstd() {
+        let archive = build(&[("foo.txt", b"foo")]);
+        let archive = zstd::encode_all(archive.as_slice(), 0).unwrap();
+
+        let mut output = Vec::new();
+        super::hide(archive.as_slice(), &mut output, &["foo.txt"]).unwrap();
+        assert!(output.starts_with(&ZSTD_MAGIC));
+        let output = zstd::decode_all(output.as_slice()).unwrap();
+        assert_eq!(paths(&output), [Path::new(".foo.txt")]);
+    }
+
+    #[test]
+    fn hide_when_hidden_member() {
+        let archive = build(&[(".foo.txt", b"foo")]);
+        assert_eq!(
+            super::hide(archive.as_slice(), io::sink(), &[".foo.txt"])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn hide_with_invalid_member() {
+        let archive = build(&[("foo.txt", b"foo")]);
+        assert_eq!(
+            super::hide(archive.as_slice(), io::sink(), &["foo.txt/.."])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn show() {
+        let archive = build(&[
+            (".foo/", b""),
+            (".foo/.bar.txt", b"bar"),
+            ("..baz.txt", b"baz"),
+        ]);
+        let mut output = Vec::new();
+        let renamed = super::show(archive.as_slice(), &mut output, &[".foo", "..baz.txt"]).unwrap();
+        assert_eq!(renamed.len(), 3);
+        assert_eq!(
+            paths(&output),
+            [
+                Path::new("foo"),
+                Path::new("foo/.bar.txt"),
+                Path::new("baz.txt")
+            ]
+        );
+    }
+
+    #[test]
+    fn show_when_non_hidden_member() {
+        let archive = build(&[("foo.txt", b"foo")]);
+        assert_eq!(
+            super::show(archive.as_slice(), io::sink(), &["foo.txt"])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn detect_compression() {
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]),
+            Compression::Zstd
+        );
+        assert_eq!(Compression::detect(b"foo.txt"), Compression::None);
+        assert_eq!(Compression::detect(&[]), Compression::None);
+    }
+}
@@ -0,0 +1,426 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Provides asynchronous versions of the operations for [Tokio].
+//!
+//! The functions in this module run the corresponding blocking functions on
+//! the blocking thread pool of Tokio by using [`spawn_blocking`], so they must
+//! be called from within a Tokio runtime. They return the same errors as the
+//! corresponding blocking functions.
+//!
+//! [Tokio]: https://tokio.rs/
+//! [`spawn_blocking`]: tokio::task::spawn_blocking
+
+use std::{
+    fs::DirEntry,
+    io::{self, Error},
+    path::Path,
+};
+
+use tokio::{sync::mpsc, task};
+
+use crate::{
+    Plan, Walk, WalkEntry,
+    batch::{Batch, Progress},
+};
+
+const WALK_CHANNEL_CAPACITY: usize = 64;
+
+async fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> io::Result<T> {
+    match task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(err) => Err(Error::other(err)),
+    }
+}
+
+/// Returns [`true`] if the path is a hidden file or directory.
+///
+/// This is an asynchronous version of [`hf::is_hidden`](crate::is_hidden).
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`hf::is_hidden`](crate::is_hidden) returns an error or
+/// the blocking task fails.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # #[tokio::main]
+/// # async fn main() {
+/// assert_eq!(hf::tokio::is_hidden(".foo.txt").await.unwrap(), true);
+/// assert_eq!(hf::tokio::is_hidden("foo.txt").await.unwrap(), false);
+/// # }
+/// #
+/// # #[cfg(not(unix))]
+/// # fn main() {}
+/// ```
+#[inline]
+pub async fn is_hidden(path: impl AsRef<Path>) -> io::Result<bool> {
+    let path = path.as_ref().to_path_buf();
+    spawn_blocking(move || crate::is_hidden(path)).await
+}
+
+/// Hides a file or directory.
+///
+/// This is an asynchronous version of [`hf::hide`](crate::hide).
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`hf::hide`](crate::hide) returns an error or the
+/// blocking task fails.
+///
+/// # Examples
+///
+/// ```
+/// # use std::fs::File;
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// let file_path = temp_dir.path().join("foo.txt");
+/// File::create(&file_path).unwrap();
+///
+/// hf::tokio::hide(&file_path).await.unwrap();
+/// # }
+/// ```
+#[inline]
+pub async fn hide(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_path_buf();
+    spawn_blocking(move || crate::hide(path)).await
+}
+
+/// Shows a hidden file or directory.
+///
+/// This is an asynchronous version of [`hf::show`](crate::show).
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`hf::show`](crate::show) returns an error or the
+/// blocking task fails.
+#[inline]
+pub async fn show(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_path_buf();
+    spawn_blocking(move || crate::show(path)).await
+}
+
+/// Returns the entries within a directory that are not hidden.
+///
+/// This is an asynchronous version of
+/// [`hf::read_dir_visible`](crate::read_dir_visible) which collects the
+/// entries.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the directory or any of the entries could not be read,
+/// or the blocking task fails.
+#[inline]
+pub async fn read_dir_visible(path: impl AsRef<Path>) -> io::Result<Vec<DirEntry>> {
+    let path = path.as_ref().to_path_buf();
+    spawn_blocking(move || crate::read_dir_visible(path)?.collect()).await
+}
+
+/// Returns the entries within a directory that are hidden.
+///
+/// This is an asynchronous version of
+/// [`hf::read_dir_hidden`](crate::read_dir_hidden) which collects the entries.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the directory or any of the entries could not be read,
+/// or the blocking task fails.
+#[inline]
+pub async fn read_dir_hidden(path: impl AsRef<Path>) -> io::Result<Vec<DirEntry>> {
+    let path = path.as_ref().to_path_buf();
+    spawn_blocking(move || crate::read_dir_hidden(path)?.collect()).await
+}
+
+/// Calls `f` with each item of `items` concurrently, and returns the results
+/// in the order of `items`.
+///
+/// This is an asynchronous version of [`Batch::map`]. The whole batch runs as
+/// a single blocking task, which spawns its own threads.
+///
+/// # Errors
+///
+/// Returns [`Err`] if the blocking task fails, such as `f` panics.
+///
+/// # Examples
+///
+/// ```
+/// # use std::num::NonZeroUsize;
+/// #
+/// # use hf::batch::Batch;
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// let batch = Batch::new(NonZeroUsize::new(2).unwrap());
+/// let results = hf::tokio::batch_map(batch, vec![1, 2, 3], |n| n * 2)
+///     .await
+///     .unwrap();
+/// assert_eq!(results, [2, 4, 6]);
+/// # }
+/// ```
+#[inline]
+pub async fn batch_map<T, R, F>(batch: Batch, items: Vec<T>, f: F) -> io::Result<Vec<R>>
+where
+    T: Send + Sync + 'static,
+    R: Send + 'static,
+    F: Fn(&T) -> R + Send + Sync + 'static,
+{
+    spawn_blocking(move || Ok(batch.map(&items, f))).await
+}
+
+/// Executes `plan` concurrently, and returns it after it has been executed.
+///
+/// This is an asynchronous version of [`Batch::execute`]. The plan is
+/// returned so that it can be reverted later with [`Plan::revert`].
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`Batch::execute`] returns an error or the blocking task
+/// fails. In the former case, the steps which have already been executed are
+/// reverted as far as possible.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::{fs::File, num::NonZeroUsize};
+/// #
+/// # use hf::{Companions, Plan, batch::Batch};
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// let file_path = temp_dir.path().join("foo.txt");
+/// File::create(&file_path).unwrap();
+/// let mut plan = Plan::new();
+/// plan.hide(&file_path, &Companions::default()).unwrap();
+///
+/// let batch = Batch::new(NonZeroUsize::new(2).unwrap());
+/// let plan = hf::tokio::batch_execute(batch, plan).await.unwrap();
+/// assert!(temp_dir.path().join(".foo.txt").exists());
+///
+/// plan.revert().unwrap();
+/// assert!(file_path.exists());
+/// # }
+/// # }
+/// ```
+#[inline]
+pub async fn batch_execute(batch: Batch, plan: Plan) -> io::Result<Plan> {
+    batch_execute_with_progress(batch, plan, |_| {}).await
+}
+
+/// Executes `plan` concurrently like [`batch_execute`], and calls `progress`
+/// whenever a step has been executed.
+///
+/// This is an asynchronous version of [`Batch::execute_with_progress`].
+/// `progress` is called on the blocking thread pool.
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`Batch::execute_with_progress`] returns an error or the
+/// blocking task fails.
+#[inline]
+pub async fn batch_execute_with_progress<P>(
+    batch: Batch,
+    plan: Plan,
+    progress: P,
+) -> io::Result<Plan>
+where
+    P: FnMut(Progress) + Send + 'static,
+{
+    spawn_blocking(move || batch.execute_with_progress(&plan, progress).map(|()| plan)).await
+}
+
+/// Walks a directory on the blocking thread pool.
+///
+/// The entries yielded by `walk` are sent to the returned receiver as they are
+/// found. The walk stops when the receiver is dropped.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::File;
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// File::create(temp_dir.path().join("foo.txt")).unwrap();
+/// File::create(temp_dir.path().join(".bar.txt")).unwrap();
+///
+/// let walk = hf::Walk::new(temp_dir.path())
+///     .min_depth(1)
+///     .visibility(hf::Visibility::Visible);
+/// let mut entries = hf::tokio::walk(walk);
+/// let entry = entries.recv().await.unwrap().unwrap();
+/// assert_eq!(entry.file_name(), "foo.txt");
+/// assert!(entries.recv().await.is_none());
+/// # }
+/// # }
+/// ```
+#[must_use]
+#[inline]
+pub fn walk(walk: Walk) -> mpsc::Receiver<io::Result<WalkEntry>> {
+    let (tx, rx) = mpsc::channel(WALK_CHANNEL_CAPACITY);
+    task::spawn_blocking(move || {
+        for entry in walk {
+            if tx.blocking_send(entry).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        io::ErrorKind,
+        num::NonZeroUsize,
+    };
+
+    use super::*;
+    use crate::Companions;
+
+    #[tokio::test]
+    async fn hide_and_show() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("foo.txt");
+        File::create(&file_path).unwrap();
+        assert!(!super::is_hidden(&file_path).await.unwrap());
+
+        super::hide(&file_path).await.unwrap();
+        #[cfg(unix)]
+        let file_path = temp_dir.path().join(".foo.txt");
+        assert!(super::is_hidden(&file_path).await.unwrap());
+
+        super::show(&file_path).await.unwrap();
+        #[cfg(unix)]
+        let file_path = temp_dir.path().join("foo.txt");
+        assert!(!super::is_hidden(&file_path).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn hide_when_file_does_not_exist() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            super::hide(temp_dir.path().join("foo.txt"))
+                .await
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn read_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        File::create(temp_dir.path().join("foo.txt")).unwrap();
+        let hidden_file_path =
+            temp_dir
+                .path()
+                .join(if cfg!(unix) { ".bar.txt" } else { "bar.txt" });
+        File::create(&hidden_file_path).unwrap();
+        #[cfg(windows)]
+        crate::hide(&hidden_file_path).unwrap();
+
+        let entries = super::read_dir_visible(temp_dir.path()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].file_name(), "foo.txt");
+
+        let entries = super::read_dir_hidden(temp_dir.path()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path(), hidden_file_path);
+
+        assert_eq!(
+            super::read_dir_visible(temp_dir.path().join("baz"))
+                .await
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_map() {
+        let batch = Batch::new(NonZeroUsize::new(4).unwrap());
+        let results = super::batch_map(batch, (0..10).collect(), |n| n * n)
+            .await
+            .unwrap();
+        assert_eq!(results, (0..10).map(|n| n * n).collect::<Vec<_>>());
+
+        let err = super::batch_map(batch, vec![0], |_| -> () { panic!() })
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Other);
+    }
+
+    #[tokio::test]
+    async fn batch_execute() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let companions = Companions::default();
+        let mut plan = Plan::new();
+        for name in ["foo.txt", "bar.txt", "baz.txt"] {
+            File::create(temp_dir.path().join(name)).unwrap();
+            plan.hide(temp_dir.path().join(name), &companions).unwrap();
+        }
+        let dests = plan
+            .steps()
+            .iter()
+            .map(|s| s.dest().to_path_buf())
+            .collect::<Vec<_>>();
+
+        let batch = Batch::new(NonZeroUsize::new(2).unwrap());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let plan = super::batch_execute_with_progress(batch, plan, move |p| {
+            let _ = tx.send(p.processed());
+        })
+        .await
+        .unwrap();
+        assert!(dests.iter().all(|p| crate::is_hidden(p).unwrap()));
+        let mut processed = Vec::new();
+        while let Some(n) = rx.recv().await {
+            processed.push(n);
+        }
+        assert_eq!(processed, [0, 1, 2, 3]);
+
+        plan.revert().unwrap();
+        fs::remove_file(temp_dir.path().join("bar.txt")).unwrap();
+        assert_eq!(
+            super::batch_execute(batch, plan).await.unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert!(!crate::is_hidden(temp_dir.path().join("foo.txt")).unwrap());
+        assert!(temp_dir.path().join("baz.txt").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn walk() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::create_dir(temp_dir.path().join("foo")).unwrap();
+        File::create(temp_dir.path().join("foo/bar.txt")).unwrap();
+
+        let mut rx = super::walk(Walk::new(temp_dir.path()).sort_by_file_name(true));
+        let mut entries = Vec::new();
+        while let Some(entry) = rx.recv().await {
+            entries.push(entry.unwrap().into_path());
+        }
+        assert_eq!(
+            entries,
+            [
+                temp_dir.path().to_path_buf(),
+                temp_dir.path().join("foo"),
+                temp_dir.path().join("foo/bar.txt")
+            ]
+        );
+    }
+}
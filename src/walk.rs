@@ -0,0 +1,697 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Listing directories by visibility.
+
+use std::{
+    ffi::OsStr,
+    fs::{self, DirEntry, FileType},
+    io::{self, Error},
+    path::{Path, PathBuf},
+    vec,
+};
+
+use crate::ext::DirEntryExt;
+
+/// Which entries to yield when listing a directory.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Visibility {
+    /// Yield all entries.
+    #[default]
+    All,
+
+    /// Yield only entries that are not hidden.
+    Visible,
+
+    /// Yield only entries that are hidden.
+    Hidden,
+}
+
+impl Visibility {
+    fn matches(self, entry: &DirEntry) -> io::Result<bool> {
+        let matches = match self {
+            Self::All => true,
+            Self::Visible => !entry.is_hidden()?,
+            Self::Hidden => entry.is_hidden()?,
+        };
+        Ok(matches)
+    }
+}
+
+/// Returns an iterator over the entries within a directory that are not
+/// hidden.
+///
+/// Whether an entry is hidden is determined in the same way as
+/// [`hf::is_hidden`](crate::is_hidden), except that symbolic links are not
+/// traversed. See [`DirEntryExt::is_hidden`] for details.
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`std::fs::read_dir`] returns an error.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::File;
+/// #
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// File::create(temp_dir.path().join("foo.txt")).unwrap();
+/// File::create(temp_dir.path().join(".bar.txt")).unwrap();
+///
+/// let entries = hf::read_dir_visible(temp_dir.path())
+///     .unwrap()
+///     .map(|e| e.unwrap().file_name())
+///     .collect::<Vec<_>>();
+/// assert_eq!(entries, ["foo.txt"]);
+/// # }
+/// ```
+#[inline]
+pub fn read_dir_visible(path: impl AsRef<Path>) -> io::Result<ReadDir> {
+    let inner = |path: &Path| -> io::Result<ReadDir> { ReadDir::new(path, Visibility::Visible) };
+    inner(path.as_ref())
+}
+
+/// Returns an iterator over the entries within a directory that are hidden.
+///
+/// Whether an entry is hidden is determined in the same way as
+/// [`hf::is_hidden`](crate::is_hidden), except that symbolic links are not
+/// traversed. See [`DirEntryExt::is_hidden`] for details.
+///
+/// # Errors
+///
+/// Returns [`Err`] if [`std::fs::read_dir`] returns an error.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::File;
+/// #
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// File::create(temp_dir.path().join("foo.txt")).unwrap();
+/// File::create(temp_dir.path().join(".bar.txt")).unwrap();
+///
+/// let entries = hf::read_dir_hidden(temp_dir.path())
+///     .unwrap()
+///     .map(|e| e.unwrap().file_name())
+///     .collect::<Vec<_>>();
+/// assert_eq!(entries, [".bar.txt"]);
+/// # }
+/// ```
+#[inline]
+pub fn read_dir_hidden(path: impl AsRef<Path>) -> io::Result<ReadDir> {
+    let inner = |path: &Path| -> io::Result<ReadDir> { ReadDir::new(path, Visibility::Hidden) };
+    inner(path.as_ref())
+}
+
+/// An iterator over the entries within a directory filtered by their
+/// visibility.
+///
+/// This is created by [`read_dir_visible`] and [`read_dir_hidden`].
+#[derive(Debug)]
+pub struct ReadDir {
+    inner: fs::ReadDir,
+    visibility: Visibility,
+}
+
+impl ReadDir {
+    fn new(path: &Path, visibility: Visibility) -> io::Result<Self> {
+        let inner = fs::read_dir(path)?;
+        Ok(Self { inner, visibility })
+    }
+}
+
+impl Iterator for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for entry in self.inner.by_ref() {
+            match entry.and_then(|e| self.visibility.matches(&e).map(|m| (e, m))) {
+                Ok((entry, true)) => return Some(Ok(entry)),
+                Ok((_, false)) => (),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+/// An entry yielded by [`Walk`].
+#[derive(Clone, Debug)]
+pub struct WalkEntry {
+    path: PathBuf,
+    depth: usize,
+    file_type: FileType,
+    path_is_symlink: bool,
+}
+
+impl WalkEntry {
+    /// Returns the path of this entry.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Consumes this entry and returns the path.
+    #[must_use]
+    #[inline]
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// Returns the file name of this entry.
+    ///
+    /// If this entry is the root of the walk, this may be the whole path.
+    #[must_use]
+    #[inline]
+    pub fn file_name(&self) -> &OsStr {
+        self.path
+            .file_name()
+            .unwrap_or_else(|| self.path.as_os_str())
+    }
+
+    /// Returns the depth of this entry relative to the root of the walk.
+    ///
+    /// The root has a depth of `0`.
+    #[must_use]
+    #[inline]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the file type of this entry.
+    ///
+    /// If symbolic links are followed, this is the file type of the target.
+    #[must_use]
+    #[inline]
+    pub const fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns [`true`] if the path of this entry is a symbolic link.
+    ///
+    /// This is [`true`] whether or not the link was followed. If it was,
+    /// [`WalkEntry::file_type`] is the file type of the target.
+    #[must_use]
+    #[inline]
+    pub const fn path_is_symlink(&self) -> bool {
+        self.path_is_symlink
+    }
+}
+
+#[derive(Debug)]
+struct Level {
+    entries: vec::IntoIter<io::Result<DirEntry>>,
+    // Used to detect loops when following symbolic links.
+    canonical_path: Option<PathBuf>,
+}
+
+/// A recursive iterator over the entries within a directory.
+///
+/// The iterator yields the root first, and then the entries in depth-first
+/// order. When filtering by [`Visibility::Visible`], hidden directories are not
+/// descended into. When filtering by [`Visibility::Hidden`], hidden entries are
+/// yielded, but hidden directories are not descended into because their
+/// contents are already invisible.
+///
+/// The options must be set before iterating.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::{self, File};
+/// #
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// let temp_dir = temp_dir.path();
+/// fs::create_dir(temp_dir.join("foo")).unwrap();
+/// File::create(temp_dir.join("foo/bar.txt")).unwrap();
+/// File::create(temp_dir.join("foo/.baz.txt")).unwrap();
+/// fs::create_dir(temp_dir.join(".qux")).unwrap();
+/// File::create(temp_dir.join(".qux/quux.txt")).unwrap();
+///
+/// let entries = hf::Walk::new(temp_dir)
+///     .min_depth(1)
+///     .visibility(hf::Visibility::Visible)
+///     .sort_by_file_name(true)
+///     .map(|e| e.unwrap().into_path())
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     entries,
+///     [temp_dir.join("foo"), temp_dir.join("foo/bar.txt")]
+/// );
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Walk {
+    root: Option<PathBuf>,
+    min_depth: usize,
+    max_depth: usize,
+    follow_links: bool,
+    visibility: Visibility,
+    sort_by_file_name: bool,
+    stack: Vec<Level>,
+    // An error which occurred while descending into the entry which has just
+    // been yielded.
+    pending: Option<Error>,
+}
+
+impl Walk {
+    /// Creates a new `Walk` which walks `root`.
+    #[must_use]
+    #[inline]
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: Some(root.as_ref().to_path_buf()),
+            min_depth: 0,
+            max_depth: usize::MAX,
+            follow_links: false,
+            visibility: Visibility::default(),
+            sort_by_file_name: false,
+            stack: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Sets the minimum depth of entries to yield.
+    ///
+    /// The default is `0`, which yields the root.
+    #[must_use]
+    #[inline]
+    pub const fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Sets the maximum depth of entries to yield.
+    ///
+    /// The default is [`usize::MAX`]. A depth of `0` yields only the root.
+    #[must_use]
+    #[inline]
+    pub const fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets whether to follow symbolic links.
+    ///
+    /// When `true`, symbolic links to directories are descended into, and an
+    /// error is yielded if a link points to one of its ancestors. When `false`,
+    /// symbolic links are yielded as is. The default is `false`.
+    #[must_use]
+    #[inline]
+    pub const fn follow_links(mut self, yes: bool) -> Self {
+        self.follow_links = yes;
+        self
+    }
+
+    /// Sets which entries to yield.
+    ///
+    /// The root is always yielded regardless of this option. The default is
+    /// [`Visibility::All`].
+    #[must_use]
+    #[inline]
+    pub const fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Sets whether to sort the entries of each directory by their file name.
+    ///
+    /// The default is `false`, which yields the entries in the order returned
+    /// by the operating system.
+    #[must_use]
+    #[inline]
+    pub const fn sort_by_file_name(mut self, yes: bool) -> Self {
+        self.sort_by_file_name = yes;
+        self
+    }
+
+    fn push(&mut self, path: &Path, canonical_path: Option<PathBuf>) -> io::Result<()> {
+        let mut entries = fs::read_dir(path)?.collect::<Vec<_>>();
+        if self.sort_by_file_name {
+            entries.sort_by(|a, b| match (a, b) {
+                (Ok(a), Ok(b)) => a.file_name().cmp(&b.file_name()),
+                (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+                (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+                (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+            });
+        }
+        self.stack.push(Level {
+            entries: entries.into_iter(),
+            canonical_path,
+        });
+        Ok(())
+    }
+
+    fn canonical_path(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        if !self.follow_links {
+            return Ok(None);
+        }
+        let canonical_path = fs::canonicalize(path)?;
+        if self
+            .stack
+            .iter()
+            .any(|l| l.canonical_path.as_ref() == Some(&canonical_path))
+        {
+            return Err(Error::other(format!(
+                "{} is a file system loop",
+                path.display()
+            )));
+        }
+        Ok(Some(canonical_path))
+    }
+
+    fn handle_entry(&mut self, entry: &DirEntry, depth: usize) -> io::Result<Option<WalkEntry>> {
+        if !self.visibility.matches(entry)? {
+            // Hidden directories are not descended into, but visible
+            // directories may contain hidden entries.
+            if self.visibility == Visibility::Hidden
+                && depth < self.max_depth
+                && self.is_dir(entry)?
+            {
+                self.descend(&entry.path());
+            }
+            return Ok(None);
+        }
+
+        let path = entry.path();
+        let mut file_type = entry.file_type()?;
+        let path_is_symlink = file_type.is_symlink();
+        if self.follow_links && path_is_symlink {
+            file_type = fs::metadata(&path)?.file_type();
+        }
+        let is_hidden = self.visibility == Visibility::Hidden;
+        if file_type.is_dir() && depth < self.max_depth && !is_hidden {
+            self.descend(&path);
+        }
+        Ok(Some(WalkEntry {
+            path,
+            depth,
+            file_type,
+            path_is_symlink,
+        }))
+    }
+
+    /// Pushes the entries of the directory at `path`.
+    ///
+    /// The directory itself is yielded before an error which occurred while
+    /// reading it, so the error is kept until the next iteration.
+    fn descend(&mut self, path: &Path) {
+        if let Err(err) = self
+            .canonical_path(path)
+            .and_then(|canonical_path| self.push(path, canonical_path))
+        {
+            self.pending = Some(err);
+        }
+    }
+
+    fn is_dir(&self, entry: &DirEntry) -> io::Result<bool> {
+        let file_type = entry.file_type()?;
+        if self.follow_links && file_type.is_symlink() {
+            fs::metadata(entry.path()).map(|m| m.is_dir())
+        } else {
+            Ok(file_type.is_dir())
+        }
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            let result = (|| {
+                let metadata = fs::symlink_metadata(&root)?;
+                let path_is_symlink = metadata.is_symlink();
+                let metadata = if self.follow_links && path_is_symlink {
+                    fs::metadata(&root)?
+                } else {
+                    metadata
+                };
+                if metadata.is_dir() && self.max_depth > 0 {
+                    self.descend(&root);
+                }
+                Ok(WalkEntry {
+                    path: root,
+                    depth: 0,
+                    file_type: metadata.file_type(),
+                    path_is_symlink,
+                })
+            })();
+            match result {
+                Ok(_) if self.min_depth > 0 => (),
+                result => return Some(result),
+            }
+        }
+
+        loop {
+            if let Some(err) = self.pending.take() {
+                return Some(Err(err));
+            }
+            let level = self.stack.last_mut()?;
+            let Some(entry) = level.entries.next() else {
+                self.stack.pop();
+                continue;
+            };
+            let depth = self.stack.len();
+            match entry.and_then(|e| self.handle_entry(&e, depth)) {
+                Ok(Some(entry)) if entry.depth >= self.min_depth => return Some(Ok(entry)),
+                Ok(_) => (),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::ErrorKind};
+
+    use super::*;
+
+    fn create_tree() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("foo")).unwrap();
+        File::create(root.join("foo/bar.txt")).unwrap();
+        fs::create_dir(root.join("foo/baz")).unwrap();
+        File::create(root.join("foo/baz/qux.txt")).unwrap();
+        File::create(root.join("quux.txt")).unwrap();
+        for path in [
+            root.join(if cfg!(unix) { ".foo.txt" } else { "foo.txt" }),
+            root.join(if cfg!(unix) { "foo/.bar" } else { "foo/bar" }),
+        ] {
+            if path.extension().is_some() {
+                File::create(&path).unwrap();
+            } else {
+                fs::create_dir(&path).unwrap();
+                File::create(path.join("corge.txt")).unwrap();
+            }
+            #[cfg(windows)]
+            crate::hide(&path).unwrap();
+        }
+        temp_dir
+    }
+
+    fn relative_paths(root: &Path, walk: Walk) -> Vec<PathBuf> {
+        walk.map(|e| e.unwrap().path().strip_prefix(root).unwrap().to_path_buf())
+            .collect()
+    }
+
+    #[test]
+    fn read_dir_visible() {
+        let temp_dir = create_tree();
+        let mut entries = super::read_dir_visible(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, ["foo", "quux.txt"]);
+    }
+
+    #[test]
+    fn read_dir_hidden() {
+        let temp_dir = create_tree();
+        let entries = super::read_dir_hidden(temp_dir.path().join("foo"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [if cfg!(unix) { ".bar" } else { "bar" }]);
+    }
+
+    #[test]
+    fn read_dir_when_directory_does_not_exist() {
+        assert_eq!(
+            super::read_dir_visible("non_existent").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            super::read_dir_hidden("non_existent").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn walk() {
+        let temp_dir = create_tree();
+        let root = temp_dir.path();
+        let entries = relative_paths(root, Walk::new(root).sort_by_file_name(true));
+        let expected: &[&str] = if cfg!(unix) {
+            &[
+                "",
+                ".foo.txt",
+                "foo",
+                "foo/.bar",
+                "foo/.bar/corge.txt",
+                "foo/bar.txt",
+                "foo/baz",
+                "foo/baz/qux.txt",
+                "quux.txt",
+            ]
+        } else {
+            &[
+                "",
+                "foo",
+                "foo/bar",
+                "foo/bar/corge.txt",
+                "foo/bar.txt",
+                "foo/baz",
+                "foo/baz/qux.txt",
+                "foo.txt",
+                "quux.txt",
+            ]
+        };
+        assert_eq!(
+            entries,
+            expected.iter().map(PathBuf::from).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn walk_visible() {
+        let temp_dir = create_tree();
+        let root = temp_dir.path();
+        let entries = relative_paths(
+            root,
+            Walk::new(root)
+                .min_depth(1)
+                .visibility(Visibility::Visible)
+                .sort_by_file_name(true),
+        );
+        assert_eq!(
+            entries,
+            [
+                "foo",
+                "foo/bar.txt",
+                "foo/baz",
+                "foo/baz/qux.txt",
+                "quux.txt"
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn walk_hidden() {
+        let temp_dir = create_tree();
+        let root = temp_dir.path();
+        let mut entries = relative_paths(root, Walk::new(root).visibility(Visibility::Hidden));
+        entries.sort();
+        let expected = if cfg!(unix) {
+            ["", ".foo.txt", "foo/.bar"]
+        } else {
+            ["", "foo.txt", "foo/bar"]
+        };
+        assert_eq!(entries, expected.map(PathBuf::from));
+    }
+
+    #[test]
+    fn walk_with_depth() {
+        let temp_dir = create_tree();
+        let root = temp_dir.path();
+        let entries = relative_paths(
+            root,
+            Walk::new(root)
+                .min_depth(2)
+                .max_depth(2)
+                .visibility(Visibility::Visible)
+                .sort_by_file_name(true),
+        );
+        assert_eq!(entries, ["foo/bar.txt", "foo/baz"].map(PathBuf::from));
+
+        let entries = relative_paths(root, Walk::new(root).max_depth(0));
+        assert_eq!(entries, [PathBuf::new()]);
+    }
+
+    #[test]
+    fn walk_when_root_does_not_exist() {
+        let mut walk = Walk::new("non_existent");
+        assert_eq!(
+            walk.next().unwrap().unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert!(walk.next().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_with_symlink() {
+        let temp_dir = create_tree();
+        let root = temp_dir.path();
+        std::os::unix::fs::symlink(root.join("foo/baz"), root.join("link")).unwrap();
+
+        let entries = relative_paths(
+            root,
+            Walk::new(root)
+                .min_depth(1)
+                .visibility(Visibility::Visible)
+                .sort_by_file_name(true),
+        );
+        assert!(entries.contains(&PathBuf::from("link")));
+        assert!(!entries.contains(&PathBuf::from("link/qux.txt")));
+
+        let entries = relative_paths(
+            root,
+            Walk::new(root)
+                .min_depth(1)
+                .visibility(Visibility::Visible)
+                .follow_links(true)
+                .sort_by_file_name(true),
+        );
+        assert!(entries.contains(&PathBuf::from("link/qux.txt")));
+
+        for follow_links in [false, true] {
+            let entry = Walk::new(root)
+                .follow_links(follow_links)
+                .find_map(|e| e.ok().filter(|e| e.file_name() == "link"))
+                .unwrap();
+            assert!(entry.path_is_symlink());
+            assert_eq!(entry.file_type().is_dir(), follow_links);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_with_symlink_loop() {
+        let temp_dir = create_tree();
+        let root = temp_dir.path();
+        std::os::unix::fs::symlink(root.join("foo"), root.join("foo/baz/loop")).unwrap();
+
+        let results = Walk::new(root).follow_links(true).collect::<Vec<_>>();
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+
+        // The link is yielded before the error.
+        let i = results.iter().position(Result::is_err).unwrap();
+        assert_eq!(
+            results[i - 1].as_ref().unwrap().path(),
+            root.join("foo/baz/loop")
+        );
+    }
+}
@@ -0,0 +1,265 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Provides predicates for [`walkdir`].
+//!
+//! [`is_hidden`] and [`is_visible`] determine whether an entry is hidden in
+//! the same way as [`DirEntryExt::is_hidden`](crate::DirEntryExt::is_hidden),
+//! so they respect the hidden file attribute on Windows. [`HiddenFilter`] also
+//! treats the entries listed in the `.hidden` file in their parent directory
+//! as hidden on Unix. They are intended to be passed to
+//! [`walkdir::IntoIter::filter_entry`].
+
+use walkdir::DirEntry;
+
+#[cfg(unix)]
+use crate::migrate::ListCache;
+use crate::platform::imp;
+
+/// Returns [`true`] if the entry is a hidden file or directory.
+///
+/// The root of the walk is never considered hidden, so the walk is not skipped
+/// entirely when the root is, for example, `.`. Returns [`false`] if the
+/// metadata about the entry could not be obtained.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::File;
+/// #
+/// use walkdir::WalkDir;
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// File::create(temp_dir.path().join("foo.txt")).unwrap();
+/// File::create(temp_dir.path().join(".bar.txt")).unwrap();
+///
+/// let entries = WalkDir::new(temp_dir.path())
+///     .min_depth(1)
+///     .into_iter()
+///     .filter_map(Result::ok)
+///     .filter(hf::walkdir::is_hidden)
+///     .map(|e| e.file_name().to_owned())
+///     .collect::<Vec<_>>();
+/// assert_eq!(entries, [".bar.txt"]);
+/// # }
+/// ```
+#[must_use]
+#[inline]
+pub fn is_hidden(entry: &DirEntry) -> bool {
+    if entry.depth() == 0 {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        imp::is_hidden_name(entry.file_name())
+    }
+    #[cfg(windows)]
+    {
+        entry
+            .metadata()
+            .is_ok_and(|m| imp::has_hidden_attribute(&m))
+    }
+}
+
+/// Returns [`true`] if the entry is not a hidden file or directory.
+///
+/// This is the negation of [`is_hidden`]. Passing this to
+/// [`walkdir::IntoIter::filter_entry`] skips hidden entries and does not
+/// descend into hidden directories.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::{self, File};
+/// #
+/// use walkdir::WalkDir;
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// fs::create_dir(temp_dir.path().join(".foo")).unwrap();
+/// File::create(temp_dir.path().join(".foo/bar.txt")).unwrap();
+/// File::create(temp_dir.path().join("baz.txt")).unwrap();
+///
+/// let entries = WalkDir::new(temp_dir.path())
+///     .min_depth(1)
+///     .into_iter()
+///     .filter_entry(hf::walkdir::is_visible)
+///     .map(|e| e.unwrap().file_name().to_owned())
+///     .collect::<Vec<_>>();
+/// assert_eq!(entries, ["baz.txt"]);
+/// # }
+/// ```
+#[must_use]
+#[inline]
+pub fn is_visible(entry: &DirEntry) -> bool {
+    !is_hidden(entry)
+}
+
+/// A predicate which also treats the entries listed in the `.hidden` file in
+/// their parent directory as hidden.
+///
+/// On Unix, the `.hidden` file of each directory is read once and kept while
+/// the walk is in the directory, so a filter should be used for a single walk.
+/// A `.hidden` file which could not be read is treated as empty. On Windows,
+/// this is the same as [`is_hidden`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(unix)]
+/// # {
+/// # use std::fs::{self, File};
+/// #
+/// use hf::walkdir::HiddenFilter;
+/// use walkdir::WalkDir;
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// fs::write(temp_dir.path().join(".hidden"), "foo.txt\n").unwrap();
+/// File::create(temp_dir.path().join("foo.txt")).unwrap();
+/// File::create(temp_dir.path().join("bar.txt")).unwrap();
+///
+/// let mut filter = HiddenFilter::new();
+/// let entries = WalkDir::new(temp_dir.path())
+///     .min_depth(1)
+///     .into_iter()
+///     .filter_entry(|e| filter.is_visible(e))
+///     .map(|e| e.unwrap().file_name().to_owned())
+///     .collect::<Vec<_>>();
+/// assert_eq!(entries, ["bar.txt"]);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct HiddenFilter {
+    #[cfg(unix)]
+    lists: ListCache,
+}
+
+impl HiddenFilter {
+    /// Creates a new `HiddenFilter`.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns [`true`] if the entry is a hidden file or directory, or it is
+    /// listed in the `.hidden` file in its parent directory.
+    ///
+    /// The root of the walk is never considered hidden.
+    #[must_use]
+    #[inline]
+    pub fn is_hidden(&mut self, entry: &DirEntry) -> bool {
+        if is_hidden(entry) {
+            return true;
+        }
+        #[cfg(unix)]
+        {
+            entry.depth() > 0
+                && entry
+                    .path()
+                    .parent()
+                    .is_some_and(|p| self.lists.is_listed(p, entry.file_name()))
+        }
+        #[cfg(windows)]
+        {
+            false
+        }
+    }
+
+    /// Returns [`true`] if the entry is not a hidden file or directory.
+    ///
+    /// This is the negation of [`HiddenFilter::is_hidden`].
+    #[must_use]
+    #[inline]
+    pub fn is_visible(&mut self, entry: &DirEntry) -> bool {
+        !self.is_hidden(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+
+    use walkdir::WalkDir;
+
+    use super::*;
+
+    #[test]
+    fn filter_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let hidden_dir = root.join(if cfg!(unix) { ".foo" } else { "foo" });
+        fs::create_dir(&hidden_dir).unwrap();
+        File::create(hidden_dir.join("bar.txt")).unwrap();
+        File::create(root.join("baz.txt")).unwrap();
+        #[cfg(windows)]
+        crate::hide(&hidden_dir).unwrap();
+
+        let entries = WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(is_visible)
+            .map(|e| e.unwrap().into_path())
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [root.to_path_buf(), root.join("baz.txt")]);
+
+        let entries = WalkDir::new(root)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(is_hidden)
+            .map(DirEntry::into_path)
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [hidden_dir]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hidden_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("foo")).unwrap();
+        fs::write(root.join(".hidden"), "bar.txt\n").unwrap();
+        fs::write(root.join("foo/.hidden"), "baz.txt\n").unwrap();
+        for name in ["bar.txt", "baz.txt", "foo/bar.txt", "foo/baz.txt"] {
+            File::create(root.join(name)).unwrap();
+        }
+
+        let entries = WalkDir::new(root)
+            .into_iter()
+            .filter_entry(is_visible)
+            .count();
+        assert_eq!(entries, 6);
+
+        let mut filter = HiddenFilter::new();
+        let entries = WalkDir::new(root)
+            .min_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| filter.is_visible(e))
+            .map(|e| e.unwrap().into_path())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                root.join("baz.txt"),
+                root.join("foo"),
+                root.join("foo/bar.txt")
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn root_is_not_hidden() {
+        let entries = WalkDir::new(".")
+            .max_depth(0)
+            .into_iter()
+            .filter_entry(is_visible)
+            .count();
+        assert_eq!(entries, 1);
+    }
+}
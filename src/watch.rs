@@ -0,0 +1,561 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Watching directories for changes in the visibility of files.
+//!
+//! A [`Watcher`] translates the notifications of the file system into
+//! [`VisibilityChanged`] events. The following changes are reported:
+//!
+//! - Renaming a file to or from a name which starts with `.`
+//!   ([`Mechanism::Dot`]).
+//! - Setting or clearing the hidden file attribute ([`Mechanism::Attribute`]).
+//! - Adding a file name to or removing it from the `.hidden` file in the parent
+//!   directory ([`Mechanism::HiddenList`]).
+//! - Setting or clearing the hidden bit of the DOS attributes which Samba
+//!   stores in the extended attributes ([`Mechanism::Samba`]).
+//!
+//! Creating or removing a file is not reported, even if the file is hidden.
+//! A file which is created or moved into the directory is assumed to be
+//! visible except for its name.
+//!
+//! # Platform-specific behavior
+//!
+//! A rename is only reported as [`Mechanism::Dot`] if the file system
+//! notifications contain both the old and the new paths. This is the case on
+//! Linux and Windows, but not on macOS.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(target_os = "linux")]
+//! # {
+//! use std::{fs, time::Duration};
+//!
+//! use hf::{migrate::Mechanism, watch::Watcher};
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//! let temp_dir = temp_dir.path();
+//! fs::write(temp_dir.join("foo.txt"), "").unwrap();
+//!
+//! let mut watcher = Watcher::new(temp_dir, false).unwrap();
+//! fs::rename(temp_dir.join("foo.txt"), temp_dir.join(".foo.txt")).unwrap();
+//!
+//! let event = watcher
+//!     .recv_timeout(Duration::from_secs(10))
+//!     .unwrap()
+//!     .unwrap();
+//! assert_eq!(event.path(), temp_dir.join(".foo.txt"));
+//! assert!(event.is_hidden());
+//! assert_eq!(event.mechanism(), Mechanism::Dot);
+//! # }
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::{self, Error},
+    mem,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _,
+    event::{ModifyKind, RenameMode},
+};
+
+use crate::{
+    Walk,
+    migrate::{self, HIDDEN_LIST, Mechanism},
+    scheme::{Dot, NamingScheme},
+};
+
+/// The mechanisms which are checked when the metadata of a file changes.
+const ATTRIBUTES: [Mechanism; 2] = [Mechanism::Attribute, Mechanism::Samba];
+
+/// An event which reports that a file has become hidden or visible.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct VisibilityChanged {
+    path: PathBuf,
+    hidden: bool,
+    mechanism: Mechanism,
+}
+
+impl VisibilityChanged {
+    /// Returns the path of the file.
+    ///
+    /// If the file has been renamed, this is the new path.
+    #[must_use]
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns [`true`] if the file has become hidden, and [`false`] if it has
+    /// become visible.
+    #[must_use]
+    #[inline]
+    pub const fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Returns the mechanism which the file has been hidden or shown with.
+    #[must_use]
+    #[inline]
+    pub const fn mechanism(&self) -> Mechanism {
+        self.mechanism
+    }
+}
+
+/// A watcher which reports changes in the visibility of the files in a
+/// directory.
+///
+/// The current state of the `.hidden` files and the attributes is read when
+/// the watcher is created, so that only the later changes are reported.
+#[derive(Debug)]
+pub struct Watcher {
+    // Dropping this stops the notifications.
+    _notifier: RecommendedWatcher,
+    rx: Receiver<notify::Result<Event>>,
+    lists: HashMap<PathBuf, Vec<u8>>,
+    attributes: HashSet<(PathBuf, Mechanism)>,
+    pending: VecDeque<VisibilityChanged>,
+    rename_from: Option<(PathBuf, Option<usize>)>,
+}
+
+impl Watcher {
+    /// Starts watching the directory at `path`.
+    ///
+    /// If `recursive` is [`true`], the subdirectories are also watched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the directory could not be watched.
+    #[inline]
+    pub fn new(path: impl AsRef<Path>, recursive: bool) -> io::Result<Self> {
+        let inner = |path: &Path| -> io::Result<Self> {
+            let (tx, rx) = mpsc::channel();
+            let mut watcher = notify::recommended_watcher(tx).map_err(into_io_error)?;
+            let mode = if recursive {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            watcher.watch(path, mode).map_err(into_io_error)?;
+            let mut watcher = Self {
+                _notifier: watcher,
+                rx,
+                lists: HashMap::new(),
+                attributes: HashSet::new(),
+                pending: VecDeque::new(),
+                rename_from: None,
+            };
+            watcher.read_state(path, if recursive { usize::MAX } else { 1 });
+            Ok(watcher)
+        };
+        inner(path.as_ref())
+    }
+
+    /// Waits for the next event.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the file system notification reports an error, or the
+    /// state of a changed file could not be read.
+    #[inline]
+    pub fn recv(&mut self) -> io::Result<VisibilityChanged> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            let event = self.rx.recv().map_err(Error::other)?;
+            self.handle(event.map_err(into_io_error)?)?;
+        }
+    }
+
+    /// Waits for the next event for at most `timeout`.
+    ///
+    /// Returns [`None`] if no event occurred before the timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if the file system notification reports an error, or the
+    /// state of a changed file could not be read.
+    #[inline]
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<Option<VisibilityChanged>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.rx.recv_timeout(timeout) {
+                Ok(event) => self.handle(event.map_err(into_io_error)?)?,
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(err) => return Err(Error::other(err)),
+            }
+        }
+    }
+
+    /// Translates a notification into events.
+    fn handle(&mut self, event: Event) -> io::Result<()> {
+        let tracker = event.tracker();
+        let rename_from = self.rename_from.take();
+        match event.kind {
+            // The old path comes just before the new path. It may not be
+            // followed by the new path if the entry has been moved out of the
+            // watched directory.
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                for path in event.paths {
+                    match &rename_from {
+                        Some((from, t)) if *t == tracker => self.moved(from, &path)?,
+                        _ => self.created(&path)?,
+                    }
+                }
+                return Ok(());
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                self.rename_from = event.paths.into_iter().next().map(|p| (p, tracker));
+            }
+            // This is reported after the old and the new paths.
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {}
+            EventKind::Create(_) => {
+                for path in &event.paths {
+                    self.created(path)?;
+                }
+            }
+            EventKind::Remove(_) => {
+                for path in &event.paths {
+                    self.removed(path)?;
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                for path in &event.paths {
+                    if path.symlink_metadata().is_ok() {
+                        self.created(path)?;
+                    } else {
+                        self.removed(path)?;
+                    }
+                }
+            }
+            EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any) => {
+                for path in event.paths.iter().filter(|p| is_list(p)) {
+                    self.refresh_list(path)?;
+                }
+            }
+            EventKind::Modify(ModifyKind::Metadata(_)) => {
+                for path in &event.paths {
+                    self.refresh_attributes(path);
+                }
+            }
+            _ => {}
+        }
+        if let Some((from, _)) = rename_from {
+            self.removed(&from)?;
+        }
+        Ok(())
+    }
+
+    /// Handles the entry at `path` which has been created or moved into the
+    /// watched directory.
+    ///
+    /// The entry is assumed to be visible except for its name.
+    fn created(&mut self, path: &Path) -> io::Result<()> {
+        if is_list(path) {
+            self.refresh_list(path)?;
+        }
+        Ok(())
+    }
+
+    /// Forgets the state of the entry at `path` which has been removed or
+    /// moved out of the watched directory.
+    fn removed(&mut self, path: &Path) -> io::Result<()> {
+        if is_list(path) {
+            return self.refresh_list(path);
+        }
+        self.lists.retain(|p, _| !p.starts_with(path));
+        self.attributes.retain(|(p, _)| !p.starts_with(path));
+        Ok(())
+    }
+
+    /// Handles the entry which has been renamed from `from` to `to`.
+    fn moved(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        if is_list(from) || is_list(to) {
+            self.removed(from)?;
+            return self.created(to);
+        }
+        let rebase = |p: &Path| p.strip_prefix(from).map(|p| to.join(p)).ok();
+        self.lists = mem::take(&mut self.lists)
+            .into_iter()
+            .map(|(p, list)| (rebase(&p).unwrap_or(p), list))
+            .collect();
+        self.attributes = mem::take(&mut self.attributes)
+            .into_iter()
+            .map(|(p, mechanism)| (rebase(&p).unwrap_or(p), mechanism))
+            .collect();
+
+        let is_hidden_name = |p: &Path| p.file_name().is_some_and(|n| Dot.is_hidden_name(n));
+        let hidden = is_hidden_name(to);
+        if is_hidden_name(from) != hidden {
+            self.push(to.to_path_buf(), hidden, Mechanism::Dot);
+        }
+        Ok(())
+    }
+
+    /// Reads the `.hidden` files and the attributes of the entries under
+    /// `path` without reporting them.
+    fn read_state(&mut self, path: &Path, max_depth: usize) {
+        for entry in Walk::new(path).max_depth(max_depth).filter_map(Result::ok) {
+            for mechanism in ATTRIBUTES {
+                if mechanism.is_hidden(entry.path()).unwrap_or_default() {
+                    self.attributes
+                        .insert((entry.path().to_path_buf(), mechanism));
+                }
+            }
+            if entry.file_type().is_dir() && entry.depth() < max_depth {
+                match migrate::read_list(&entry.path().join(HIDDEN_LIST)) {
+                    Ok(list) if !list.is_empty() => {
+                        self.lists.insert(entry.into_path(), list);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Reports the entries which have been added to or removed from the
+    /// `.hidden` file at `list_path`.
+    fn refresh_list(&mut self, list_path: &Path) -> io::Result<()> {
+        let Some(dir) = list_path.parent() else {
+            return Ok(());
+        };
+        let list = migrate::read_list(list_path)?;
+        let old_list = if list.is_empty() {
+            self.lists.remove(dir)
+        } else {
+            self.lists.insert(dir.to_path_buf(), list.clone())
+        }
+        .unwrap_or_default();
+        if list == old_list {
+            return Ok(());
+        }
+
+        let names = migrate::lines(&list).collect::<HashSet<_>>();
+        let old_names = migrate::lines(&old_list).collect::<HashSet<_>>();
+        let mut changes = fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let file_name = entry.file_name();
+                let file_name = file_name.as_encoded_bytes();
+                let hidden = names.contains(file_name);
+                (hidden != old_names.contains(file_name)).then(|| (entry.path(), hidden))
+            })
+            .collect::<Vec<_>>();
+        changes.sort_unstable();
+        for (path, hidden) in changes {
+            self.push(path, hidden, Mechanism::HiddenList);
+        }
+        Ok(())
+    }
+
+    /// Reports the attributes of the entry at `path` which have changed.
+    fn refresh_attributes(&mut self, path: &Path) {
+        for mechanism in ATTRIBUTES {
+            let Ok(hidden) = mechanism.is_hidden(path) else {
+                continue;
+            };
+            let key = (path.to_path_buf(), mechanism);
+            let changed = if hidden {
+                self.attributes.insert(key)
+            } else {
+                self.attributes.remove(&key)
+            };
+            if changed {
+                self.push(path.to_path_buf(), hidden, mechanism);
+            }
+        }
+    }
+
+    fn push(&mut self, path: PathBuf, hidden: bool, mechanism: Mechanism) {
+        self.pending.push_back(VisibilityChanged {
+            path,
+            hidden,
+            mechanism,
+        });
+    }
+}
+
+impl Iterator for Watcher {
+    type Item = io::Result<VisibilityChanged>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.recv())
+    }
+}
+
+/// Returns [`true`] if `path` is a `.hidden` file.
+fn is_list(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == HIDDEN_LIST)
+}
+
+fn into_io_error(err: notify::Error) -> Error {
+    match err.kind {
+        notify::ErrorKind::Io(err) => err,
+        notify::ErrorKind::PathNotFound => Error::from(io::ErrorKind::NotFound),
+        _ => Error::other(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, thread};
+
+    use super::*;
+    use crate::migrate::Migration;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn recv(watcher: &mut Watcher) -> VisibilityChanged {
+        watcher.recv_timeout(TIMEOUT).unwrap().unwrap()
+    }
+
+    #[test]
+    fn visibility_changed() {
+        let event = VisibilityChanged {
+            path: PathBuf::from(".foo.txt"),
+            hidden: true,
+            mechanism: Mechanism::Dot,
+        };
+        assert_eq!(event.path(), Path::new(".foo.txt"));
+        assert!(event.is_hidden());
+        assert_eq!(event.mechanism(), Mechanism::Dot);
+    }
+
+    #[cfg(any(target_os = "linux", windows))]
+    #[test]
+    fn rename_to_and_from_dot_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        File::create(temp_dir.join("bar.txt")).unwrap();
+
+        let mut watcher = Watcher::new(temp_dir, false).unwrap();
+        fs::rename(temp_dir.join("bar.txt"), temp_dir.join("baz.txt")).unwrap();
+        fs::rename(temp_dir.join("foo.txt"), temp_dir.join(".foo.txt")).unwrap();
+        let event = recv(&mut watcher);
+        assert_eq!(event.path(), temp_dir.join(".foo.txt"));
+        assert!(event.is_hidden());
+        assert_eq!(event.mechanism(), Mechanism::Dot);
+
+        fs::rename(temp_dir.join(".foo.txt"), temp_dir.join("foo.txt")).unwrap();
+        let event = recv(&mut watcher);
+        assert_eq!(event.path(), temp_dir.join("foo.txt"));
+        assert!(!event.is_hidden());
+        assert_eq!(event.mechanism(), Mechanism::Dot);
+        assert!(watcher.recv_timeout(Duration::ZERO).unwrap().is_none());
+    }
+
+    #[cfg(any(target_os = "linux", windows))]
+    #[test]
+    fn change_hidden_list() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join("foo.txt")).unwrap();
+        File::create(temp_dir.join("bar.txt")).unwrap();
+        fs::write(temp_dir.join(".hidden"), "bar.txt\n").unwrap();
+
+        let mut watcher = Watcher::new(temp_dir, false).unwrap();
+        fs::write(temp_dir.join(".hidden"), "bar.txt\nfoo.txt\n").unwrap();
+        let event = recv(&mut watcher);
+        assert_eq!(event.path(), temp_dir.join("foo.txt"));
+        assert!(event.is_hidden());
+        assert_eq!(event.mechanism(), Mechanism::HiddenList);
+
+        fs::remove_file(temp_dir.join(".hidden")).unwrap();
+        let mut events = [recv(&mut watcher), recv(&mut watcher)];
+        events.sort_unstable_by(|a, b| a.path().cmp(b.path()));
+        assert_eq!(events[0].path(), temp_dir.join("bar.txt"));
+        assert!(!events[0].is_hidden());
+        assert_eq!(events[1].path(), temp_dir.join("foo.txt"));
+        assert!(!events[1].is_hidden());
+    }
+
+    #[cfg(any(target_os = "linux", windows))]
+    #[test]
+    fn change_hidden_list_in_subdirectory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+
+        let mut watcher = Watcher::new(temp_dir, true).unwrap();
+        fs::create_dir(temp_dir.join("foo")).unwrap();
+        File::create(temp_dir.join("foo/bar.txt")).unwrap();
+        // Wait until the new directory is watched.
+        thread::sleep(Duration::from_millis(500));
+        fs::write(temp_dir.join("foo/.hidden"), "bar.txt\n").unwrap();
+        let event = recv(&mut watcher);
+        assert_eq!(event.path(), temp_dir.join("foo/bar.txt"));
+        assert!(event.is_hidden());
+        assert_eq!(event.mechanism(), Mechanism::HiddenList);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn watch_directory_with_fifo() {
+        use std::sync::mpsc;
+
+        use rustix::fs::{CWD, FileType, Mode};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path().to_path_buf();
+        let mkfifo = |path: &Path| {
+            rustix::fs::mknodat(CWD, path, FileType::Fifo, Mode::RUSR | Mode::WUSR, 0).unwrap();
+        };
+        mkfifo(&temp_dir.join("foo"));
+        File::create(temp_dir.join("bar.txt")).unwrap();
+
+        // Reading the state of a FIFO must not block until a writer opens it.
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut watcher = Watcher::new(&temp_dir, false).unwrap();
+            mkfifo(&temp_dir.join("baz"));
+            fs::rename(temp_dir.join("bar.txt"), temp_dir.join(".bar.txt")).unwrap();
+            let event = recv(&mut watcher);
+            tx.send((event, temp_dir)).unwrap();
+        });
+        let (event, temp_dir) = rx.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(event.path(), temp_dir.join(".bar.txt"));
+        assert!(event.is_hidden());
+        assert_eq!(event.mechanism(), Mechanism::Dot);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn migrate_to_samba() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_dir = temp_dir.path();
+        File::create(temp_dir.join(".foo.txt")).unwrap();
+
+        // The temporary directory may not support extended attributes.
+        if Mechanism::Samba
+            .is_hidden(temp_dir.join(".foo.txt"))
+            .is_err()
+        {
+            return;
+        }
+        let mut watcher = Watcher::new(temp_dir, false).unwrap();
+        let mut migration = Migration::new(Mechanism::Dot, Mechanism::Samba);
+        migration.find(temp_dir, false).unwrap();
+        migration.execute().unwrap();
+        let mut events = [recv(&mut watcher), recv(&mut watcher)];
+        events.sort_unstable_by_key(VisibilityChanged::is_hidden);
+        assert_eq!(events[0].path(), temp_dir.join("foo.txt"));
+        assert!(!events[0].is_hidden());
+        assert_eq!(events[0].mechanism(), Mechanism::Dot);
+        assert_eq!(events[1].path(), temp_dir.join("foo.txt"));
+        assert!(events[1].is_hidden());
+        assert_eq!(events[1].mechanism(), Mechanism::Samba);
+    }
+}
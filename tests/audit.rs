@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use std::fs::{self, File};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+
+const MANIFEST: &str = r#"
+[[rule]]
+glob = "**/*.log"
+state = "hidden"
+
+[[rule]]
+path = "README.md"
+state = "visible"
+"#;
+
+#[cfg(unix)]
+#[test]
+fn basic_audit() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(temp_dir.join("hf.toml"), MANIFEST).unwrap();
+    File::create(temp_dir.join("foo.log")).unwrap();
+    File::create(temp_dir.join(".bar.log")).unwrap();
+    File::create(temp_dir.join(".README.md")).unwrap();
+
+    utils::command::command()
+        .arg("audit")
+        .arg("-m")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains(format!(
+            "{}: should be hidden",
+            temp_dir.join("foo.log").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}: should be visible",
+            temp_dir.join(".README.md").display()
+        )))
+        .stdout(predicate::str::contains("bar.log").not())
+        .stderr(predicate::str::contains(format!(
+            "2 files are not in the state declared by {}",
+            temp_dir.join("hf.toml").display()
+        )));
+}
+
+#[test]
+fn audit_without_drift() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(temp_dir.join("hf.toml"), MANIFEST).unwrap();
+    File::create(temp_dir.join("README.md")).unwrap();
+
+    utils::command::command()
+        .arg("audit")
+        .arg("--manifest")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn audit_uses_manifest_in_current_directory_by_default() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    fs::write(temp_dir.path().join("hf.toml"), MANIFEST).unwrap();
+
+    utils::command::command()
+        .current_dir(&temp_dir)
+        .arg("audit")
+        .assert()
+        .success();
+}
+
+#[test]
+fn audit_when_manifest_is_invalid() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(temp_dir.join("hf.toml"), "[[rule]]\nstate = \"hidden\"\n").unwrap();
+
+    utils::command::command()
+        .arg("audit")
+        .arg("-m")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .failure()
+        .code(65)
+        .stderr(predicate::str::contains(
+            "a rule must have either `path` or `glob`",
+        ));
+}
+
+#[test]
+fn audit_when_manifest_does_not_exist() {
+    utils::command::command()
+        .arg("audit")
+        .arg("-m")
+        .arg("non_existent.toml")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains("could not read non_existent.toml"));
+}
+
+#[test]
+fn long_version_for_audit_command() {
+    utils::command::command()
+        .arg("audit")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_audit_command() {
+    utils::command::command()
+        .arg("audit")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/audit-after-long-help.md"
+        )));
+}
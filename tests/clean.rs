@@ -0,0 +1,262 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use std::fs::{self, File};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+
+#[test]
+fn basic_clean() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    let junk_path = (temp_dir.join(".DS_Store"), temp_dir.join("Thumbs.db"));
+
+    File::create(&junk_path.0).unwrap();
+    File::create(&junk_path.1).unwrap();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-n")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            junk_path.0.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            junk_path.1.display()
+        )))
+        .stdout(predicate::str::contains("foo.txt").not());
+    assert!(junk_path.0.exists());
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been removed",
+            junk_path.0.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} has been removed",
+            junk_path.1.display()
+        )));
+    assert!(!junk_path.0.exists());
+    assert!(!junk_path.1.exists());
+    assert!(temp_dir.join("foo.txt").exists());
+}
+
+#[test]
+fn clean_current_directory_by_default() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    File::create(temp_dir.join("desktop.ini")).unwrap();
+
+    utils::command::command()
+        .current_dir(temp_dir)
+        .arg("clean")
+        .arg("-f")
+        .assert()
+        .success();
+    assert!(!temp_dir.join("desktop.ini").exists());
+}
+
+#[test]
+fn clean_with_recursive() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    fs::create_dir_all(temp_dir.join("foo/.Trash-1000/files")).unwrap();
+    File::create(temp_dir.join("foo/.Trash-1000/files/bar.txt")).unwrap();
+    File::create(temp_dir.join("foo/._bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo/._bar.txt").exists());
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("-r")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been removed",
+            temp_dir.join("foo").join(".Trash-1000").display()
+        )));
+    assert!(!temp_dir.join("foo/.Trash-1000").exists());
+    assert!(!temp_dir.join("foo/._bar.txt").exists());
+}
+
+#[test]
+fn clean_with_recursive_keeps_directories_named_like_junk_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    fs::create_dir_all(temp_dir.join("._keep")).unwrap();
+    File::create(temp_dir.join("._keep/foo.txt")).unwrap();
+    File::create(temp_dir.join("._keep/.DS_Store")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("-r")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join("._keep/foo.txt").exists());
+    assert!(!temp_dir.join("._keep/.DS_Store").exists());
+}
+
+#[test]
+fn clean_with_pattern() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    File::create(temp_dir.join(".foo.txt.swp")).unwrap();
+    File::create(temp_dir.join(".DS_Store")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("--pattern")
+        .arg("*.swp")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(!temp_dir.join(".foo.txt.swp").exists());
+    assert!(!temp_dir.join(".DS_Store").exists());
+}
+
+#[test]
+fn clean_when_directory_does_not_exist() {
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("non_existent")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("could not read non_existent"));
+}
+
+#[test]
+fn clean_with_force_and_dry_run() {
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("-n")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--force' cannot be used with '--dry-run'",
+        ));
+}
+
+#[test]
+fn clean_with_progress() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    File::create(temp_dir.join(".DS_Store")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("--progress")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("removing ["));
+    assert!(!temp_dir.join(".DS_Store").exists());
+
+    File::create(temp_dir.join(".DS_Store")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("--progress")
+        .arg("--no-progress")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+    assert!(!temp_dir.join(".DS_Store").exists());
+}
+
+#[test]
+fn clean_with_interactive_when_stdin_is_not_terminal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    File::create(temp_dir.join(".DS_Store")).unwrap();
+
+    utils::command::command()
+        .arg("clean")
+        .arg("-i")
+        .arg(temp_dir)
+        .write_stdin("y")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "could not prompt because standard input is not a terminal",
+        ));
+    assert!(temp_dir.join(".DS_Store").exists());
+}
+
+#[test]
+fn clean_with_force_and_interactive() {
+    utils::command::command()
+        .arg("clean")
+        .arg("-f")
+        .arg("-i")
+        .arg("foo")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--force' cannot be used with '--interactive'",
+        ));
+}
+
+#[test]
+fn long_version_for_clean_command() {
+    utils::command::command()
+        .arg("clean")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_clean_command() {
+    utils::command::command()
+        .arg("clean")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/clean-after-long-help.md"
+        )));
+}
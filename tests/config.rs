@@ -0,0 +1,250 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use std::{fs, path::Path};
+
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+/// Returns the command which runs in `dir` with the user configuration
+/// directory in `config_dir`.
+fn command(dir: &Path, config_dir: &Path) -> Command {
+    let mut command = utils::command::command();
+    command
+        .current_dir(dir)
+        .env("XDG_CONFIG_HOME", config_dir)
+        .env("APPDATA", config_dir)
+        .env_remove("HF_LOG_LEVEL")
+        .env_remove("HF_JOBS")
+        .env_remove("HF_COMPANIONS");
+    command
+}
+
+#[test]
+fn config_show() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    command(temp_dir, temp_dir)
+        .arg("config")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout("[options]\nlog-level = \"INFO\" # default\njobs = 1 # default\ncompanions = [] # default\n");
+}
+
+#[test]
+fn config_show_with_layers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    let user_config = temp_dir.join("config/hf/config.toml");
+    let project_config = temp_dir.join("project/hf.toml");
+    fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+    fs::create_dir_all(temp_dir.join("project/foo")).unwrap();
+    fs::write(
+        &user_config,
+        "[options]\nlog-level = \"warn\"\njobs = 3\ncompanions = [\"{stem}.xmp\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        &project_config,
+        "[options]\njobs = 5\n\n[[rule]]\npath = \"foo\"\nstate = \"hidden\"\n",
+    )
+    .unwrap();
+
+    command(&temp_dir.join("project/foo"), &temp_dir.join("config"))
+        .arg("config")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout(format!(
+            "[options]\nlog-level = \"WARN\" # {}\njobs = 5 # {}\ncompanions = [\"{{stem}}.xmp\"] # {}\n",
+            user_config.display(),
+            project_config.display(),
+            user_config.display()
+        ));
+
+    command(&temp_dir.join("project/foo"), &temp_dir.join("config"))
+        .arg("config")
+        .arg("show")
+        .arg("--log-level")
+        .arg("debug")
+        .env("HF_JOBS", "8")
+        .env("HF_COMPANIONS", "")
+        .assert()
+        .success()
+        .stdout(
+            "[options]\nlog-level = \"DEBUG\" # command line\njobs = 8 # HF_JOBS\ncompanions = [] # \
+             HF_COMPANIONS\n",
+        );
+}
+
+#[test]
+fn config_with_invalid_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(temp_dir.join("hf.toml"), "[options]\nmechanism = \"dot\"\n").unwrap();
+
+    command(temp_dir, temp_dir)
+        .arg("config")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout("[options]\nlog-level = \"INFO\" # default\njobs = 1 # default\ncompanions = [] # default\n")
+        .stderr(predicate::str::contains(format!(
+            "Warning: could not parse {}",
+            temp_dir.join("hf.toml").display()
+        )))
+        .stderr(predicate::str::contains("unknown field `mechanism`"));
+}
+
+#[test]
+fn config_with_invalid_user_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    let user_config = temp_dir.join("config/hf/config.toml");
+    fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+    fs::create_dir(temp_dir.join("project")).unwrap();
+    fs::write(
+        &user_config,
+        "[options]
+jobs = 0
+",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.join("project/hf.toml"),
+        "[options]
+jobs = 2
+",
+    )
+    .unwrap();
+
+    command(&temp_dir.join("project"), &temp_dir.join("config"))
+        .arg("config")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "jobs = 2 # {}",
+            temp_dir.join("project/hf.toml").display()
+        )))
+        .stderr(predicate::str::contains(format!(
+            "could not parse {}",
+            user_config.display()
+        )));
+}
+
+#[test]
+fn config_with_invalid_env() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    command(temp_dir, temp_dir)
+        .arg("config")
+        .arg("show")
+        .env("HF_JOBS", "0")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("jobs = 1 # default"))
+        .stderr(predicate::str::contains(
+            "Warning: invalid value for HF_JOBS",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_invalid_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(temp_dir.join("hf.toml"), "[options]\nmechanism = \"dot\"\n").unwrap();
+    fs::write(temp_dir.join("foo.txt"), "").unwrap();
+
+    command(temp_dir, temp_dir)
+        .arg("hide")
+        .arg("-f")
+        .arg("foo.txt")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("unknown field `mechanism`"));
+    assert!(temp_dir.join(".foo.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_project_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(
+        temp_dir.join("hf.toml"),
+        "[options]\nlog-level = \"warn\"\ncompanions = [\"{stem}.xmp\"]\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.join("IMG_1.jpg"), "").unwrap();
+    fs::write(temp_dir.join("IMG_1.xmp"), "").unwrap();
+
+    command(temp_dir, temp_dir)
+        .arg("hide")
+        .arg("-f")
+        .arg("IMG_1.jpg")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+    assert!(temp_dir.join(".IMG_1.jpg").exists());
+    assert!(temp_dir.join(".IMG_1.xmp").exists());
+
+    command(temp_dir, temp_dir)
+        .arg("show")
+        .arg("-f")
+        .arg("--no-companions")
+        .arg("--log-level")
+        .arg("info")
+        .arg(".IMG_1.jpg")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("has been shown"));
+    assert!(temp_dir.join("IMG_1.jpg").exists());
+    assert!(temp_dir.join(".IMG_1.xmp").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn audit_with_options_in_manifest() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(
+        temp_dir.join("hf.toml"),
+        "[options]\nlog-level = \"warn\"\n\n[[rule]]\npath = \"foo.txt\"\nstate = \"hidden\"\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.join(".foo.txt"), "").unwrap();
+
+    command(temp_dir, temp_dir).arg("audit").assert().success();
+}
+
+#[test]
+fn long_version_for_config_command() {
+    utils::command::command()
+        .arg("config")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_config_command() {
+    utils::command::command()
+        .arg("config")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/config-after-long-help.md"
+        )));
+}
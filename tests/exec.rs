@@ -0,0 +1,237 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+#[cfg(unix)]
+use std::{
+    fs::File,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use predicates::prelude::predicate;
+
+#[cfg(unix)]
+#[test]
+fn exec() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join(".env")).unwrap();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+
+    utils::command::command()
+        .current_dir(temp_dir)
+        .arg("exec")
+        .arg("--show")
+        .arg(".env")
+        .arg("--hide")
+        .arg("foo.txt")
+        .arg("--")
+        .arg("ls")
+        .arg("-A")
+        .assert()
+        .success()
+        .stdout(".foo.txt\nenv\n")
+        .stderr(predicate::str::contains(".env has been renamed to env"))
+        .stderr(predicate::str::contains(
+            "foo.txt has been renamed to .foo.txt",
+        ))
+        .stderr(predicate::str::contains(".env has been restored"))
+        .stderr(predicate::str::contains("foo.txt has been restored"));
+    assert!(temp_dir.join(".env").exists());
+    assert!(temp_dir.join("foo.txt").exists());
+    assert!(!temp_dir.join("env").exists());
+    assert!(!temp_dir.join(".foo.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_propagates_exit_status() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join(".env")).unwrap();
+
+    utils::command::command()
+        .current_dir(temp_dir)
+        .arg("exec")
+        .arg("--show")
+        .arg(".env")
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("test -f env && exit 3")
+        .assert()
+        .failure()
+        .code(3);
+    assert!(temp_dir.join(".env").exists());
+    assert!(!temp_dir.join("env").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_when_command_is_killed() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join(".env")).unwrap();
+
+    utils::command::command()
+        .current_dir(temp_dir)
+        .arg("exec")
+        .arg("--show")
+        .arg(".env")
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("kill -KILL $$")
+        .assert()
+        .failure()
+        .code(137);
+    assert!(temp_dir.join(".env").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_when_terminated_by_signal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join(".env")).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hf"))
+        .current_dir(temp_dir)
+        .arg("exec")
+        .arg("--show")
+        .arg(".env")
+        .arg("--")
+        .arg("sleep")
+        .arg("10")
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // Wait until the command is running.
+    thread::sleep(Duration::from_secs(1));
+    assert!(temp_dir.join("env").exists());
+    let status = Command::new("kill")
+        .arg("-TERM")
+        .arg(child.id().to_string())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(143));
+    assert!(temp_dir.join(".env").exists());
+    assert!(!temp_dir.join("env").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_non_existent_command() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join(".env")).unwrap();
+
+    utils::command::command()
+        .current_dir(temp_dir)
+        .arg("exec")
+        .arg("--show")
+        .arg(".env")
+        .arg("--")
+        .arg("non_existent_command")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains(
+            "could not run non_existent_command",
+        ));
+    assert!(temp_dir.join(".env").exists());
+    assert!(!temp_dir.join("env").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_when_file_is_already_visible() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join("env")).unwrap();
+
+    utils::command::command()
+        .current_dir(temp_dir)
+        .arg("exec")
+        .arg("--show")
+        .arg("env")
+        .arg("--")
+        .arg("true")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("env is already visible"));
+    assert!(temp_dir.join("env").exists());
+}
+
+#[test]
+fn exec_non_existent_file() {
+    utils::command::command()
+        .arg("exec")
+        .arg("--hide")
+        .arg("non_existent.txt")
+        .arg("--")
+        .arg("true")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains("could not hide non_existent.txt"));
+}
+
+#[test]
+fn exec_without_files() {
+    utils::command::command()
+        .arg("exec")
+        .arg("--")
+        .arg("true")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn exec_without_command() {
+    utils::command::command()
+        .arg("exec")
+        .arg("--hide")
+        .arg("foo.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn long_version_for_exec_command() {
+    utils::command::command()
+        .arg("exec")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_exec_command() {
+    utils::command::command()
+        .arg("exec")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/exec-after-long-help.md"
+        )));
+}
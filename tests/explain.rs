@@ -0,0 +1,170 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use std::fs::File;
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+
+#[test]
+fn basic_explain() {
+    let temp_dir = tempfile::Builder::new().prefix("hf").tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    let file_path = temp_dir.join("foo.txt");
+
+    File::create(&file_path).unwrap();
+
+    utils::command::command()
+        .arg("explain")
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}: not hidden",
+            file_path.display()
+        )));
+
+    hf::hide(&file_path).unwrap();
+    #[cfg(unix)]
+    let file_path = temp_dir.join(".foo.txt");
+    utils::command::command()
+        .arg("explain")
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}: hidden",
+            file_path.display()
+        )))
+        .stdout(predicate::str::contains(if cfg!(unix) {
+            "  - the file name starts with `.`"
+        } else {
+            "  - the hidden file attribute is set"
+        }));
+}
+
+#[cfg(unix)]
+#[test]
+fn explain_with_hidden_ancestor() {
+    let temp_dir = tempfile::Builder::new().prefix("hf").tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    std::fs::create_dir(temp_dir.join(".foo")).unwrap();
+    File::create(temp_dir.join(".foo/bar.txt")).unwrap();
+    std::fs::write(temp_dir.join(".foo/.hidden"), "bar.txt\n").unwrap();
+
+    utils::command::command()
+        .arg("explain")
+        .arg(temp_dir.join(".foo/bar.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "  - the file name is listed in {}",
+            temp_dir.join(".foo/.hidden").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "  - the ancestor directory {} is hidden",
+            temp_dir.join(".foo").display()
+        )));
+}
+
+#[cfg(unix)]
+#[test]
+fn explain_with_effective() {
+    let temp_dir = tempfile::Builder::new().prefix("hf").tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    std::fs::create_dir_all(temp_dir.join(".foo/bar")).unwrap();
+    File::create(temp_dir.join(".foo/bar/baz.txt")).unwrap();
+    std::fs::write(temp_dir.join(".foo/bar/.hidden"), "baz.txt\n").unwrap();
+
+    utils::command::command()
+        .arg("explain")
+        .arg("--effective")
+        .arg(temp_dir.join(".foo/bar/baz.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "  - the ancestor directory {} is hidden",
+            temp_dir.join(".foo").display()
+        )))
+        .stdout(predicate::str::contains(".hidden").not());
+
+    utils::command::command()
+        .arg("explain")
+        .arg("-e")
+        .arg("--root")
+        .arg(temp_dir.join(".foo"))
+        .arg(temp_dir.join(".foo/bar/baz.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}: not hidden",
+            temp_dir.join(".foo/bar/baz.txt").display()
+        )));
+}
+
+#[test]
+fn explain_with_root_requires_effective() {
+    utils::command::command()
+        .arg("explain")
+        .arg("--root")
+        .arg(".")
+        .arg(".")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn explain_when_file_does_not_exist() {
+    utils::command::command()
+        .arg("explain")
+        .arg("non_existent.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not read information from non_existent.txt",
+        ));
+}
+
+#[test]
+fn explain_without_file() {
+    utils::command::command()
+        .arg("explain")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn long_version_for_explain_command() {
+    utils::command::command()
+        .arg("explain")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_explain_command() {
+    utils::command::command()
+        .arg("explain")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/explain-after-long-help.md"
+        )));
+}
@@ -183,6 +183,705 @@ fn hide_with_force_and_dry_run() {
         ));
 }
 
+#[cfg(unix)]
+#[test]
+fn hide_symlink() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+    std::os::unix::fs::symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+    std::os::unix::fs::symlink("qux.txt", temp_dir.join("baz.txt")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg(temp_dir.join("bar.txt"))
+        .arg(temp_dir.join("baz.txt"))
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo.txt").exists());
+    assert!(temp_dir.join(".bar.txt").is_symlink());
+    assert!(temp_dir.join(".baz.txt").is_symlink());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_dereference() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+    std::os::unix::fs::symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+    std::os::unix::fs::symlink("bar.txt", temp_dir.join("baz.txt")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--dereference")
+        .arg(temp_dir.join("baz.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been hidden",
+            temp_dir.join("baz.txt").display()
+        )));
+    assert!(temp_dir.join(".foo.txt").exists());
+    assert!(temp_dir.join("bar.txt").is_symlink());
+    assert!(temp_dir.join("baz.txt").is_symlink());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_dereference_when_link_is_dangling() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    std::os::unix::fs::symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("-L")
+        .arg(temp_dir.join("bar.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "{} does not exist",
+            temp_dir.join("bar.txt").display()
+        )));
+    assert!(temp_dir.join("bar.txt").is_symlink());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_no_dereference() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+    std::os::unix::fs::symlink("foo.txt", temp_dir.join("bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--dereference")
+        .arg("--no-dereference")
+        .arg(temp_dir.join("bar.txt"))
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo.txt").exists());
+    assert!(temp_dir.join(".bar.txt").is_symlink());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_fix_links() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = std::fs::canonicalize(temp_dir.path()).unwrap();
+    std::fs::create_dir(temp_dir.join("foo")).unwrap();
+    File::create(temp_dir.join("config")).unwrap();
+    std::os::unix::fs::symlink("../config", temp_dir.join("foo/relative")).unwrap();
+    std::os::unix::fs::symlink(temp_dir.join("config"), temp_dir.join("absolute")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-n")
+        .arg("--fix-links")
+        .arg(&temp_dir)
+        .arg(temp_dir.join("config"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} -> {}",
+            temp_dir.join("foo/relative").display(),
+            "../.config"
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} -> {}",
+            temp_dir.join("absolute").display(),
+            temp_dir.join(".config").display()
+        )));
+    assert!(temp_dir.join("config").exists());
+    assert_eq!(
+        std::fs::read_link(temp_dir.join("foo/relative")).unwrap(),
+        std::path::Path::new("../config")
+    );
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--fix-links")
+        .arg(&temp_dir)
+        .arg(temp_dir.join("config"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been hidden",
+            temp_dir.join("config").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} has been rewritten to point to ../.config",
+            temp_dir.join("foo/relative").display()
+        )));
+    assert!(temp_dir.join(".config").exists());
+    assert_eq!(
+        std::fs::read_link(temp_dir.join("foo/relative")).unwrap(),
+        std::path::Path::new("../.config")
+    );
+    assert_eq!(
+        std::fs::read_link(temp_dir.join("absolute")).unwrap(),
+        temp_dir.join(".config")
+    );
+    assert!(temp_dir.join("foo/relative").exists());
+    assert!(temp_dir.join("absolute").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_companions() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    for name in ["IMG_1.jpg", "._IMG_1.jpg", "IMG_1.xmp", "IMG_2.jpg"] {
+        File::create(temp_dir.join(name)).unwrap();
+    }
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-n")
+        .arg("--companion")
+        .arg("{stem}.xmp")
+        .arg(temp_dir.join("IMG_1.jpg"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            temp_dir.join("._IMG_1.jpg").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            temp_dir.join("IMG_1.xmp").display()
+        )));
+    assert!(temp_dir.join("._IMG_1.jpg").exists());
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--companion")
+        .arg("{stem}.xmp")
+        .arg(temp_dir.join("IMG_1.jpg"))
+        .arg(temp_dir.join("IMG_2.jpg"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been renamed to {}",
+            temp_dir.join("._IMG_1.jpg").display(),
+            temp_dir.join("._.IMG_1.jpg").display()
+        )));
+    for name in [".IMG_1.jpg", "._.IMG_1.jpg", ".IMG_1.xmp", ".IMG_2.jpg"] {
+        assert!(temp_dir.join(name).exists());
+    }
+}
+
+#[test]
+fn hide_with_scheme() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    for name in ["bashrc", "dot_profile", "foo.txt"] {
+        File::create(temp_dir.join(name)).unwrap();
+    }
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--scheme")
+        .arg("prefix:dot_")
+        .arg(temp_dir.join("bashrc"))
+        .arg(temp_dir.join("dot_profile"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been hidden",
+            temp_dir.join("bashrc").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} is already hidden",
+            temp_dir.join("dot_profile").display()
+        )));
+    assert!(temp_dir.join("dot_bashrc").exists());
+    assert!(temp_dir.join("dot_profile").exists());
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--scheme")
+        .arg("suffix:~")
+        .arg(temp_dir.join("foo.txt"))
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo.txt~").exists());
+}
+
+#[test]
+fn hide_with_invalid_scheme() {
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--scheme")
+        .arg("infix:_")
+        .arg("foo.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'infix:_' for '--scheme <SCHEME>'",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_no_companions() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+    File::create(temp_dir.join("._foo.txt")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--no-companions")
+        .arg(temp_dir.join("foo.txt"))
+        .assert()
+        .success();
+    assert!(temp_dir.join(".foo.txt").exists());
+    assert!(temp_dir.join("._foo.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_when_destination_exists() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    for name in ["foo.txt", "bar.txt", "._bar.txt", "._.bar.txt"] {
+        File::create(temp_dir.join(name)).unwrap();
+    }
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg(temp_dir.join("foo.txt"))
+        .arg(temp_dir.join("bar.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "{} already exists",
+            temp_dir.join("._.bar.txt").display()
+        )));
+    assert!(temp_dir.join("foo.txt").exists());
+    assert!(temp_dir.join("bar.txt").exists());
+    assert!(temp_dir.join("._bar.txt").exists());
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[test]
+fn hide_with_git() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    std::fs::create_dir(temp_dir.join("foo")).unwrap();
+    std::fs::write(temp_dir.join("foo/bar.txt"), "bar").unwrap();
+    std::fs::write(temp_dir.join("baz.txt"), "baz").unwrap();
+    File::create(temp_dir.join("qux.txt")).unwrap();
+    utils::git::init(temp_dir, &["baz.txt", "foo/bar.txt"]);
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg(temp_dir.join("foo"))
+        .arg(temp_dir.join("baz.txt"))
+        .arg(temp_dir.join("qux.txt"))
+        .assert()
+        .success();
+    assert!(temp_dir.join(".foo/bar.txt").exists());
+    assert!(temp_dir.join(".baz.txt").exists());
+    assert!(temp_dir.join(".qux.txt").exists());
+    assert_eq!(
+        utils::git::index_paths(temp_dir),
+        [".baz.txt", ".foo/bar.txt"]
+    );
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[test]
+fn hide_with_git_when_index_could_not_be_written() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    std::fs::write(temp_dir.join("foo.txt"), "foo").unwrap();
+    std::fs::write(temp_dir.join("bar.txt"), "bar").unwrap();
+    utils::git::init(temp_dir, &["foo.txt", "bar.txt"]);
+    File::create(temp_dir.join(".git/index.lock")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg(temp_dir.join("foo.txt"))
+        .arg(temp_dir.join("bar.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not update the Git index, so the changes have been rolled back",
+        ));
+    assert!(temp_dir.join("foo.txt").exists());
+    assert!(temp_dir.join("bar.txt").exists());
+    assert!(!temp_dir.join(".foo.txt").exists());
+    assert!(!temp_dir.join(".bar.txt").exists());
+    assert_eq!(utils::git::index_paths(temp_dir), ["bar.txt", "foo.txt"]);
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[test]
+fn hide_with_git_when_second_index_could_not_be_written() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    for repo in ["foo", "bar"] {
+        std::fs::create_dir(temp_dir.join(repo)).unwrap();
+        std::fs::write(temp_dir.join(repo).join("baz.txt"), "baz").unwrap();
+        utils::git::init(&temp_dir.join(repo), &["baz.txt"]);
+    }
+    File::create(temp_dir.join("bar/.git/index.lock")).unwrap();
+    let index = std::fs::read(temp_dir.join("foo/.git/index")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg(temp_dir.join("foo/baz.txt"))
+        .arg(temp_dir.join("bar/baz.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not update the Git index, so the changes have been rolled back",
+        ));
+    assert!(temp_dir.join("foo/baz.txt").exists());
+    assert!(temp_dir.join("bar/baz.txt").exists());
+    assert_eq!(
+        std::fs::read(temp_dir.join("foo/.git/index")).unwrap(),
+        index
+    );
+    assert_eq!(utils::git::index_paths(&temp_dir.join("foo")), ["baz.txt"]);
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[test]
+fn hide_with_git_ignore_when_gitignore_could_not_be_written() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    for repo in ["foo", "bar"] {
+        std::fs::create_dir(temp_dir.join(repo)).unwrap();
+        std::fs::write(temp_dir.join(repo).join("baz.txt"), "baz").unwrap();
+        utils::git::init(&temp_dir.join(repo), &["baz.txt"]);
+    }
+    // The `.gitignore` file can be read, but cannot be created.
+    std::os::unix::fs::symlink("qux/.gitignore", temp_dir.join("bar/.gitignore")).unwrap();
+    let index = std::fs::read(temp_dir.join("foo/.git/index")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg("--git-ignore")
+        .arg(temp_dir.join("foo/baz.txt"))
+        .arg(temp_dir.join("bar/baz.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not update the Git index, so the changes have been rolled back",
+        ));
+    assert!(temp_dir.join("foo/baz.txt").exists());
+    assert!(temp_dir.join("bar/baz.txt").exists());
+    assert!(!temp_dir.join("foo/.gitignore").exists());
+    assert!(temp_dir.join("bar/.gitignore").is_symlink());
+    assert_eq!(
+        std::fs::read(temp_dir.join("foo/.git/index")).unwrap(),
+        index
+    );
+    for repo in ["foo", "bar"] {
+        assert_eq!(utils::git::index_paths(&temp_dir.join(repo)), ["baz.txt"]);
+    }
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[test]
+fn hide_with_git_when_file_is_dirty() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    std::fs::write(temp_dir.join("foo.txt"), "foo").unwrap();
+    std::fs::write(temp_dir.join("bar.txt"), "bar").unwrap();
+    utils::git::init(temp_dir, &["foo.txt", "bar.txt"]);
+    std::fs::write(temp_dir.join("bar.txt"), "modified").unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg(temp_dir.join("foo.txt"))
+        .arg(temp_dir.join("bar.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "{} has uncommitted changes",
+            temp_dir.join("bar.txt").display()
+        )));
+    assert!(temp_dir.join("foo.txt").exists());
+    assert!(temp_dir.join("bar.txt").exists());
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg("--allow-dirty")
+        .arg(temp_dir.join("foo.txt"))
+        .arg(temp_dir.join("bar.txt"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has uncommitted changes",
+            temp_dir.join("bar.txt").display()
+        )));
+    assert_eq!(utils::git::index_paths(temp_dir), [".bar.txt", ".foo.txt"]);
+}
+
+#[cfg(all(unix, feature = "git"))]
+#[test]
+fn hide_with_git_ignore() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    std::fs::write(temp_dir.join("foo.txt"), "foo").unwrap();
+    std::fs::write(temp_dir.join("bar.txt"), "bar").unwrap();
+    utils::git::init(temp_dir, &["foo.txt", "bar.txt"]);
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git")
+        .arg("--git-ignore")
+        .arg(temp_dir.join("foo.txt"))
+        .assert()
+        .success();
+    assert!(temp_dir.join(".foo.txt").exists());
+    assert_eq!(utils::git::index_paths(temp_dir), ["bar.txt"]);
+    assert_eq!(
+        std::fs::read_to_string(temp_dir.join(".gitignore")).unwrap(),
+        "/.foo.txt\n"
+    );
+}
+
+#[cfg(feature = "git")]
+#[test]
+fn hide_with_git_ignore_requires_git() {
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--git-ignore")
+        .arg("data/data.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ))
+        .stderr(predicate::str::contains("--git"));
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn hide_with_tar() {
+    let mut builder = tar::Builder::new(Vec::new());
+    for path in ["foo/bar.txt", ".bar.txt"] {
+        let mut header = tar::Header::new_ustar();
+        header.set_size(3);
+        builder
+            .append_data(&mut header, path, b"bar".as_slice())
+            .unwrap();
+    }
+    let archive = builder.into_inner().unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-n")
+        .arg("--tar")
+        .arg("foo")
+        .arg(".bar.txt")
+        .write_stdin(archive.as_slice())
+        .assert()
+        .success()
+        .stdout("foo\n")
+        .stderr(predicate::str::contains(".bar.txt is ignored"));
+
+    let output = utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--tar")
+        .arg("foo")
+        .arg(".bar.txt")
+        .write_stdin(archive)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("foo has been hidden"))
+        .stderr(predicate::str::contains(".bar.txt is already hidden"))
+        .get_output()
+        .stdout
+        .clone();
+    let paths = tar::Archive::new(output.as_slice())
+        .entries()
+        .unwrap()
+        .map(|e| e.unwrap().path().unwrap().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            std::path::Path::new(".foo/bar.txt"),
+            std::path::Path::new(".bar.txt")
+        ]
+    );
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn hide_with_tar_when_member_does_not_exist() {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_ustar();
+    header.set_size(3);
+    builder
+        .append_data(&mut header, ".bar.txt", b"bar".as_slice())
+        .unwrap();
+    let archive = builder.into_inner().unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--tar")
+        .arg("foo")
+        .write_stdin(archive.as_slice())
+        .assert()
+        .success()
+        .stdout(archive)
+        .stderr(predicate::str::contains(
+            "foo does not exist in the archive",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn hide_with_jobs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    let mut files = Vec::new();
+    for i in 0..4 {
+        let dir = temp_dir.join(format!("dir{i}"));
+        std::fs::create_dir(&dir).unwrap();
+        for j in 0..8 {
+            File::create(dir.join(format!("{j}.txt"))).unwrap();
+            files.push(dir.join(format!("{j}.txt")));
+        }
+    }
+    let output = utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("-j")
+        .arg("4")
+        .args(&files)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), files.len());
+    for (line, file) in lines.into_iter().zip(&files) {
+        assert!(line.ends_with(&format!("{} has been hidden", file.display())));
+    }
+
+    for i in 0..4 {
+        for j in 0..8 {
+            assert!(temp_dir.join(format!("dir{i}/.{j}.txt")).exists());
+        }
+    }
+}
+
+#[test]
+fn hide_with_invalid_jobs() {
+    utils::command::command()
+        .arg("hide")
+        .arg("-j")
+        .arg("0")
+        .arg("foo.txt")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value '0' for '--jobs <N>'",
+        ));
+}
+
+#[test]
+fn hide_with_progress() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("foo.txt");
+
+    File::create(&file_path).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-n")
+        .arg("--no-progress")
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--progress")
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("hiding ["))
+        .stderr(predicate::str::contains("/1 files"));
+}
+
+#[test]
+fn hide_without_progress_when_stderr_is_not_terminal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("foo.txt");
+
+    File::create(&file_path).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn hide_with_progress_and_tar() {
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("--tar")
+        .arg("--progress")
+        .arg("foo")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--tar' cannot be used with '--progress'",
+        ));
+}
+
 #[test]
 fn hide_with_off_log_level() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -382,6 +1081,57 @@ fn hide_with_invalid_log_level() {
         ));
 }
 
+#[test]
+fn hide_with_interactive_when_stdin_is_not_terminal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    File::create(temp_dir.join("foo.txt")).unwrap();
+
+    utils::command::command()
+        .arg("hide")
+        .arg("-i")
+        .arg(temp_dir.join("foo.txt"))
+        .write_stdin("y")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "could not prompt because standard input is not a terminal",
+        ));
+    assert!(temp_dir.join("foo.txt").exists());
+}
+
+#[test]
+fn hide_with_force_and_interactive() {
+    utils::command::command()
+        .arg("hide")
+        .arg("-f")
+        .arg("-i")
+        .arg("foo")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--force' cannot be used with '--interactive'",
+        ));
+}
+
+#[test]
+fn hide_with_interactive_and_tar() {
+    utils::command::command()
+        .arg("hide")
+        .arg("-i")
+        .arg("--tar")
+        .arg("foo")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--interactive' cannot be used with '--tar'",
+        ));
+}
+
 #[test]
 fn long_version_for_hide_command() {
     utils::command::command()
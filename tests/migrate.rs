@@ -0,0 +1,241 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use std::fs::{self, File};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+
+#[test]
+fn basic_migrate() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join(".foo.txt")).unwrap();
+    File::create(temp_dir.join("bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("migrate")
+        .arg("-n")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("hidden-list")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            temp_dir.join(".foo.txt").display()
+        )))
+        .stdout(predicate::str::contains("bar.txt").not());
+    assert!(temp_dir.join(".foo.txt").exists());
+
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("hidden-list")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been renamed to {}",
+            temp_dir.join(".foo.txt").display(),
+            temp_dir.join("foo.txt").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} has been hidden with hidden-list",
+            temp_dir.join("foo.txt").display()
+        )));
+    assert!(temp_dir.join("foo.txt").exists());
+    assert_eq!(
+        fs::read_to_string(temp_dir.join(".hidden")).unwrap(),
+        "foo.txt\n"
+    );
+
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("--from")
+        .arg("hidden-list")
+        .arg("--to")
+        .arg("dot")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join(".foo.txt").exists());
+    assert!(temp_dir.join("bar.txt").exists());
+    assert!(!temp_dir.join(".hidden").exists());
+}
+
+#[test]
+fn migrate_with_recursive() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::create_dir(temp_dir.join(".foo")).unwrap();
+    File::create(temp_dir.join(".foo/.bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("hidden-list")
+        .arg("-r")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo/bar.txt").exists());
+    assert_eq!(
+        fs::read_to_string(temp_dir.join("foo/.hidden")).unwrap(),
+        "bar.txt\n"
+    );
+}
+
+#[test]
+fn migrate_when_destination_exists() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    for name in [".foo.txt", "foo.txt"] {
+        File::create(temp_dir.join(name)).unwrap();
+    }
+
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("hidden-list")
+        .arg(temp_dir)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "{} already exists",
+            temp_dir.join("foo.txt").display()
+        )));
+    assert!(temp_dir.join(".foo.txt").exists());
+    assert!(!temp_dir.join(".hidden").exists());
+}
+
+#[test]
+fn migrate_with_same_mechanisms() {
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("dot")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--from and --to must be different",
+        ));
+}
+
+#[test]
+fn migrate_without_mechanisms() {
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("--to")
+        .arg("samba")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn migrate_with_force_and_dry_run() {
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("-n")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("samba")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--force' cannot be used with '--dry-run'",
+        ));
+}
+
+#[test]
+fn migrate_with_interactive_when_stdin_is_not_terminal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    File::create(temp_dir.join(".foo.txt")).unwrap();
+
+    utils::command::command()
+        .arg("migrate")
+        .arg("-i")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("samba")
+        .arg(temp_dir)
+        .write_stdin("y")
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "could not prompt because standard input is not a terminal",
+        ));
+    assert!(temp_dir.join(".foo.txt").exists());
+}
+
+#[test]
+fn migrate_with_force_and_interactive() {
+    utils::command::command()
+        .arg("migrate")
+        .arg("-f")
+        .arg("-i")
+        .arg("--from")
+        .arg("dot")
+        .arg("--to")
+        .arg("samba")
+        .arg("foo")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--force' cannot be used with '--interactive'",
+        ));
+}
+
+#[test]
+fn long_version_for_migrate_command() {
+    utils::command::command()
+        .arg("migrate")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_migrate_command() {
+    utils::command::command()
+        .arg("migrate")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/migrate-after-long-help.md"
+        )));
+}
@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use std::fs::{self, File};
+
+use predicates::prelude::{PredicateBooleanExt, predicate};
+
+const MANIFEST: &str = r#"
+[[rule]]
+glob = "**/*"
+state = "hidden"
+
+[[rule]]
+path = "build/README.md"
+state = "visible"
+"#;
+
+#[cfg(unix)]
+#[test]
+fn basic_sync() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(temp_dir.join("hf.toml"), MANIFEST).unwrap();
+    fs::create_dir(temp_dir.join("build")).unwrap();
+    File::create(temp_dir.join("build/.README.md")).unwrap();
+    File::create(temp_dir.join("build/foo.txt")).unwrap();
+
+    utils::command::command()
+        .arg("sync")
+        .arg("-n")
+        .arg("-m")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            temp_dir.join("build/foo.txt").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}",
+            temp_dir.join("build/.README.md").display()
+        )))
+        .stdout(predicate::str::contains("hf.toml").not());
+    assert!(temp_dir.join("build/foo.txt").exists());
+
+    utils::command::command()
+        .arg("sync")
+        .arg("-f")
+        .arg("-m")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been hidden",
+            temp_dir.join("build").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} has been shown",
+            temp_dir.join("build/.README.md").display()
+        )));
+    assert!(temp_dir.join(".build/README.md").exists());
+    assert!(temp_dir.join(".build/.foo.txt").exists());
+    assert!(temp_dir.join("hf.toml").exists());
+
+    utils::command::command()
+        .arg("audit")
+        .arg("-m")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .success();
+}
+
+#[cfg(unix)]
+#[test]
+fn sync_when_destination_exists() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::write(
+        temp_dir.join("hf.toml"),
+        "[[rule]]\nglob = \"*.txt\"\nstate = \"hidden\"\n",
+    )
+    .unwrap();
+    for name in ["foo.txt", "bar.txt", ".bar.txt"] {
+        File::create(temp_dir.join(name)).unwrap();
+    }
+
+    utils::command::command()
+        .arg("sync")
+        .arg("-f")
+        .arg("-m")
+        .arg(temp_dir.join("hf.toml"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "{} already exists",
+            temp_dir.join(".bar.txt").display()
+        )));
+    assert!(temp_dir.join("foo.txt").exists());
+}
+
+#[test]
+fn sync_with_force_and_dry_run() {
+    utils::command::command()
+        .arg("sync")
+        .arg("-f")
+        .arg("-n")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the argument '--force' cannot be used with '--dry-run'",
+        ));
+}
+
+#[test]
+fn long_version_for_sync_command() {
+    utils::command::command()
+        .arg("sync")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_sync_command() {
+    utils::command::command()
+        .arg("sync")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/sync-after-long-help.md"
+        )));
+}
@@ -0,0 +1,46 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+use predicates::prelude::predicate;
+
+#[test]
+fn tui_when_stdout_is_not_terminal() {
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    utils::command::command()
+        .arg("tui")
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "could not start the terminal UI because standard output is not a terminal",
+        ));
+}
+
+#[test]
+fn long_version_for_tui_command() {
+    utils::command::command()
+        .arg("tui")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_tui_command() {
+    utils::command::command()
+        .arg("tui")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/tui-after-long-help.md"
+        )));
+}
@@ -0,0 +1,40 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::{fs, path::Path};
+
+use gix::index::{
+    self,
+    entry::{Flags, Mode, Stat},
+};
+
+/// Initializes a Git repository in `dir` and adds `files` to the index.
+#[allow(dead_code)]
+pub fn init(dir: &Path, files: &[&str]) {
+    let repo = gix::init(dir).unwrap();
+    let mut index =
+        index::File::from_state(index::State::new(repo.object_hash()), repo.index_path());
+    for file in files {
+        let path = dir.join(file);
+        let contents = fs::read(&path).unwrap();
+        let id =
+            gix::objs::compute_hash(repo.object_hash(), gix::objs::Kind::Blob, &contents).unwrap();
+        let metadata = index::fs::Metadata::from_path_no_follow(&path).unwrap();
+        let stat = Stat::from_fs(&metadata).unwrap();
+        index.dangerously_push_entry(stat, id, Flags::empty(), Mode::FILE, (*file).into());
+    }
+    index.sort_entries();
+    index.write(index::write::Options::default()).unwrap();
+}
+
+/// Returns the paths in the index of the Git repository in `dir`.
+#[allow(dead_code)]
+pub fn index_paths(dir: &Path) -> Vec<String> {
+    let index = gix::open(dir).unwrap().open_index().unwrap();
+    index
+        .entries()
+        .iter()
+        .map(|e| e.path(&index).to_string())
+        .collect()
+}
@@ -3,3 +3,5 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 pub mod command;
+#[cfg(feature = "git")]
+pub mod git;
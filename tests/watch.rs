@@ -0,0 +1,209 @@
+// SPDX-FileCopyrightText: 2025 Shun Sakai
+//
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+mod utils;
+
+#[cfg(unix)]
+use std::{
+    fs::{self, File},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use predicates::prelude::predicate;
+
+#[cfg(unix)]
+#[test]
+fn watch_once() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::create_dir(temp_dir.join("snap")).unwrap();
+    File::create(temp_dir.join(".go")).unwrap();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg("--rule")
+        .arg("snap=hidden")
+        .arg("--rule")
+        .arg("go=visible")
+        .arg(temp_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "{} has been hidden",
+            temp_dir.join("snap").display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{} has been shown",
+            temp_dir.join(".go").display()
+        )));
+    assert!(temp_dir.join(".snap").is_dir());
+    assert!(temp_dir.join("go").exists());
+    assert!(temp_dir.join("foo.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_once_when_last_rule_wins() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    File::create(temp_dir.join("foo.txt")).unwrap();
+    File::create(temp_dir.join("bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg("--rule")
+        .arg("*.txt=hidden")
+        .arg("--rule")
+        .arg("bar.txt=visible")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join(".foo.txt").exists());
+    assert!(temp_dir.join("bar.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_once_without_recursive() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::create_dir(temp_dir.join("foo")).unwrap();
+    File::create(temp_dir.join("foo/snap")).unwrap();
+
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg("--rule")
+        .arg("**/snap=hidden")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo/snap").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_once_recursively() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+    fs::create_dir_all(temp_dir.join("foo/snap")).unwrap();
+    File::create(temp_dir.join("foo/snap/bar.txt")).unwrap();
+
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg("-r")
+        .arg("--rule")
+        .arg("**/snap=hidden")
+        .arg(temp_dir)
+        .assert()
+        .success();
+    assert!(temp_dir.join("foo/.snap/bar.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_when_entry_is_created() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_dir = temp_dir.path();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hf"))
+        .arg("watch")
+        .arg("--rule")
+        .arg("snap=hidden")
+        .arg("--debounce")
+        .arg("100")
+        .arg(temp_dir)
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+    // Wait until the watcher is ready.
+    thread::sleep(Duration::from_secs(1));
+    fs::create_dir(temp_dir.join("snap")).unwrap();
+
+    let start = Instant::now();
+    while !temp_dir.join(".snap").exists() && start.elapsed() < Duration::from_secs(10) {
+        thread::sleep(Duration::from_millis(100));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(temp_dir.join(".snap").is_dir());
+    assert!(!temp_dir.join("snap").exists());
+}
+
+#[test]
+fn watch_with_invalid_rule() {
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg("--rule")
+        .arg("snap=gone")
+        .arg(".")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "invalid value 'snap=gone' for '--rule <PATTERN=STATE>'",
+        ))
+        .stderr(predicate::str::contains(
+            "the state must be `hidden` or `visible`",
+        ));
+}
+
+#[test]
+fn watch_without_rule() {
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg(".")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "the following required arguments were not provided",
+        ));
+}
+
+#[test]
+fn watch_non_existent_directory() {
+    utils::command::command()
+        .arg("watch")
+        .arg("--once")
+        .arg("--rule")
+        .arg("snap=hidden")
+        .arg("non_existent")
+        .assert()
+        .failure()
+        .code(66)
+        .stderr(predicate::str::contains("could not open non_existent"));
+}
+
+#[test]
+fn long_version_for_watch_command() {
+    utils::command::command()
+        .arg("watch")
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/long-version.md"
+        )));
+}
+
+#[test]
+fn after_long_help_for_watch_command() {
+    utils::command::command()
+        .arg("watch")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(include_str!(
+            "assets/watch-after-long-help.md"
+        )));
+}